@@ -0,0 +1,608 @@
+//! `swivel` command line.
+//!
+//! ```text
+//! swivel backends                            list the backends compiled in
+//! swivel notion get <page> [--complete]      fetch a Notion page as JSON;
+//!                                            `--complete` fetches values
+//!                                            over 25 items in full
+//! swivel notion put <json | ->               create a page (no id) or update
+//!                                            the fields given
+//! swivel notion archive <page>               move a page to the trash
+//! swivel notion restore <page>               take it back out
+//! swivel notion search [text] [--pages | --data-sources] [--newest | --oldest]
+//!                                            find pages and data sources by
+//!                                            title, one per line: object, id,
+//!                                            title, URL, parent
+//! swivel notion schema apply <file> [--yes]  print what it takes to make a
+//!                                            data source match a TOML or
+//!                                            JSON schema; `--yes` does it
+//! swivel export md <page> [--out <file>] [--assets <dir>] [--no-front-matter]
+//!                                            render a Notion page as Markdown
+//! swivel import md <file> --parent <id>      create a Notion page from Markdown
+//!                                            under a page or data source
+//! swivel codegen notion <id> [--name <Struct>] [--out <file>]
+//!                                            generate a Rust module from a
+//!                                            data source schema
+//! swivel supabase get <table> <id>           fetch a row (uses SUPABASE_URL
+//!                                            and SUPABASE_KEY)
+//! swivel supabase put <table> <json>         upsert a row and print it as
+//!                                            stored
+//! swivel supabase rpc <fn> [--arg k=v]... [--get]
+//!                                            call a Postgres function and
+//!                                            print its result; values are
+//!                                            JSON where they parse as JSON
+//! swivel supabase login <email> [--otp]      sign in with the password on
+//!                                            stdin, or email a one-time code
+//! swivel supabase verify <email> <code>      sign in with that code
+//! swivel supabase logout                     sign out everywhere
+//! swivel postgres get <table> <id>           fetch a row (uses DATABASE_URL)
+//! swivel postgres put <table> <json>         upsert a row
+//! swivel sqlite get <db-file> <table> <id>   fetch a row
+//! swivel sqlite put <db-file> <table> <json> upsert a row
+//! ```
+//!
+//! `<page>` and `<id>` take a page or data source id in any UUID format, a
+//! `notion.so` or `notion.site` URL, or a `collection://` reference; anything
+//! else is a usage error, caught before any request. A database id stands for
+//! its data source, `NOTION_VERSION` picks the API version and
+//! `NOTION_BASE_URL` the API root.
+//!
+//! Once signed in, `supabase` commands run as that user, so row-level
+//! security applies. The session is kept in `SUPABASE_SESSION_FILE`, by
+//! default `~/.config/swivel/supabase-session.json`, and refreshed as needed.
+//!
+//! Exit codes, so scripts can branch on the kind of failure:
+//!
+//! | code | meaning                                      |
+//! |------|----------------------------------------------|
+//! | 0    | success                                      |
+//! | 1    | any other error                              |
+//! | 2    | bad command line usage, or backend not compiled in |
+//! | 3    | missing configuration (e.g. `NOTION_API_KEY`) |
+//! | 4    | unauthorized                                 |
+//! | 5    | restricted resource, permission denied       |
+//! | 6    | object not found                             |
+//! | 7    | validation error                             |
+//! | 8    | rate limited                                 |
+//! | 9    | conflict, unique or foreign key violation    |
+//! | 10   | server error                                 |
+//! | 11   | transport (network) error                    |
+//! | 12   | database driver error                        |
+//! | 13   | local file error                             |
+
+use anyhow::Result;
+use std::env;
+use std::fmt;
+use std::process::ExitCode;
+
+const USAGE: &str = "usage: swivel backends
+       swivel <notion | supabase | postgres | sqlite> <get | put> [args...]
+       swivel notion <archive | restore> <page>
+       swivel notion search [text] [options]
+       swivel notion schema apply <file> [--yes]
+       swivel supabase rpc <fn> [--arg k=v]... [--get]
+       swivel supabase <login | verify | logout> [args...]
+       swivel export md <page> [options]
+       swivel import md <file> --parent <id>
+       swivel codegen notion <id> [options]";
+
+/// Every backend the CLI knows about, compiled in or not.
+const KNOWN_BACKENDS: &[&str] = &["notion", "supabase", "postgres", "sqlite"];
+
+/// A command line the CLI cannot act on; exits with code 2.
+#[derive(Debug)]
+struct Usage(String);
+
+impl fmt::Display for Usage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Usage {}
+
+fn usage(message: impl Into<String>) -> anyhow::Error {
+    Usage(message.into()).into()
+}
+
+fn run(args: &[String]) -> Result<()> {
+    let Some(backend) = args.first().map(String::as_str) else {
+        return Err(usage(USAGE));
+    };
+    match backend {
+        "backends" => {
+            for name in swivel::BACKENDS {
+                println!("{name}");
+            }
+            Ok(())
+        }
+        #[cfg(feature = "notion")]
+        "notion" => notion(&args[1..]),
+        #[cfg(feature = "notion")]
+        "export" => export(&args[1..]),
+        #[cfg(feature = "notion")]
+        "import" => import(&args[1..]),
+        #[cfg(feature = "notion")]
+        "codegen" => codegen(&args[1..]),
+        #[cfg(not(feature = "notion"))]
+        name @ ("export" | "import" | "codegen") => Err(usage(format!(
+            "{name} needs the notion backend; rebuild with `--features notion`"
+        ))),
+        #[cfg(feature = "postgres")]
+        "postgres" => postgres(&args[1..]),
+        #[cfg(feature = "sqlite")]
+        "sqlite" => sqlite(&args[1..]),
+        #[cfg(feature = "supabase")]
+        "supabase" => supabase(&args[1..]),
+        name if KNOWN_BACKENDS.contains(&name) => Err(usage(format!(
+            "the `{name}` backend is not compiled into this build (compiled: {}); \
+             rebuild with `--features {name}`",
+            compiled()
+        ))),
+        name => Err(usage(format!("unknown backend `{name}`\n{USAGE}"))),
+    }
+}
+
+fn compiled() -> String {
+    if swivel::BACKENDS.is_empty() {
+        "none".to_string()
+    } else {
+        swivel::BACKENDS.join(", ")
+    }
+}
+
+#[cfg(feature = "notion")]
+fn notion(args: &[String]) -> Result<()> {
+    use anyhow::Context;
+    use std::io::Read;
+    use swivel::notion::{
+        Direction, NotionClient, PageInput, PageOptions, Parent, SchemaSpec, SearchObject,
+    };
+
+    const NOTION_USAGE: &str = "usage: swivel notion get <page> [--complete]\n       swivel notion put <json | ->\n       swivel notion archive <page>\n       swivel notion restore <page>\n       swivel notion search [text] [--pages | --data-sources] [--newest | --oldest]\n       swivel notion schema apply <file.toml | file.json> [--yes]";
+
+    match (args.first().map(String::as_str), args.get(1)) {
+        (Some("get"), Some(page)) => {
+            let page_id = notion_id(page)?;
+            let mut options = PageOptions::default();
+            for flag in &args[2..] {
+                match flag.as_str() {
+                    "--complete" => options = options.with_fetch_complete(true),
+                    other => {
+                        return Err(usage(format!("unknown option `{other}`\n{NOTION_USAGE}")))
+                    }
+                }
+            }
+            let notion = NotionClient::from_env()?;
+            let page = notion
+                .get_page_with(page_id, &options)
+                .context("Notion API call failed")?;
+            println!("{}", serde_json::to_string_pretty(&page)?);
+            Ok(())
+        }
+        (Some("put"), Some(json)) => {
+            let json = if json == "-" {
+                let mut stdin = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut stdin)
+                    .map_err(swivel::Error::from)?;
+                stdin
+            } else {
+                json.clone()
+            };
+            let input: PageInput = serde_json::from_str(&json)
+                .map_err(|e| usage(format!("invalid page JSON: {e}")))?;
+            let notion = NotionClient::from_env()?;
+            match input {
+                PageInput::Create(page) => notion.create_page(&page)?,
+                PageInput::Update { id, update } => notion.update_page(id.as_str(), &update)?,
+            };
+            Ok(())
+        }
+        (Some("archive"), Some(page)) => {
+            let page_id = notion_id(page)?;
+            NotionClient::from_env()?.archive_page(page_id)?;
+            Ok(())
+        }
+        (Some("restore"), Some(page)) => {
+            let page_id = notion_id(page)?;
+            NotionClient::from_env()?.restore_page(page_id)?;
+            Ok(())
+        }
+        (Some("search"), _) => {
+            let (mut text, mut object, mut direction) = ("", None, None);
+            for arg in &args[1..] {
+                match arg.as_str() {
+                    "--pages" => object = Some(SearchObject::Page),
+                    "--data-sources" => object = Some(SearchObject::DataSource),
+                    "--newest" => direction = Some(Direction::Descending),
+                    "--oldest" => direction = Some(Direction::Ascending),
+                    flag if flag.starts_with("--") => {
+                        return Err(usage(format!("unknown option `{flag}`\n{NOTION_USAGE}")))
+                    }
+                    arg => text = arg,
+                }
+            }
+            let notion = NotionClient::from_env()?;
+            let mut search = notion.search(text);
+            if let Some(object) = object {
+                search = search.object(object);
+            }
+            if let Some(direction) = direction {
+                search = search.sort(direction);
+            }
+            for hit in search {
+                let hit = hit?;
+                let parent = match hit.parent() {
+                    Some(Parent::DataSource { data_source_id, .. }) => {
+                        format!("data_source:{data_source_id}")
+                    }
+                    Some(Parent::Database { database_id }) => format!("database:{database_id}"),
+                    Some(Parent::Page { page_id }) => format!("page:{page_id}"),
+                    Some(Parent::Block { block_id }) => format!("block:{block_id}"),
+                    Some(Parent::Workspace) => "workspace".to_string(),
+                    Some(Parent::Unknown(_)) | None => "-".to_string(),
+                };
+                println!(
+                    "{}\t{}\t{}\t{}\t{parent}",
+                    hit.object(),
+                    hit.id(),
+                    hit.title(),
+                    hit.url().unwrap_or("-")
+                );
+            }
+            Ok(())
+        }
+        (Some("schema"), Some(sub)) if sub == "apply" => {
+            let (mut file, mut yes) = (None, false);
+            for arg in &args[2..] {
+                match arg.as_str() {
+                    "--yes" | "-y" => yes = true,
+                    flag if flag.starts_with('-') => {
+                        return Err(usage(format!("unknown option `{flag}`\n{NOTION_USAGE}")))
+                    }
+                    arg => file = Some(arg),
+                }
+            }
+            let file = file.ok_or_else(|| usage(NOTION_USAGE))?;
+            let source = std::fs::read_to_string(file).map_err(swivel::Error::from)?;
+            let spec = if file.ends_with(".json") {
+                SchemaSpec::from_json(&source)?
+            } else {
+                SchemaSpec::from_toml(&source)?
+            };
+
+            let notion = NotionClient::from_env()?;
+            let plan = notion.plan_schema(&spec)?;
+            print!("{plan}");
+            if plan.is_empty() {
+                return Ok(());
+            }
+            if !yes {
+                eprintln!("dry run: pass --yes to apply these changes");
+                return Ok(());
+            }
+            let data_source = notion.apply_schema(&plan)?;
+            println!("{}", data_source.url.unwrap_or(data_source.id));
+            Ok(())
+        }
+        _ => Err(usage(NOTION_USAGE)),
+    }
+}
+
+/// Parse a page or data source argument before anything is sent.
+#[cfg(feature = "notion")]
+fn notion_id(arg: &str) -> Result<swivel::notion::NotionId> {
+    swivel::notion::NotionId::parse(arg).map_err(|err| usage(err.to_string()))
+}
+
+#[cfg(feature = "notion")]
+fn export(args: &[String]) -> Result<()> {
+    use swivel::notion::{MarkdownOptions, NotionClient};
+
+    const EXPORT_USAGE: &str =
+        "usage: swivel export md <page> [--out <file>] [--assets <dir>] [--no-front-matter]";
+
+    let (Some("md"), Some(page)) = (args.first().map(String::as_str), args.get(1)) else {
+        return Err(usage(EXPORT_USAGE));
+    };
+    let page_id = notion_id(page)?;
+    let mut options = MarkdownOptions::default();
+    let mut out = None;
+    let mut flags = args[2..].iter();
+    while let Some(flag) = flags.next() {
+        match flag.as_str() {
+            "--out" | "-o" => out = Some(flags.next().ok_or_else(|| usage(EXPORT_USAGE))?),
+            "--assets" => {
+                options = options.with_assets_dir(flags.next().ok_or_else(|| usage(EXPORT_USAGE))?)
+            }
+            "--no-front-matter" => options = options.with_front_matter(false),
+            other => return Err(usage(format!("unknown option `{other}`\n{EXPORT_USAGE}"))),
+        }
+    }
+
+    let markdown = NotionClient::from_env()?.export_markdown(page_id, &options)?;
+    match out {
+        Some(path) => std::fs::write(path, markdown).map_err(swivel::Error::from)?,
+        None => print!("{markdown}"),
+    }
+    Ok(())
+}
+
+#[cfg(feature = "notion")]
+fn import(args: &[String]) -> Result<()> {
+    use std::path::Path;
+    use swivel::notion::{parse_markdown, NotionClient, Parent};
+
+    const IMPORT_USAGE: &str = "usage: swivel import md <file> --parent <page-or-data-source-id>";
+
+    let (Some("md"), Some(file)) = (args.first().map(String::as_str), args.get(1)) else {
+        return Err(usage(IMPORT_USAGE));
+    };
+    let mut parent_id = None;
+    let mut flags = args[2..].iter();
+    while let Some(flag) = flags.next() {
+        match flag.as_str() {
+            "--parent" | "-p" => {
+                parent_id = Some(flags.next().ok_or_else(|| usage(IMPORT_USAGE))?.clone())
+            }
+            other => return Err(usage(format!("unknown option `{other}`\n{IMPORT_USAGE}"))),
+        }
+    }
+    let parent_id = notion_id(&parent_id.ok_or_else(|| usage(IMPORT_USAGE))?)?;
+
+    let source = std::fs::read_to_string(file).map_err(swivel::Error::from)?;
+    let mut doc = parse_markdown(&source);
+    if doc.title.is_none() {
+        let stem = Path::new(file).file_stem().map(|s| s.to_string_lossy());
+        doc.title = stem.map(String::from);
+    }
+
+    // The id alone does not say what it names: try it as a data source
+    // first, and otherwise create a child page.
+    let notion = NotionClient::from_env()?;
+    let page = match notion.get_data_source(&parent_id) {
+        Ok(data_source) => notion.import_markdown_into(&data_source, &doc)?,
+        Err(swivel::Error::ObjectNotFound { .. } | swivel::Error::Validation { .. }) => {
+            let parent = Parent::Page {
+                page_id: parent_id.to_string(),
+            };
+            notion.import_markdown(parent, &doc)?
+        }
+        Err(err) => return Err(err.into()),
+    };
+    println!("{}", page.url.unwrap_or(page.id));
+    Ok(())
+}
+
+#[cfg(feature = "notion")]
+fn codegen(args: &[String]) -> Result<()> {
+    use swivel::notion::{generate_rust, CodegenOptions, NotionClient};
+
+    const CODEGEN_USAGE: &str =
+        "usage: swivel codegen notion <data-source> [--name <Struct>] [--out <file>]";
+
+    let (Some("notion"), Some(data_source)) = (args.first().map(String::as_str), args.get(1))
+    else {
+        return Err(usage(CODEGEN_USAGE));
+    };
+    let data_source_id = notion_id(data_source)?;
+    let mut options = CodegenOptions::default();
+    let mut out = None;
+    let mut flags = args[2..].iter();
+    while let Some(flag) = flags.next() {
+        match flag.as_str() {
+            "--name" => {
+                options =
+                    options.with_struct_name(flags.next().ok_or_else(|| usage(CODEGEN_USAGE))?)
+            }
+            "--out" | "-o" => out = Some(flags.next().ok_or_else(|| usage(CODEGEN_USAGE))?),
+            other => return Err(usage(format!("unknown option `{other}`\n{CODEGEN_USAGE}"))),
+        }
+    }
+
+    let schema = NotionClient::from_env()?.get_data_source(data_source_id)?;
+    let code = generate_rust(&schema, &options);
+    match out {
+        Some(path) => std::fs::write(path, code).map_err(swivel::Error::from)?,
+        None => print!("{code}"),
+    }
+    Ok(())
+}
+
+#[cfg(feature = "supabase")]
+fn supabase(args: &[String]) -> Result<()> {
+    use serde_json::{Map, Value};
+    use std::io::BufRead;
+    use std::path::PathBuf;
+    use swivel::supabase::{AuthClient, FileStore, SupabaseClient};
+    use swivel::Database;
+
+    const SUPABASE_USAGE: &str = "usage: swivel supabase get <table> <id>\n       swivel supabase put <table> <json>\n       swivel supabase rpc <fn> [--arg k=v]... [--get]\n       swivel supabase login <email> [--otp]\n       swivel supabase verify <email> <code>\n       swivel supabase logout";
+
+    let auth = || -> Result<AuthClient> {
+        let path = match env::var_os("SUPABASE_SESSION_FILE") {
+            Some(path) => PathBuf::from(path),
+            None => {
+                let home = env::var_os("HOME").ok_or_else(|| {
+                    swivel::Error::Config("set SUPABASE_SESSION_FILE or HOME".into())
+                })?;
+                PathBuf::from(home).join(".config/swivel/supabase-session.json")
+            }
+        };
+        Ok(AuthClient::from_env()?.with_store(FileStore::new(path)))
+    };
+    // Signed in, requests run as the user; otherwise with the project key.
+    // A session that cannot be read or refreshed fails the command rather
+    // than falling back to a key that may bypass row-level security.
+    let client = |table: Option<&str>| -> Result<SupabaseClient> {
+        let db = SupabaseClient::project_from_env()?;
+        let db = match table {
+            Some(table) => db.with_table(table),
+            None => db,
+        };
+        let auth = auth()?;
+        match auth.session()? {
+            Some(_) => Ok(db.with_auth(auth)),
+            None => Ok(db),
+        }
+    };
+
+    match (args.first().map(String::as_str), args.get(1), args.get(2)) {
+        (Some("get"), Some(table), Some(id)) => {
+            let row = client(Some(table))?.get(id)?;
+            println!("{}", serde_json::to_string_pretty(&row)?);
+            Ok(())
+        }
+        (Some("put"), Some(table), Some(json)) => {
+            let rec: Value =
+                serde_json::from_str(json).map_err(|e| usage(format!("invalid JSON: {e}")))?;
+            let row = client(Some(table))?.upsert(&rec)?;
+            println!("{}", serde_json::to_string_pretty(&row)?);
+            Ok(())
+        }
+        (Some("rpc"), Some(function), _) => {
+            let (mut call_args, mut get) = (Map::new(), false);
+            let mut rest = args[2..].iter();
+            while let Some(flag) = rest.next() {
+                match flag.as_str() {
+                    "--get" => get = true,
+                    "--arg" => {
+                        let pair = rest
+                            .next()
+                            .ok_or_else(|| usage(format!("--arg needs k=v\n{SUPABASE_USAGE}")))?;
+                        let (name, value) = pair
+                            .split_once('=')
+                            .ok_or_else(|| usage(format!("--arg `{pair}` is not k=v")))?;
+                        let value = serde_json::from_str(value)
+                            .unwrap_or_else(|_| Value::String(value.to_string()));
+                        call_args.insert(name.to_string(), value);
+                    }
+                    other => {
+                        return Err(usage(format!("unknown option `{other}`\n{SUPABASE_USAGE}")))
+                    }
+                }
+            }
+            // Functions are not tied to a table.
+            let db = client(None)?;
+            let result: Value = if get {
+                db.rpc_get(function, call_args)?
+            } else {
+                db.rpc(function, call_args)?
+            };
+            println!("{}", serde_json::to_string_pretty(&result)?);
+            Ok(())
+        }
+        (Some("login"), Some(email), Some(flag)) if flag == "--otp" => {
+            auth()?.send_otp(email)?;
+            eprintln!("code sent: run `swivel supabase verify {email} <code>`");
+            Ok(())
+        }
+        (Some("login"), Some(email), None) => {
+            let mut password = String::new();
+            std::io::stdin()
+                .lock()
+                .read_line(&mut password)
+                .map_err(swivel::Error::from)?;
+            let password = password.trim_end_matches(['\r', '\n']);
+            auth()?.sign_in_with_password(email, password)?;
+            eprintln!("signed in as {email}");
+            Ok(())
+        }
+        (Some("verify"), Some(email), Some(code)) => {
+            auth()?.verify_otp(email, code)?;
+            eprintln!("signed in as {email}");
+            Ok(())
+        }
+        (Some("logout"), None, None) => {
+            auth()?.sign_out()?;
+            Ok(())
+        }
+        _ => Err(usage(SUPABASE_USAGE)),
+    }
+}
+
+#[cfg(feature = "postgres")]
+fn postgres(args: &[String]) -> Result<()> {
+    use swivel::{postgres::PostgresClient, Database};
+
+    match (args.first().map(String::as_str), args.get(1), args.get(2)) {
+        (Some("get"), Some(table), Some(id)) => {
+            let row = PostgresClient::from_env(table.as_str())?.get(id)?;
+            println!("{}", serde_json::to_string_pretty(&row)?);
+            Ok(())
+        }
+        (Some("put"), Some(table), Some(json)) => {
+            let rec =
+                serde_json::from_str(json).map_err(|e| usage(format!("invalid JSON: {e}")))?;
+            PostgresClient::from_env(table.as_str())?.put(rec)?;
+            Ok(())
+        }
+        _ => Err(usage(
+            "usage: swivel postgres get <table> <id>\n       swivel postgres put <table> <json>",
+        )),
+    }
+}
+
+#[cfg(feature = "sqlite")]
+fn sqlite(args: &[String]) -> Result<()> {
+    use swivel::{sqlite::SqliteClient, Database};
+
+    match (
+        args.first().map(String::as_str),
+        args.get(1),
+        args.get(2),
+        args.get(3),
+    ) {
+        (Some("get"), Some(path), Some(table), Some(id)) => {
+            let row = SqliteClient::open(path, table.as_str())?.get(id)?;
+            println!("{}", serde_json::to_string_pretty(&row)?);
+            Ok(())
+        }
+        (Some("put"), Some(path), Some(table), Some(json)) => {
+            let rec = serde_json::from_str(json).map_err(|e| usage(format!("invalid JSON: {e}")))?;
+            SqliteClient::open(path, table.as_str())?.put(rec)?;
+            Ok(())
+        }
+        _ => Err(usage(
+            "usage: swivel sqlite get <db-file> <table> <id>\n       swivel sqlite put <db-file> <table> <json>",
+        )),
+    }
+}
+
+/// Map an error onto the documented exit code table above.
+fn exit_code(err: &anyhow::Error) -> u8 {
+    if err.downcast_ref::<Usage>().is_some() {
+        return 2;
+    }
+    let Some(err) = err.downcast_ref::<swivel::Error>() else {
+        return 1;
+    };
+    match err {
+        swivel::Error::Config(_) => 3,
+        swivel::Error::Unauthorized { .. } => 4,
+        swivel::Error::RestrictedResource { .. } | swivel::Error::PermissionDenied { .. } => 5,
+        swivel::Error::ObjectNotFound { .. } => 6,
+        swivel::Error::Validation { .. } | swivel::Error::InvalidInput(_) => 7,
+        swivel::Error::RateLimited { .. } => 8,
+        swivel::Error::Conflict { .. }
+        | swivel::Error::UniqueViolation { .. }
+        | swivel::Error::ForeignKeyViolation { .. } => 9,
+        swivel::Error::Server { .. } => 10,
+        swivel::Error::Transport(_) => 11,
+        swivel::Error::Backend(_) => 12,
+        swivel::Error::Io(_) => 13,
+        _ => 1,
+    }
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err:#}");
+            ExitCode::from(exit_code(&err))
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use std::error::Error as StdError;
+use std::time::Duration;
+
+use thiserror::Error;
+
+/// Convenience alias used throughout the crate.
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Everything that can go wrong talking to a backend.
+///
+/// API failures carry the `request_id` the backend assigned, when it sent
+/// one; quote it when contacting support. See [`Error::request_id`].
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum Error {
+    /// Required configuration (usually an environment variable) is missing.
+    #[error("missing configuration: {0}")]
+    Config(String),
+
+    /// The token is missing, malformed or revoked (HTTP 401).
+    #[error("unauthorized: {message}{}", suffix(request_id))]
+    Unauthorized {
+        message: String,
+        request_id: Option<String>,
+    },
+
+    /// The token is valid but lacks access to the resource (HTTP 403).
+    #[error("restricted resource: {message}{}", suffix(request_id))]
+    RestrictedResource {
+        message: String,
+        request_id: Option<String>,
+    },
+
+    /// The object does not exist or is not shared with the integration (HTTP 404).
+    #[error("object not found: {message}{}", suffix(request_id))]
+    ObjectNotFound {
+        message: String,
+        request_id: Option<String>,
+    },
+
+    /// The request was rejected as malformed (HTTP 400). `code` is the
+    /// backend's machine-readable reason, e.g. `validation_error`.
+    #[error("validation failed ({code}): {message}{}", suffix(request_id))]
+    Validation {
+        code: String,
+        message: String,
+        request_id: Option<String>,
+    },
+
+    /// Too many requests (HTTP 429). `retry_after` comes from the
+    /// `Retry-After` header when present.
+    #[error("rate limited{}{}", retry_hint(retry_after), suffix(request_id))]
+    RateLimited {
+        retry_after: Option<Duration>,
+        request_id: Option<String>,
+    },
+
+    /// The write collided with a concurrent one (HTTP 409).
+    #[error("conflict: {message}{}", suffix(request_id))]
+    Conflict {
+        message: String,
+        request_id: Option<String>,
+    },
+
+    /// A row with the same value already exists under a unique constraint
+    /// (Postgres `23505`). `constraint` names it, e.g. `tasks_pkey`.
+    #[error(
+        "unique violation{}: {message}{}",
+        constraint_hint(constraint),
+        suffix(request_id)
+    )]
+    UniqueViolation {
+        constraint: Option<String>,
+        message: String,
+        request_id: Option<String>,
+    },
+
+    /// The row refers to a row that does not exist, or is still referred to
+    /// (Postgres `23503`).
+    #[error(
+        "foreign key violation{}: {message}{}",
+        constraint_hint(constraint),
+        suffix(request_id)
+    )]
+    ForeignKeyViolation {
+        constraint: Option<String>,
+        message: String,
+        request_id: Option<String>,
+    },
+
+    /// The database refused the statement for the current role: a missing
+    /// grant or a row-level security policy (Postgres `42501`).
+    #[error("permission denied: {message}{}", suffix(request_id))]
+    PermissionDenied {
+        message: String,
+        request_id: Option<String>,
+    },
+
+    /// The backend failed or is unavailable (HTTP 5xx).
+    #[error(
+        "server error ({code}): {message}: HTTP {status}{}",
+        suffix(request_id)
+    )]
+    Server {
+        status: u16,
+        code: String,
+        message: String,
+        request_id: Option<String>,
+    },
+
+    /// Any other non-2xx status the backend did not explain.
+    #[error("request failed: {message}: HTTP {status}{}", suffix(request_id))]
+    Http {
+        status: u16,
+        message: String,
+        request_id: Option<String>,
+    },
+
+    /// The request never produced a response (DNS, TLS, connection reset, ...).
+    #[error("failed to send HTTP request")]
+    Transport(#[source] Box<dyn StdError + Send + Sync>),
+
+    /// A database driver (postgres, sqlite) reported an error.
+    #[error("database driver error")]
+    Backend(#[source] Box<dyn StdError + Send + Sync>),
+
+    /// A local file could not be read or written.
+    #[error("I/O error")]
+    Io(#[from] std::io::Error),
+
+    /// The response body was not the JSON we expected.
+    #[error("failed to decode response body")]
+    Decode(#[from] serde_json::Error),
+
+    /// The caller passed something the backend cannot accept; nothing was sent.
+    #[error("invalid input: {0}")]
+    InvalidInput(String),
+
+    /// The backend does not support this operation (yet).
+    #[error("unsupported operation: {0}")]
+    Unsupported(&'static str),
+}
+
+impl Error {
+    /// The backend-assigned id of the failed request, if any.
+    pub fn request_id(&self) -> Option<&str> {
+        match self {
+            Error::Unauthorized { request_id, .. }
+            | Error::RestrictedResource { request_id, .. }
+            | Error::ObjectNotFound { request_id, .. }
+            | Error::Validation { request_id, .. }
+            | Error::RateLimited { request_id, .. }
+            | Error::Conflict { request_id, .. }
+            | Error::UniqueViolation { request_id, .. }
+            | Error::ForeignKeyViolation { request_id, .. }
+            | Error::PermissionDenied { request_id, .. }
+            | Error::Server { request_id, .. }
+            | Error::Http { request_id, .. } => request_id.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Whether sending the same request again may succeed: rate limits,
+    /// transient server failures and connection problems.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::RateLimited { .. } => true,
+            Error::Server { status, .. } => matches!(status, 500 | 502 | 503 | 504),
+            #[cfg(any(feature = "notion", feature = "supabase"))]
+            Error::Transport(err) => err
+                .downcast_ref::<reqwest::Error>()
+                .is_some_and(|err| err.is_connect() || err.is_timeout()),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(any(feature = "notion", feature = "supabase"))]
+impl From<reqwest::Error> for Error {
+    fn from(err: reqwest::Error) -> Self {
+        Error::Transport(Box::new(err))
+    }
+}
+
+#[cfg(feature = "postgres")]
+impl From<postgres::Error> for Error {
+    fn from(err: postgres::Error) -> Self {
+        Error::Backend(Box::new(err))
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl From<rusqlite::Error> for Error {
+    fn from(err: rusqlite::Error) -> Self {
+        Error::Backend(Box::new(err))
+    }
+}
+
+fn suffix(request_id: &Option<String>) -> String {
+    match request_id {
+        Some(id) => format!(" (request id {id})"),
+        None => String::new(),
+    }
+}
+
+fn constraint_hint(constraint: &Option<String>) -> String {
+    match constraint {
+        Some(name) => format!(" on {name}"),
+        None => String::new(),
+    }
+}
+
+fn retry_hint(retry_after: &Option<Duration>) -> String {
+    match retry_after {
+        Some(d) => format!(", retry after {}s", d.as_secs()),
+        None => String::new(),
+    }
+}
@@ -0,0 +1,297 @@
+//! Backend-agnostic HTTP plumbing: request description, rate limiting and
+//! the retry loop. Backends build an [`HttpRequest`] and supply their own
+//! response parser.
+
+use std::thread;
+use std::time::Duration;
+
+use reqwest::header::HeaderMap;
+use reqwest::Method;
+use serde_json::Value;
+
+use crate::{RateLimiter, Result, RetryPolicy};
+
+/// Everything needed to send (and re-send) one request.
+#[derive(Debug, Clone)]
+pub(crate) struct HttpRequest {
+    pub method: Method,
+    pub url: String,
+    pub headers: Vec<(&'static str, String)>,
+    pub body: Option<Body>,
+    pub idempotent: bool,
+}
+
+/// A request body: JSON for the APIs, raw bytes for file uploads.
+#[derive(Debug, Clone)]
+pub(crate) enum Body {
+    Json(Value),
+    #[cfg(feature = "supabase")]
+    Bytes {
+        content_type: String,
+        data: Vec<u8>,
+    },
+}
+
+impl Body {
+    fn content_type(&self) -> &str {
+        match self {
+            Body::Json(_) => "application/json",
+            #[cfg(feature = "supabase")]
+            Body::Bytes { content_type, .. } => content_type,
+        }
+    }
+
+    fn to_bytes(&self) -> Result<Vec<u8>> {
+        match self {
+            Body::Json(value) => Ok(serde_json::to_vec(value)?),
+            #[cfg(feature = "supabase")]
+            Body::Bytes { data, .. } => Ok(data.clone()),
+        }
+    }
+}
+
+impl HttpRequest {
+    pub fn new(method: Method, url: impl Into<String>) -> Self {
+        let idempotent = matches!(
+            method,
+            Method::GET | Method::HEAD | Method::PUT | Method::DELETE | Method::OPTIONS
+        );
+        Self {
+            method,
+            url: url.into(),
+            headers: Vec::new(),
+            body: None,
+            idempotent,
+        }
+    }
+
+    pub fn header(mut self, name: &'static str, value: impl Into<String>) -> Self {
+        self.headers.push((name, value.into()));
+        self
+    }
+
+    pub fn json(mut self, body: Value) -> Self {
+        self.body = Some(Body::Json(body));
+        self
+    }
+
+    /// Send `data` as it is, labelled with `content_type`.
+    #[cfg(feature = "supabase")]
+    pub fn bytes(mut self, content_type: impl Into<String>, data: Vec<u8>) -> Self {
+        self.body = Some(Body::Bytes {
+            content_type: content_type.into(),
+            data,
+        });
+        self
+    }
+
+    /// Mark a request as safe to re-send, e.g. a read-only `POST` query.
+    pub fn idempotent(mut self, idempotent: bool) -> Self {
+        self.idempotent = idempotent;
+        self
+    }
+}
+
+/// A response with its body fully read.
+#[derive(Debug)]
+pub(crate) struct HttpResponse {
+    pub status: u16,
+    pub headers: HeaderMap,
+    pub body: String,
+}
+
+impl HttpResponse {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).and_then(|v| v.to_str().ok())
+    }
+
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// The `Retry-After` header, when given in whole seconds.
+    pub fn retry_after(&self) -> Option<Duration> {
+        self.header("retry-after")?
+            .trim()
+            .parse::<u64>()
+            .ok()
+            .map(Duration::from_secs)
+    }
+}
+
+/// A pooled blocking client plus the retry and rate-limit settings that
+/// apply to every request sent through it.
+#[derive(Debug, Clone)]
+pub(crate) struct Transport {
+    pub http: reqwest::blocking::Client,
+    pub retry: RetryPolicy,
+    pub limiter: Option<RateLimiter>,
+}
+
+impl Transport {
+    pub fn new(limiter: Option<RateLimiter>) -> Self {
+        Self {
+            http: reqwest::blocking::Client::new(),
+            retry: RetryPolicy::default(),
+            limiter,
+        }
+    }
+
+    /// Send `req`, hand the response to `parse`, and retry whatever the
+    /// retry policy allows.
+    pub fn execute<T>(
+        &self,
+        req: &HttpRequest,
+        parse: impl Fn(HttpResponse) -> Result<T>,
+    ) -> Result<T> {
+        self.retrying(req, || self.send(req).and_then(&parse))
+    }
+
+    /// Like [`execute`](Self::execute), but for a body that need not be
+    /// text, such as a stored file: a successful response's bytes are
+    /// returned as they are, and a failed one goes to `failed`.
+    #[cfg(feature = "supabase")]
+    pub fn execute_bytes(
+        &self,
+        req: &HttpRequest,
+        failed: impl Fn(HttpResponse) -> crate::Error,
+    ) -> Result<Vec<u8>> {
+        self.retrying(req, || {
+            let resp = self.builder(req)?.send()?;
+            let status = resp.status().as_u16();
+            if resp.status().is_success() {
+                return Ok(resp.bytes()?.to_vec());
+            }
+            let headers = resp.headers().clone();
+            let body = resp.text()?;
+            Err(failed(HttpResponse {
+                status,
+                headers,
+                body,
+            }))
+        })
+    }
+
+    fn retrying<T>(&self, req: &HttpRequest, attempt_once: impl Fn() -> Result<T>) -> Result<T> {
+        let mut attempt = 1;
+        loop {
+            if let Some(limiter) = &self.limiter {
+                limiter.acquire();
+            }
+            match attempt_once() {
+                Err(err) if self.retry.should_retry(attempt, &err, req.idempotent) => {
+                    thread::sleep(self.retry.delay(attempt, &err));
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Fetch a file as bytes, e.g. a signed download URL. Not rate limited
+    /// or retried: such URLs point at a storage host, not the backend API.
+    #[cfg(feature = "notion")]
+    pub fn download(&self, url: &str) -> Result<Vec<u8>> {
+        let resp = self.http.get(url).send()?;
+        let status = resp.status();
+        if !status.is_success() {
+            return Err(crate::Error::Http {
+                status: status.as_u16(),
+                message: format!("downloading {url} failed"),
+                request_id: None,
+            });
+        }
+        Ok(resp.bytes()?.to_vec())
+    }
+
+    fn builder(&self, req: &HttpRequest) -> Result<reqwest::blocking::RequestBuilder> {
+        let mut builder = self.http.request(req.method.clone(), &req.url);
+        for (name, value) in &req.headers {
+            builder = builder.header(*name, value);
+        }
+        if let Some(body) = &req.body {
+            builder = builder
+                .header("Content-Type", body.content_type())
+                .body(body.to_bytes()?);
+        }
+        Ok(builder)
+    }
+
+    fn send(&self, req: &HttpRequest) -> Result<HttpResponse> {
+        let resp = self.builder(req)?.send()?;
+        let status = resp.status().as_u16();
+        let headers = resp.headers().clone();
+        let body = resp.text()?;
+        Ok(HttpResponse {
+            status,
+            headers,
+            body,
+        })
+    }
+}
+
+/// The async twin of [`Transport`]: same retry policy and rate limiter,
+/// but sleeps on the tokio timer instead of blocking the thread.
+#[cfg(all(feature = "async", feature = "notion"))]
+#[derive(Debug, Clone)]
+pub(crate) struct AsyncTransport {
+    pub http: reqwest::Client,
+    pub retry: RetryPolicy,
+    pub limiter: Option<RateLimiter>,
+}
+
+#[cfg(all(feature = "async", feature = "notion"))]
+impl AsyncTransport {
+    pub fn new(limiter: Option<RateLimiter>) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            retry: RetryPolicy::default(),
+            limiter,
+        }
+    }
+
+    /// See [`Transport::execute`].
+    pub async fn execute<T>(
+        &self,
+        req: &HttpRequest,
+        parse: impl Fn(HttpResponse) -> Result<T>,
+    ) -> Result<T> {
+        let mut attempt = 1;
+        loop {
+            if let Some(limiter) = &self.limiter {
+                let wait = limiter.reserve();
+                if !wait.is_zero() {
+                    tokio::time::sleep(wait).await;
+                }
+            }
+            match self.send(req).await.and_then(&parse) {
+                Err(err) if self.retry.should_retry(attempt, &err, req.idempotent) => {
+                    tokio::time::sleep(self.retry.delay(attempt, &err)).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn send(&self, req: &HttpRequest) -> Result<HttpResponse> {
+        let mut builder = self.http.request(req.method.clone(), &req.url);
+        for (name, value) in &req.headers {
+            builder = builder.header(*name, value);
+        }
+        if let Some(body) = &req.body {
+            builder = builder
+                .header("Content-Type", body.content_type())
+                .body(body.to_bytes()?);
+        }
+        let resp = builder.send().await?;
+        let status = resp.status().as_u16();
+        let headers = resp.headers().clone();
+        let body = resp.text().await?;
+        Ok(HttpResponse {
+            status,
+            headers,
+            body,
+        })
+    }
+}
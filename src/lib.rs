@@ -0,0 +1,98 @@
+//! **One trait, many databases.**
+//!
+//! `swivel` lets you write against a single [`Database`] trait and swap the
+//! storage backend without touching call sites.
+//!
+//! Each backend sits behind its own cargo feature; the trait core compiles
+//! with none of them and pulls in no HTTP stack.
+//!
+//! | feature    | module       | default |
+//! |------------|--------------|---------|
+//! | `notion`   | [`notion`]   | yes     |
+//! | `supabase` | [`supabase`] | no      |
+//! | `postgres` | `postgres`   | no      |
+//! | `sqlite`   | `sqlite`     | no      |
+//! | `async`    | `AsyncDatabase` and async clients | no |
+//! | `derive`   | `#[derive(Record)]` | no |
+//!
+//! [`BACKENDS`] lists what a given build contains.
+//!
+//! ```no_run
+//! # #[cfg(feature = "notion")] {
+//! use swivel::{notion::NotionClient, Database};
+//!
+//! let notion = NotionClient::from_env()?;
+//! let page = notion.get("275a1865-b187-807a-adea-ebaf36fb49b0")?;
+//! println!("{}", page.title());
+//! # }
+//! # Ok::<(), swivel::Error>(())
+//! ```
+
+mod error;
+#[cfg(any(feature = "notion", feature = "supabase"))]
+mod http;
+#[cfg(feature = "notion")]
+pub mod notion;
+#[cfg(feature = "postgres")]
+pub mod postgres;
+mod record;
+#[cfg(any(feature = "notion", feature = "supabase"))]
+mod retry;
+#[cfg(any(feature = "postgres", feature = "sqlite"))]
+mod sql;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+#[cfg(feature = "supabase")]
+pub mod supabase;
+
+pub use error::{Error, Result};
+#[doc(hidden)]
+pub use record::__private;
+pub use record::{Backend, Record, Records};
+#[cfg(any(feature = "notion", feature = "supabase"))]
+pub use retry::{RateLimiter, RetryPolicy};
+#[cfg(feature = "derive")]
+pub use swivel_derive::Record;
+
+/// Names of the backends compiled into this build.
+pub const BACKENDS: &[&str] = &[
+    #[cfg(feature = "notion")]
+    "notion",
+    #[cfg(feature = "supabase")]
+    "supabase",
+    #[cfg(feature = "postgres")]
+    "postgres",
+    #[cfg(feature = "sqlite")]
+    "sqlite",
+];
+
+/// `Database` captures the storage operations you care about.
+/// Start small; you can split into multiple traits later (Reads, Writes, Pages, etc.).
+pub trait Database {
+    type Record;
+    fn get(&self, id: &str) -> Result<Self::Record>;
+    fn put(&self, rec: Self::Record) -> Result<()>;
+}
+
+/// The async counterpart of [`Database`], enabled by the `async` feature.
+///
+/// Implementations are safe to call from inside a tokio runtime, where the
+/// blocking clients would panic.
+#[cfg(feature = "async")]
+pub trait AsyncDatabase {
+    type Record;
+    fn get(&self, id: &str) -> impl std::future::Future<Output = Result<Self::Record>> + Send;
+    fn put(&self, rec: Self::Record) -> impl std::future::Future<Output = Result<()>> + Send;
+}
+
+/// Copy one record onto itself through a statically dispatched backend.
+pub fn sync_one<D: Database>(db: &D, id: &str) -> Result<()> {
+    let rec = db.get(id)?;
+    db.put(rec)
+}
+
+/// Same as [`sync_one`], for backends chosen at runtime.
+pub fn sync_dyn<R>(db: &dyn Database<Record = R>, id: &str) -> Result<()> {
+    let rec = db.get(id)?;
+    db.put(rec)
+}
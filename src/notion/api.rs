@@ -0,0 +1,289 @@
+//! Request building and response parsing shared by [`NotionClient`] and
+//! `AsyncNotionClient`, so the two cannot drift apart: each endpoint is an
+//! [`HttpRequest`] built here plus a parser applied to the response.
+//!
+//! [`NotionClient`]: super::NotionClient
+
+use std::collections::HashMap;
+use std::env;
+use std::sync::{Arc, Mutex};
+
+use reqwest::Method;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+use super::{error, NotionDatabase};
+use crate::http::{HttpRequest, HttpResponse};
+use crate::{Error, Result};
+
+/// Base URL of the public Notion API.
+pub const DEFAULT_BASE_URL: &str = "https://api.notion.com/v1";
+
+/// The `Notion-Version` header sent by default, the first version with
+/// data sources.
+pub const NOTION_VERSION: &str = "2025-09-03";
+
+/// The last version before data sources, where databases are queried
+/// directly.
+pub const LEGACY_NOTION_VERSION: &str = "2022-06-28";
+
+/// The outcome of a call addressed to a data source; see
+/// [`Api::data_source_fallback`].
+pub(crate) enum Fallback<T> {
+    Done(Result<T>),
+    /// Look the id up as a database, keeping the error in case it is not one.
+    LookUpDatabase(Error),
+}
+
+/// Credentials, endpoint root and API version for one client.
+#[derive(Debug, Clone)]
+pub(crate) struct Api {
+    api_key: String,
+    base_url: String,
+    version: String,
+    /// Database ids already looked up, and the data source each stands
+    /// for. Shared by clones.
+    data_sources: Arc<Mutex<HashMap<String, String>>>,
+}
+
+impl Api {
+    pub fn new(api_key: String) -> Self {
+        Self {
+            api_key,
+            base_url: DEFAULT_BASE_URL.to_string(),
+            version: NOTION_VERSION.to_string(),
+            data_sources: Arc::default(),
+        }
+    }
+
+    pub fn set_base_url(&mut self, base_url: String) {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+    }
+
+    pub fn set_version(&mut self, version: String) {
+        self.version = version;
+    }
+
+    /// Whether the version has data sources. Versions are dates, so they
+    /// compare as strings.
+    pub fn uses_data_sources(&self) -> bool {
+        self.version.as_str() >= NOTION_VERSION
+    }
+
+    /// The data source to use for `id`: the one its database was found to
+    /// hold, or `id` itself.
+    pub fn data_source_for(&self, id: &str) -> String {
+        let known = self.data_sources.lock().expect("data source cache");
+        known.get(id).cloned().unwrap_or_else(|| id.to_string())
+    }
+
+    /// Remember the data source `database` stands for. Only a database
+    /// with exactly one can stand in for it.
+    pub fn resolve(&self, database: &NotionDatabase) -> Result<String> {
+        let data_source = match database.data_sources.as_slice() {
+            [only] => only.id.clone(),
+            [] => {
+                return Err(Error::InvalidInput(format!(
+                    "database `{}` has no data sources",
+                    database.title()
+                )))
+            }
+            several => {
+                let ids: Vec<_> = several
+                    .iter()
+                    .map(|ds| format!("{} ({})", ds.id, ds.name))
+                    .collect();
+                return Err(Error::InvalidInput(format!(
+                    "database `{}` has {} data sources; pass one of {}",
+                    database.title(),
+                    several.len(),
+                    ids.join(", ")
+                )));
+            }
+        };
+        let mut known = self.data_sources.lock().expect("data source cache");
+        known.insert(database.id.clone(), data_source.clone());
+        Ok(data_source)
+    }
+
+    /// What to do with the `result` of a call made with `data_source`, the
+    /// data source first tried for `id`: an id Notion does not know as a
+    /// data source may be a database, worth looking up unless it already
+    /// was or the API version has no data sources.
+    pub fn data_source_fallback<T>(
+        &self,
+        id: &str,
+        data_source: &str,
+        result: Result<T>,
+    ) -> Fallback<T> {
+        match result {
+            Err(err @ Error::ObjectNotFound { .. })
+                if data_source == id && self.uses_data_sources() =>
+            {
+                Fallback::LookUpDatabase(err)
+            }
+            result => Fallback::Done(result),
+        }
+    }
+
+    /// The data source to retry with once `id` was looked up as a database
+    /// after [`Fallback::LookUpDatabase`]. Not a database either, the
+    /// original error `err` stands.
+    pub fn retry_with_database(
+        &self,
+        database: Result<NotionDatabase>,
+        err: Error,
+    ) -> Result<String> {
+        match database {
+            Ok(database) => self.resolve(&database),
+            Err(_) => Err(err),
+        }
+    }
+
+    pub fn request(&self, method: Method, path: &str) -> HttpRequest {
+        HttpRequest::new(method, format!("{}/{path}", self.base_url))
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .header("Notion-Version", &self.version)
+    }
+
+    pub fn get_page(&self, page_id: &str) -> HttpRequest {
+        self.request(Method::GET, &format!("pages/{page_id}"))
+    }
+
+    /// `GET /pages/{id}/properties/{property_id}`, one page of at most 100
+    /// items for list properties.
+    pub fn property_item(
+        &self,
+        page_id: &str,
+        property_id: &str,
+        cursor: Option<&str>,
+    ) -> HttpRequest {
+        let mut path = format!("pages/{page_id}/properties/{property_id}?page_size=100");
+        if let Some(cursor) = cursor {
+            path.push_str("&start_cursor=");
+            path.push_str(cursor);
+        }
+        self.request(Method::GET, &path)
+    }
+
+    /// `GET /blocks/{id}/children`, one page of at most 100 blocks.
+    pub fn block_children(&self, block_id: &str, cursor: Option<&str>) -> HttpRequest {
+        let mut path = format!("blocks/{block_id}/children?page_size=100");
+        if let Some(cursor) = cursor {
+            path.push_str("&start_cursor=");
+            path.push_str(cursor);
+        }
+        self.request(Method::GET, &path)
+    }
+
+    /// `POST /pages` creates a page each time, so it is never retried.
+    /// Before data sources, rows are created under their database.
+    pub fn create_page(&self, mut body: Value) -> HttpRequest {
+        if !self.uses_data_sources() && body["parent"]["type"] == "data_source_id" {
+            let parent = &body["parent"];
+            let database_id = parent
+                .get("database_id")
+                .unwrap_or(&parent["data_source_id"])
+                .clone();
+            body["parent"] = json!({ "type": "database_id", "database_id": database_id });
+        }
+        self.request(Method::POST, "pages").json(body)
+    }
+
+    /// `PATCH /pages/{id}` sets values, so sending it twice is harmless.
+    pub fn update_page(&self, page_id: &str, body: Value) -> HttpRequest {
+        self.request(Method::PATCH, &format!("pages/{page_id}"))
+            .json(body)
+            .idempotent(true)
+    }
+
+    /// `PATCH /blocks/{id}/children` appends each time, so it is never
+    /// retried.
+    pub fn append_block_children(&self, block_id: &str, children: Vec<Value>) -> HttpRequest {
+        self.request(Method::PATCH, &format!("blocks/{block_id}/children"))
+            .json(json!({ "children": children }))
+    }
+
+    pub fn get_database(&self, database_id: &str) -> HttpRequest {
+        self.request(Method::GET, &format!("databases/{database_id}"))
+    }
+
+    /// `POST /databases` creates a database each time, so it is never
+    /// retried.
+    pub fn create_database(&self, body: Value) -> HttpRequest {
+        self.request(Method::POST, "databases").json(body)
+    }
+
+    /// `PATCH /data_sources/{id}`, or `/databases/{id}` before data
+    /// sources. It sets the schema, so sending it twice is harmless.
+    pub fn update_data_source(&self, data_source_id: &str, body: Value) -> HttpRequest {
+        let path = if self.uses_data_sources() {
+            format!("data_sources/{data_source_id}")
+        } else {
+            format!("databases/{data_source_id}")
+        };
+        self.request(Method::PATCH, &path)
+            .json(body)
+            .idempotent(true)
+    }
+
+    /// Before data sources, the database holds the schema itself.
+    pub fn get_data_source(&self, data_source_id: &str) -> HttpRequest {
+        if !self.uses_data_sources() {
+            return self.get_database(data_source_id);
+        }
+        self.request(Method::GET, &format!("data_sources/{data_source_id}"))
+    }
+
+    /// `POST /search` only reads, so it may be retried. Before data
+    /// sources, the object filter names databases instead.
+    pub fn search(&self, mut body: Value) -> HttpRequest {
+        if !self.uses_data_sources() && body["filter"]["value"] == "data_source" {
+            body["filter"]["value"] = json!("database");
+        }
+        self.request(Method::POST, "search")
+            .json(body)
+            .idempotent(true)
+    }
+
+    /// `POST /data_sources/{id}/query`, or `/databases/{id}/query` before
+    /// data sources. It only reads, so it may be retried.
+    pub fn query_data_source(&self, data_source_id: &str, body: Value) -> HttpRequest {
+        let path = if self.uses_data_sources() {
+            format!("data_sources/{data_source_id}/query")
+        } else {
+            format!("databases/{data_source_id}/query")
+        };
+        self.request(Method::POST, &path)
+            .json(body)
+            .idempotent(true)
+    }
+}
+
+/// Read the integration token from `NOTION_API_KEY`.
+pub(crate) fn api_key_from_env() -> Result<String> {
+    env::var("NOTION_API_KEY")
+        .map_err(|_| Error::Config("NOTION_API_KEY is not set in the environment".into()))
+}
+
+/// The API version from `NOTION_VERSION`, when it is set.
+pub(crate) fn version_from_env() -> Option<String> {
+    env::var("NOTION_VERSION").ok().filter(|v| !v.is_empty())
+}
+
+/// The API root from `NOTION_BASE_URL`, when it is set.
+pub(crate) fn base_url_from_env() -> Option<String> {
+    env::var("NOTION_BASE_URL").ok().filter(|v| !v.is_empty())
+}
+
+/// Decode a successful response, or turn a failed one into a typed [`Error`].
+pub(crate) fn parse_json<T: DeserializeOwned>(resp: HttpResponse) -> Result<T> {
+    if !resp.is_success() {
+        return Err(error::from_response(
+            resp.status,
+            resp.retry_after(),
+            &resp.body,
+        ));
+    }
+    Ok(serde_json::from_str(&resp.body)?)
+}
@@ -0,0 +1,202 @@
+use super::api::{self, Api, Fallback};
+use super::property_item::{PageOptions, PropertyItems};
+use super::{DataSource, IntoNotionId, NewPage, NotionDatabase, Page, PageUpdate, PropertyValue};
+use crate::http::AsyncTransport;
+use crate::{AsyncDatabase, RateLimiter, Result, RetryPolicy};
+
+/// Async client for the Notion REST API, safe to use inside a tokio runtime.
+///
+/// Shares request building and response parsing with
+/// [`NotionClient`](super::NotionClient), so both see identical pages and errors.
+#[derive(Debug, Clone)]
+pub struct AsyncNotionClient {
+    transport: AsyncTransport,
+    api: Api,
+}
+
+impl AsyncNotionClient {
+    /// Create a client authenticated with an integration token.
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            transport: AsyncTransport::new(Some(RateLimiter::new(3.0, 3))),
+            api: Api::new(api_key.into()),
+        }
+    }
+
+    /// Read the integration token from `NOTION_API_KEY`, and the API
+    /// version from `NOTION_VERSION` and the API root from
+    /// `NOTION_BASE_URL` when they are set.
+    pub fn from_env() -> Result<Self> {
+        let mut client = Self::new(api::api_key_from_env()?);
+        if let Some(version) = api::version_from_env() {
+            client = client.with_notion_version(version);
+        }
+        if let Some(base_url) = api::base_url_from_env() {
+            client = client.with_base_url(base_url);
+        }
+        Ok(client)
+    }
+
+    /// Point the client at a different API root (a proxy or a local mock).
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.api.set_base_url(base_url.into());
+        self
+    }
+
+    /// Send `version` as the `Notion-Version` header; see
+    /// [`NotionClient::with_notion_version`](super::NotionClient::with_notion_version).
+    pub fn with_notion_version(mut self, version: impl Into<String>) -> Self {
+        self.api.set_version(version.into());
+        self
+    }
+
+    /// Replace the default retry policy.
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.transport.retry = retry;
+        self
+    }
+
+    /// Replace the default rate limiter; `None` disables client-side limiting.
+    pub fn with_rate_limiter(mut self, limiter: Option<RateLimiter>) -> Self {
+        self.transport.limiter = limiter;
+        self
+    }
+
+    /// Retrieve a page object by id.
+    pub async fn get_page(&self, page_id: impl IntoNotionId) -> Result<Page> {
+        let page_id = page_id.into_notion_id()?;
+        self.transport
+            .execute(&self.api.get_page(page_id.as_str()), api::parse_json)
+            .await
+    }
+
+    /// [`get_page`](Self::get_page), then what `options` asks for; see
+    /// [`PageOptions`].
+    pub async fn get_page_with(
+        &self,
+        page_id: impl IntoNotionId,
+        options: &PageOptions,
+    ) -> Result<Page> {
+        let mut page = self.get_page(page_id).await?;
+        if options.fetch_complete() {
+            self.complete_page(&mut page).await?;
+        }
+        Ok(page)
+    }
+
+    /// Retrieve one property of a page in full, following pagination; see
+    /// [`NotionClient::get_property`](super::NotionClient::get_property).
+    pub async fn get_property(
+        &self,
+        page_id: impl IntoNotionId,
+        property_id: &str,
+    ) -> Result<PropertyValue> {
+        let page_id = page_id.into_notion_id()?;
+        let mut items = PropertyItems::default();
+        let mut cursor = None;
+        loop {
+            let req = self
+                .api
+                .property_item(page_id.as_str(), property_id, cursor.as_deref());
+            cursor = items.add(self.transport.execute(&req, api::parse_json).await?)?;
+            if cursor.is_none() {
+                return items.finish();
+            }
+        }
+    }
+
+    /// Replace each of the page's truncated properties with its full value.
+    pub async fn complete_page(&self, page: &mut Page) -> Result<()> {
+        for name in page.truncated_properties() {
+            let property = page.properties.get_mut(&name).expect("listed by name");
+            property.value = self.get_property(page.id.as_str(), &property.id).await?;
+            property.extra.remove("has_more");
+        }
+        Ok(())
+    }
+
+    /// Retrieve a data source, including its property schema. A database
+    /// id gives its data source's, as with the blocking client.
+    pub async fn get_data_source(&self, data_source_id: impl IntoNotionId) -> Result<DataSource> {
+        let id = data_source_id.into_notion_id()?;
+        let data_source = self.api.data_source_for(id.as_str());
+        let result = self
+            .transport
+            .execute(&self.api.get_data_source(&data_source), api::parse_json)
+            .await;
+        match self
+            .api
+            .data_source_fallback(id.as_str(), &data_source, result)
+        {
+            Fallback::Done(result) => result,
+            Fallback::LookUpDatabase(err) => {
+                let database = self.get_database(&id).await;
+                let data_source = self.api.retry_with_database(database, err)?;
+                self.transport
+                    .execute(&self.api.get_data_source(&data_source), api::parse_json)
+                    .await
+            }
+        }
+    }
+
+    /// Retrieve a database, including the list of its data sources.
+    pub async fn get_database(&self, database_id: impl IntoNotionId) -> Result<NotionDatabase> {
+        let id = database_id.into_notion_id()?;
+        self.transport
+            .execute(&self.api.get_database(id.as_str()), api::parse_json)
+            .await
+    }
+
+    /// Apply `update` to a page and return the page as it now is.
+    pub async fn update_page(
+        &self,
+        page_id: impl IntoNotionId,
+        update: &PageUpdate,
+    ) -> Result<Page> {
+        let page_id = page_id.into_notion_id()?;
+        self.transport
+            .execute(
+                &self.api.update_page(page_id.as_str(), update.body()),
+                api::parse_json,
+            )
+            .await
+    }
+
+    /// Move a page to the trash.
+    pub async fn archive_page(&self, page_id: impl IntoNotionId) -> Result<Page> {
+        self.update_page(page_id, &PageUpdate::new().with_in_trash(true))
+            .await
+    }
+
+    /// Take a page back out of the trash.
+    pub async fn restore_page(&self, page_id: impl IntoNotionId) -> Result<Page> {
+        self.update_page(page_id, &PageUpdate::new().with_in_trash(false))
+            .await
+    }
+}
+
+impl AsyncDatabase for AsyncNotionClient {
+    type Record = Page;
+
+    async fn get(&self, id: &str) -> Result<Self::Record> {
+        self.get_page(id).await
+    }
+
+    /// Same as the blocking [`put`](crate::Database::put): create the page
+    /// when it has no id, otherwise send only what differs.
+    async fn put(&self, rec: Self::Record) -> Result<()> {
+        if rec.id.is_empty() {
+            let body = NewPage::from(&rec).body();
+            let _: Page = self
+                .transport
+                .execute(&self.api.create_page(body), api::parse_json)
+                .await?;
+            return Ok(());
+        }
+        let update = PageUpdate::diff(&self.get_page(&rec.id).await?, &rec);
+        if !update.is_empty() {
+            self.update_page(&rec.id, &update).await?;
+        }
+        Ok(())
+    }
+}
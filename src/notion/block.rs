@@ -0,0 +1,224 @@
+use serde::de::Error as _;
+use serde::ser::{Error as _, SerializeMap};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
+
+use super::common::{FileObject, Icon};
+use super::keyed::{self, keyed_enum};
+use super::rich_text::{self, Equation, RichText};
+
+/// One block of page content.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Block {
+    /// Empty for blocks built locally.
+    pub id: String,
+    /// Whether Notion holds nested blocks under this one.
+    pub has_children: bool,
+    pub content: BlockContent,
+    /// Other fields Notion sent (`object`, `parent`, `created_time`, ...).
+    pub extra: Map<String, Value>,
+}
+
+impl Block {
+    pub fn new(content: BlockContent) -> Self {
+        Self {
+            id: String::new(),
+            has_children: false,
+            content,
+            extra: Map::new(),
+        }
+    }
+
+    /// The block's own text as plain text, empty for blocks without any.
+    pub fn plain_text(&self) -> String {
+        self.content
+            .rich_text()
+            .map(rich_text::plain_text)
+            .unwrap_or_default()
+    }
+}
+
+impl Serialize for Block {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let value = self.content.keyed_value().map_err(S::Error::custom)?;
+        let mut map = serializer.serialize_map(None)?;
+        if !self.id.is_empty() {
+            map.serialize_entry("id", &self.id)?;
+        }
+        map.serialize_entry("has_children", &self.has_children)?;
+        map.serialize_entry("type", self.content.kind())?;
+        map.serialize_entry(self.content.kind(), &value)?;
+        for (k, v) in &self.extra {
+            map.serialize_entry(k, v)?;
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Block {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut map = Map::deserialize(deserializer)?;
+        let id = match map.remove("id") {
+            Some(Value::String(id)) => id,
+            _ => String::new(),
+        };
+        let has_children = map
+            .remove("has_children")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let (kind, value) = keyed::split(&mut map).map_err(D::Error::custom)?;
+        let content = BlockContent::from_keyed(kind, value).map_err(D::Error::custom)?;
+        Ok(Self {
+            id,
+            has_children,
+            content,
+            extra: map,
+        })
+    }
+}
+
+keyed_enum! {
+    /// The typed content of a block.
+    pub enum BlockContent {
+        Paragraph(TextBlock) = "paragraph",
+        Heading1(Heading) = "heading_1",
+        Heading2(Heading) = "heading_2",
+        Heading3(Heading) = "heading_3",
+        BulletedListItem(TextBlock) = "bulleted_list_item",
+        NumberedListItem(TextBlock) = "numbered_list_item",
+        ToDo(ToDo) = "to_do",
+        Toggle(TextBlock) = "toggle",
+        Quote(TextBlock) = "quote",
+        Callout(Callout) = "callout",
+        Code(Code) = "code",
+        Equation(Equation) = "equation",
+        Divider(Map<String, Value>) = "divider",
+        Bookmark(Bookmark) = "bookmark",
+        Embed(Bookmark) = "embed",
+        Image(FileBlock) = "image",
+        Video(FileBlock) = "video",
+        File(FileBlock) = "file",
+        Pdf(FileBlock) = "pdf",
+        Audio(FileBlock) = "audio",
+        Table(Table) = "table",
+        TableRow(TableRow) = "table_row",
+        ColumnList(Map<String, Value>) = "column_list",
+        Column(Map<String, Value>) = "column",
+        /// A page nested under this one; its children are the page's content.
+        ChildPage(ChildTitle) = "child_page",
+        /// A database nested under this page.
+        ChildDatabase(ChildTitle) = "child_database",
+        SyncedBlock(SyncedBlock) = "synced_block",
+        LinkToPage(Value) = "link_to_page",
+    }
+}
+
+impl BlockContent {
+    /// The block's rich text, for the types that have one.
+    pub fn rich_text(&self) -> Option<&[RichText]> {
+        Some(match self {
+            Self::Paragraph(b)
+            | Self::BulletedListItem(b)
+            | Self::NumberedListItem(b)
+            | Self::Toggle(b)
+            | Self::Quote(b) => &b.rich_text,
+            Self::Heading1(h) | Self::Heading2(h) | Self::Heading3(h) => &h.rich_text,
+            Self::ToDo(t) => &t.rich_text,
+            Self::Callout(c) => &c.rich_text,
+            Self::Code(c) => &c.rich_text,
+            _ => return None,
+        })
+    }
+}
+
+fn default_color() -> String {
+    "default".into()
+}
+
+/// Paragraphs, list items, toggles and quotes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextBlock {
+    pub rich_text: Vec<RichText>,
+    #[serde(default = "default_color")]
+    pub color: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Heading {
+    pub rich_text: Vec<RichText>,
+    #[serde(default = "default_color")]
+    pub color: String,
+    /// A toggle heading hides its children until expanded.
+    #[serde(default)]
+    pub is_toggleable: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToDo {
+    pub rich_text: Vec<RichText>,
+    #[serde(default)]
+    pub checked: bool,
+    #[serde(default = "default_color")]
+    pub color: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Callout {
+    pub rich_text: Vec<RichText>,
+    #[serde(default)]
+    pub icon: Option<Icon>,
+    #[serde(default = "default_color")]
+    pub color: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Code {
+    pub rich_text: Vec<RichText>,
+    #[serde(default)]
+    pub caption: Vec<RichText>,
+    /// Notion's language name, e.g. `"rust"` or `"plain text"`.
+    pub language: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub url: String,
+    #[serde(default)]
+    pub caption: Vec<RichText>,
+}
+
+/// Images, videos, files, PDFs and audio.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileBlock {
+    #[serde(default)]
+    pub caption: Vec<RichText>,
+    #[serde(flatten)]
+    pub file: FileObject,
+}
+
+/// A table; its rows are its children.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Table {
+    pub table_width: usize,
+    #[serde(default)]
+    pub has_column_header: bool,
+    #[serde(default)]
+    pub has_row_header: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TableRow {
+    pub cells: Vec<Vec<RichText>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChildTitle {
+    pub title: String,
+}
+
+/// An original synced block (`synced_from` is `None`) or a copy of one.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyncedBlock {
+    #[serde(default)]
+    pub synced_from: Option<Value>,
+}
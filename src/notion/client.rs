@@ -0,0 +1,448 @@
+use std::fs;
+
+use super::api::{self, Api, Fallback};
+use super::create::{self, NewPage};
+use super::export::{self, MarkdownOptions};
+use super::import::{self, MarkdownDocument};
+use super::pagination::Paginator;
+use super::property_item::{PageOptions, PropertyItems};
+use super::tree::{self, BlockTree, ContentOptions};
+use super::{
+    Block, BlockNode, DataSource, DataSourceQuery, DataSourceRecords, DataSourceRef, IntoNotionId,
+    NotionDatabase, Page, PageUpdate, Parent, PropertyValue, SchemaPlan, SchemaSpec, SchemaTarget,
+    Search, SearchObject, SearchResult,
+};
+use crate::http::Transport;
+use crate::{Database, Error, RateLimiter, Record, Result, RetryPolicy};
+
+/// Blocking client for the Notion REST API.
+///
+/// Requests are retried according to a [`RetryPolicy`] and throttled by a
+/// [`RateLimiter`] (three requests per second by default, Notion's documented
+/// average). Cloning is cheap: clones share the connection pool and the rate
+/// limiter.
+///
+/// Requests use API version [`NOTION_VERSION`](super::NOTION_VERSION)
+/// unless [`with_notion_version`](Self::with_notion_version) says
+/// otherwise. Either way, data source methods also take database ids: on
+/// 2025-09-03 and later a database id is swapped for the database's data
+/// source, and earlier versions use the database endpoints.
+#[derive(Debug, Clone)]
+pub struct NotionClient {
+    transport: Transport,
+    api: Api,
+}
+
+impl NotionClient {
+    /// Create a client authenticated with an integration token.
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            transport: Transport::new(Some(RateLimiter::new(3.0, 3))),
+            api: Api::new(api_key.into()),
+        }
+    }
+
+    /// Read the integration token from `NOTION_API_KEY`, and the API
+    /// version from `NOTION_VERSION` and the API root from
+    /// `NOTION_BASE_URL` when they are set.
+    pub fn from_env() -> Result<Self> {
+        let mut client = Self::new(api::api_key_from_env()?);
+        if let Some(version) = api::version_from_env() {
+            client = client.with_notion_version(version);
+        }
+        if let Some(base_url) = api::base_url_from_env() {
+            client = client.with_base_url(base_url);
+        }
+        Ok(client)
+    }
+
+    /// Point the client at a different API root (a proxy or a local mock).
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.api.set_base_url(base_url.into());
+        self
+    }
+
+    /// Send `version` as the `Notion-Version` header, e.g.
+    /// [`LEGACY_NOTION_VERSION`](super::LEGACY_NOTION_VERSION) for
+    /// integrations that still work with databases.
+    pub fn with_notion_version(mut self, version: impl Into<String>) -> Self {
+        self.api.set_version(version.into());
+        self
+    }
+
+    /// Replace the default retry policy.
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.transport.retry = retry;
+        self
+    }
+
+    /// Replace the default rate limiter; `None` disables client-side limiting.
+    pub fn with_rate_limiter(mut self, limiter: Option<RateLimiter>) -> Self {
+        self.transport.limiter = limiter;
+        self
+    }
+
+    /// Retrieve a page object by id.
+    pub fn get_page(&self, page_id: impl IntoNotionId) -> Result<Page> {
+        let page_id = page_id.into_notion_id()?;
+        self.transport
+            .execute(&self.api.get_page(page_id.as_str()), api::parse_json)
+    }
+
+    /// [`get_page`](Self::get_page), then what `options` asks for; see
+    /// [`PageOptions`].
+    pub fn get_page_with(&self, page_id: impl IntoNotionId, options: &PageOptions) -> Result<Page> {
+        let mut page = self.get_page(page_id)?;
+        if options.fetch_complete() {
+            self.complete_page(&mut page)?;
+        }
+        Ok(page)
+    }
+
+    /// Retrieve one property of a page in full, following pagination. This
+    /// is the complete value of a title, rich text, people, relation or
+    /// rollup property whose page object copy was cut off at
+    /// [`MAX_INLINE_ITEMS`](super::MAX_INLINE_ITEMS).
+    pub fn get_property(
+        &self,
+        page_id: impl IntoNotionId,
+        property_id: &str,
+    ) -> Result<PropertyValue> {
+        let page_id = page_id.into_notion_id()?;
+        let mut items = PropertyItems::default();
+        let mut cursor = None;
+        loop {
+            let req = self
+                .api
+                .property_item(page_id.as_str(), property_id, cursor.as_deref());
+            cursor = items.add(self.transport.execute(&req, api::parse_json)?)?;
+            if cursor.is_none() {
+                return items.finish();
+            }
+        }
+    }
+
+    /// Replace each of the page's [truncated properties] with its full
+    /// value.
+    ///
+    /// [truncated properties]: Page::truncated_properties
+    pub fn complete_page(&self, page: &mut Page) -> Result<()> {
+        for name in page.truncated_properties() {
+            let property = page.properties.get_mut(&name).expect("listed by name");
+            property.value = self.get_property(page.id.as_str(), &property.id)?;
+            property.extra.remove("has_more");
+        }
+        Ok(())
+    }
+
+    /// Retrieve a data source, including its property schema. A database
+    /// id gives its data source's.
+    pub fn get_data_source(&self, data_source_id: impl IntoNotionId) -> Result<DataSource> {
+        let id = data_source_id.into_notion_id()?;
+        self.with_data_source(id.as_str(), |id| {
+            self.transport
+                .execute(&self.api.get_data_source(id), api::parse_json)
+        })
+    }
+
+    /// Retrieve a database, including the list of its data sources.
+    pub fn get_database(&self, database_id: impl IntoNotionId) -> Result<NotionDatabase> {
+        let id = database_id.into_notion_id()?;
+        self.transport
+            .execute(&self.api.get_database(id.as_str()), api::parse_json)
+    }
+
+    /// The data sources of a database. Before data sources existed, the
+    /// database stands in for its only one.
+    pub fn data_sources(&self, database_id: impl IntoNotionId) -> Result<Vec<DataSourceRef>> {
+        let database = self.get_database(database_id)?;
+        if self.api.uses_data_sources() {
+            return Ok(database.data_sources);
+        }
+        Ok(vec![DataSourceRef {
+            name: database.title(),
+            id: database.id,
+        }])
+    }
+
+    /// Run `call` with the data source `id` stands for. An id Notion does
+    /// not know as a data source is looked up as a database, and when that
+    /// database has a single data source the call is made again with it;
+    /// the answer is remembered for the client and its clones.
+    pub(crate) fn with_data_source<T>(
+        &self,
+        id: &str,
+        call: impl Fn(&str) -> Result<T>,
+    ) -> Result<T> {
+        let data_source = self.api.data_source_for(id);
+        match self
+            .api
+            .data_source_fallback(id, &data_source, call(&data_source))
+        {
+            Fallback::Done(result) => result,
+            Fallback::LookUpDatabase(err) => {
+                call(&self.api.retry_with_database(self.get_database(id), err)?)
+            }
+        }
+    }
+
+    /// Start a query against a data source; see [`DataSourceQuery`].
+    pub fn query_data_source(&self, data_source_id: impl IntoNotionId) -> DataSourceQuery<'_> {
+        DataSourceQuery::new(self, data_source_id.into_notion_id())
+    }
+
+    /// The pages of a data source as records of a [`Record`] type; see
+    /// [`DataSourceRecords`]. An invalid id fails on first use.
+    ///
+    /// [`Record`]: crate::Record
+    pub fn records<T: Record>(
+        &self,
+        data_source_id: impl IntoNotionId,
+    ) -> DataSourceRecords<'_, T> {
+        DataSourceRecords::new(self, data_source_id.into_notion_id())
+    }
+
+    /// Compare `spec` with the data source it describes; see [`SchemaPlan`].
+    ///
+    /// The data source is `spec.id` when given, and otherwise the one data
+    /// source shared with the integration whose title is `spec.title`. When
+    /// there is none, the plan creates it under `spec.parent`.
+    pub fn plan_schema(&self, spec: &SchemaSpec) -> Result<SchemaPlan> {
+        spec.validate()?;
+        let existing = match &spec.id {
+            Some(id) => Some(self.get_data_source(id.as_str())?),
+            None => self.find_data_source(&spec.title)?,
+        };
+        SchemaPlan::new(spec, existing.as_ref())
+    }
+
+    /// Carry out `plan` and return the data source as it now is. Nothing is
+    /// sent for a plan with no changes.
+    pub fn apply_schema(&self, plan: &SchemaPlan) -> Result<DataSource> {
+        let legacy = !self.api.uses_data_sources();
+        match &plan.target {
+            SchemaTarget::Create { parent } => {
+                let req = self.api.create_database(plan.create_body(parent, legacy));
+                let database: NotionDatabase = self.transport.execute(&req, api::parse_json)?;
+                let id = if legacy {
+                    database.id.clone()
+                } else {
+                    self.api.resolve(&database)?
+                };
+                self.get_data_source(id.as_str())
+            }
+            SchemaTarget::Update { .. } if plan.is_empty() => Ok(plan
+                .existing()
+                .cloned()
+                .expect("an update has a data source")),
+            SchemaTarget::Update { data_source_id } => {
+                let req = self
+                    .api
+                    .update_data_source(data_source_id, plan.update_body(legacy));
+                self.transport.execute(&req, api::parse_json)
+            }
+        }
+    }
+
+    /// The data source titled exactly `title`, if there is one.
+    fn find_data_source(&self, title: &str) -> Result<Option<DataSource>> {
+        let mut found = Vec::new();
+        for hit in self.search(title).object(SearchObject::DataSource) {
+            if let SearchResult::DataSource(data_source) = hit? {
+                if data_source.title() == title {
+                    found.push(data_source.id);
+                }
+            }
+        }
+        match found.as_slice() {
+            [] => Ok(None),
+            [id] => self.get_data_source(id.as_str()).map(Some),
+            several => Err(Error::InvalidInput(format!(
+                "{} data sources are titled `{title}`; give the schema an `id`: {}",
+                several.len(),
+                several.join(", ")
+            ))),
+        }
+    }
+
+    /// Search pages and data sources shared with the integration by title;
+    /// see [`Search`]. An empty `query` matches everything.
+    pub fn search(&self, query: &str) -> Search<'_> {
+        Search::new(self, query)
+    }
+
+    /// Lazily iterate over the direct children of a block or page. An
+    /// invalid id is the first and only item.
+    pub fn block_children(&self, block_id: impl IntoNotionId) -> Paginator<'_, Block> {
+        match block_id.into_notion_id() {
+            Ok(id) => self.child_blocks(id.to_string()),
+            Err(err) => {
+                let mut err = Some(err);
+                Paginator::new(move |_| Err(err.take().expect("a failed paginator stops")))
+            }
+        }
+    }
+
+    /// [`block_children`](Self::block_children) for ids Notion returned.
+    pub(crate) fn child_blocks(&self, block_id: String) -> Paginator<'_, Block> {
+        Paginator::new(move |cursor| {
+            self.transport
+                .execute(&self.api.block_children(&block_id, cursor), api::parse_json)
+        })
+    }
+
+    /// Fetch a page's content as a tree, recursing into every block with
+    /// children; see [`ContentOptions`] for the defaults.
+    pub fn page_content(&self, page_id: impl IntoNotionId) -> Result<BlockTree> {
+        self.page_content_with(page_id, &ContentOptions::default())
+    }
+
+    /// [`page_content`](Self::page_content) with explicit depth, concurrency
+    /// and stop points.
+    pub fn page_content_with(
+        &self,
+        page_id: impl IntoNotionId,
+        options: &ContentOptions,
+    ) -> Result<BlockTree> {
+        tree::fetch(self, page_id.into_notion_id()?.as_str(), options)
+    }
+
+    /// Fetch a page and its whole content and render it as Markdown; see
+    /// [`MarkdownOptions`]. When an assets directory is set, Notion-hosted
+    /// files are downloaded into it.
+    pub fn export_markdown(
+        &self,
+        page_id: impl IntoNotionId,
+        options: &MarkdownOptions,
+    ) -> Result<String> {
+        let page_id = page_id.into_notion_id()?;
+        let page = self.get_page(&page_id)?;
+        let tree = self.page_content(&page_id)?;
+        let markdown = export::page_to_markdown(&page, &tree, options);
+        for asset in &markdown.assets {
+            if let Some(dir) = asset.path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            let bytes = self.transport.download(&asset.url)?;
+            fs::write(&asset.path, bytes)?;
+        }
+        Ok(markdown.text)
+    }
+
+    /// Create a page, then append its children; see [`append_children`].
+    ///
+    /// The page exists once the first request succeeds: if appending fails
+    /// afterwards, the error is returned and the page keeps the content
+    /// appended so far.
+    ///
+    /// [`append_children`]: Self::append_children
+    pub fn create_page(&self, page: &NewPage) -> Result<Page> {
+        let created: Page = self
+            .transport
+            .execute(&self.api.create_page(page.body()), api::parse_json)?;
+        create::append(self, &created.id, &page.children)?;
+        Ok(created)
+    }
+
+    /// Apply `update` to a page and return the page as it now is.
+    pub fn update_page(&self, page_id: impl IntoNotionId, update: &PageUpdate) -> Result<Page> {
+        let page_id = page_id.into_notion_id()?;
+        self.transport.execute(
+            &self.api.update_page(page_id.as_str(), update.body()),
+            api::parse_json,
+        )
+    }
+
+    /// Move a page to the trash.
+    pub fn archive_page(&self, page_id: impl IntoNotionId) -> Result<Page> {
+        self.update_page(page_id, &PageUpdate::new().with_in_trash(true))
+    }
+
+    /// Take a page back out of the trash.
+    pub fn restore_page(&self, page_id: impl IntoNotionId) -> Result<Page> {
+        self.update_page(page_id, &PageUpdate::new().with_in_trash(false))
+    }
+
+    /// Append blocks with their nested children under a block or page.
+    ///
+    /// Notion takes at most 100 blocks and two levels of nesting per
+    /// request, so longer or deeper content is sent in several requests, in
+    /// document order.
+    pub fn append_children(
+        &self,
+        block_id: impl IntoNotionId,
+        children: &[BlockNode],
+    ) -> Result<()> {
+        create::append(self, block_id.into_notion_id()?.as_str(), children)
+    }
+
+    /// Create a page from parsed Markdown; see [`parse_markdown`].
+    ///
+    /// Under a data source the schema is fetched first, to map front matter
+    /// onto typed property values.
+    ///
+    /// [`parse_markdown`]: super::parse_markdown
+    pub fn import_markdown(&self, parent: Parent, doc: &MarkdownDocument) -> Result<Page> {
+        let (parent, schema) = match parent {
+            Parent::DataSource {
+                data_source_id,
+                database_id,
+            } => {
+                // The schema's id, in case a database id was given.
+                let schema = self.get_data_source(&data_source_id)?;
+                let parent = Parent::DataSource {
+                    data_source_id: schema.id.clone(),
+                    database_id,
+                };
+                (parent, Some(schema))
+            }
+            parent => (parent, None),
+        };
+        self.create_page(&import::new_page(parent, doc, schema.as_ref())?)
+    }
+
+    /// [`import_markdown`](Self::import_markdown) under a data source
+    /// already fetched, so its schema is not fetched again.
+    pub fn import_markdown_into(
+        &self,
+        data_source: &DataSource,
+        doc: &MarkdownDocument,
+    ) -> Result<Page> {
+        let parent = Parent::DataSource {
+            data_source_id: data_source.id.clone(),
+            database_id: None,
+        };
+        self.create_page(&import::new_page(parent, doc, Some(data_source))?)
+    }
+
+    pub(crate) fn api(&self) -> &Api {
+        &self.api
+    }
+
+    pub(crate) fn transport(&self) -> &Transport {
+        &self.transport
+    }
+}
+
+impl Database for NotionClient {
+    type Record = Page;
+
+    fn get(&self, id: &str) -> Result<Self::Record> {
+        self.get_page(id)
+    }
+
+    /// Create the page when it has no id, under its `parent`. Otherwise
+    /// fetch the stored page and send only what differs; see
+    /// [`PageUpdate::diff`].
+    fn put(&self, rec: Self::Record) -> Result<()> {
+        if rec.id.is_empty() {
+            self.create_page(&NewPage::from(&rec))?;
+            return Ok(());
+        }
+        let update = PageUpdate::diff(&self.get_page(&rec.id)?, &rec);
+        if !update.is_empty() {
+            self.update_page(&rec.id, &update)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,703 @@
+//! Generate Rust types that mirror a data source schema.
+
+use std::collections::{BTreeSet, HashSet};
+use std::fmt::Write;
+
+use serde_json::Value;
+
+use super::data_source::{DataSource, PropertySchema};
+
+/// How [`generate_rust`] names what it emits.
+#[derive(Debug, Clone, Default)]
+pub struct CodegenOptions {
+    struct_name: Option<String>,
+}
+
+impl CodegenOptions {
+    /// Name the struct `name` instead of deriving it from the data source
+    /// title, e.g. `Task` for a data source called "Tasks".
+    pub fn with_struct_name(mut self, name: impl Into<String>) -> Self {
+        self.struct_name = Some(name.into());
+        self
+    }
+}
+
+/// A Rust module for the pages of `data_source`: a struct with one field
+/// per property, an enum per select, multi-select and status property, and
+/// conversions to and from Notion property values and JSON.
+///
+/// The output depends on the schema alone (properties in name order, title
+/// first; options in the order Notion lists them), so it can be checked in
+/// and regenerated in CI to catch drift. It uses nothing from the crate it
+/// lands in besides `swivel` and `serde_json`.
+///
+/// Options added in Notion after generation read as the enum's
+/// `Unknown(name)` variant; a property that was removed or changed type
+/// makes reading fail with [`Error::InvalidInput`](crate::Error) naming it.
+pub fn generate_rust(data_source: &DataSource, options: &CodegenOptions) -> String {
+    Generator::new(data_source, options).render()
+}
+
+/// What a field is read from and written as.
+enum Shape {
+    Title,
+    RichText,
+    Number,
+    /// Select, multi-select or status, with the name of its enum and the
+    /// variant names paired with option names.
+    Options {
+        kind: OptionKind,
+        enum_name: String,
+        variants: Vec<(String, String)>,
+    },
+    Date,
+    People,
+    Files,
+    Checkbox,
+    Url,
+    Email,
+    PhoneNumber,
+    Relation,
+    Formula,
+    Rollup,
+    UniqueId,
+    Verification,
+    CreatedTime,
+    LastEditedTime,
+    CreatedBy,
+    LastEditedBy,
+    /// A type this crate does not know yet, kept as the raw value.
+    Other,
+}
+
+#[derive(Clone, Copy)]
+enum OptionKind {
+    Select,
+    MultiSelect,
+    Status,
+}
+
+struct Field<'a> {
+    schema: &'a PropertySchema,
+    ident: String,
+    shape: Shape,
+}
+
+/// Names generated code imports or relies on, which generated types must
+/// not shadow.
+const RESERVED: &[&str] = &[
+    "BTreeMap",
+    "Box",
+    "DateValue",
+    "Err",
+    "Error",
+    "FileObject",
+    "FormulaValue",
+    "Map",
+    "None",
+    "Number",
+    "Ok",
+    "Option",
+    "Page",
+    "Property",
+    "PropertyValue",
+    "RelationRef",
+    "Result",
+    "Rollup",
+    "SelectOption",
+    "Self",
+    "Some",
+    "String",
+    "UniqueId",
+    "User",
+    "Value",
+    "Vec",
+    "Verification",
+];
+
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl",
+    "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "static", "struct", "super", "trait", "true", "try", "type", "typeof",
+    "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+struct Generator<'a> {
+    data_source: &'a DataSource,
+    struct_name: String,
+    fields: Vec<Field<'a>>,
+}
+
+impl<'a> Generator<'a> {
+    fn new(data_source: &'a DataSource, options: &CodegenOptions) -> Self {
+        let mut types = HashSet::new();
+        let title = data_source.title();
+        let base = options.struct_name.as_deref().unwrap_or(&title);
+        let struct_name = unique(type_name(base, "DataSource"), "Page", &mut types);
+
+        // Title first, then the rest by name.
+        let mut schemas: Vec<&PropertySchema> = data_source.properties.values().collect();
+        schemas.sort_by_key(|p| p.kind != "title");
+
+        let mut idents = HashSet::new();
+        let fields = schemas
+            .into_iter()
+            .map(|schema| Field {
+                schema,
+                ident: unique(field_name(&schema.name), "_", &mut idents),
+                shape: shape(schema, &mut types),
+            })
+            .collect();
+        Self {
+            data_source,
+            struct_name,
+            fields,
+        }
+    }
+
+    fn render(&self) -> String {
+        let mut out = String::new();
+        let title = self.data_source.title();
+        let _ = writeln!(
+            out,
+            "// @generated by `swivel codegen notion` from the {title:?} data source,\n\
+             // {}. Do not edit; regenerate when the schema changes.\n\
+             #![cfg_attr(rustfmt, rustfmt::skip)]\n",
+            self.data_source.id
+        );
+        out.push_str(&self.imports());
+        out.push('\n');
+        self.render_struct(&mut out);
+        for field in &self.fields {
+            if let Shape::Options {
+                kind,
+                enum_name,
+                variants,
+            } = &field.shape
+            {
+                out.push('\n');
+                render_enum(&mut out, &field.schema.name, *kind, enum_name, variants);
+            }
+        }
+        out.push_str(HELPERS);
+        out
+    }
+
+    fn imports(&self) -> String {
+        let mut std = BTreeSet::from(["collections::BTreeMap"]);
+        let mut json = BTreeSet::from(["Map", "Value"]);
+        let mut notion = BTreeSet::from(["Page", "Property", "PropertyValue"]);
+        for field in &self.fields {
+            match &field.shape {
+                Shape::Title | Shape::RichText => {
+                    notion.insert("rich_text");
+                }
+                Shape::Number => {
+                    json.insert("Number");
+                }
+                Shape::Options { .. } => {
+                    std.insert("fmt");
+                    notion.insert("SelectOption");
+                }
+                Shape::Date => {
+                    notion.insert("DateValue");
+                }
+                Shape::People | Shape::CreatedBy | Shape::LastEditedBy => {
+                    notion.insert("User");
+                }
+                Shape::Files => {
+                    notion.insert("FileObject");
+                }
+                Shape::Relation => {
+                    notion.insert("RelationRef");
+                }
+                Shape::Formula => {
+                    notion.insert("FormulaValue");
+                }
+                Shape::Rollup => {
+                    notion.insert("Rollup");
+                }
+                Shape::UniqueId => {
+                    notion.insert("UniqueId");
+                }
+                Shape::Verification => {
+                    notion.insert("Verification");
+                }
+                _ => {}
+            }
+        }
+        let mut out = String::new();
+        for item in std {
+            let _ = writeln!(out, "use std::{item};");
+        }
+        out.push('\n');
+        use_list(&mut out, "serde_json", json);
+        use_list(&mut out, "swivel::notion", notion);
+        out.push_str("use swivel::{Error, Result};\n");
+        out
+    }
+
+    fn render_struct(&self, out: &mut String) {
+        let name = &self.struct_name;
+        let title = self.data_source.title();
+        let _ = writeln!(out, "/// A page of the {title:?} data source.");
+        out.push_str("#[derive(Debug, Clone, PartialEq)]\n");
+        let _ = writeln!(out, "pub struct {name} {{");
+        for field in &self.fields {
+            let _ = writeln!(out, "    /// {}", describe(field));
+            let _ = writeln!(out, "    pub {}: {},", field.ident, rust_type(&field.shape));
+        }
+        out.push_str("}\n\n");
+
+        let _ = writeln!(out, "impl {name} {{");
+        let _ = writeln!(
+            out,
+            "    /// The data source these pages belong to.\n    \
+             pub const DATA_SOURCE_ID: &'static str = {:?};\n",
+            self.data_source.id
+        );
+        out.push_str(
+            "    /// Read the properties of `page`.\n    \
+             pub fn from_page(page: &Page) -> Result<Self> {\n        \
+             Self::from_properties(&page.properties)\n    \
+             }\n\n    \
+             /// Read a page's `properties` object, as Notion sends it.\n    \
+             pub fn from_json(properties: &Value) -> Result<Self> {\n        \
+             let properties: BTreeMap<String, Property> =\n            \
+             serde_json::from_value(properties.clone())?;\n        \
+             Self::from_properties(&properties)\n    \
+             }\n\n    \
+             pub fn from_properties(properties: &BTreeMap<String, Property>) -> Result<Self> {\n        \
+             Ok(Self {\n",
+        );
+        for field in &self.fields {
+            let Some((variant, read)) = read(&field.shape) else {
+                let _ = writeln!(
+                    out,
+                    "            {}: value(properties, {:?})?.clone(),",
+                    field.ident, field.schema.name
+                );
+                continue;
+            };
+            let _ = writeln!(
+                out,
+                "            {}: match value(properties, {:?})? {{",
+                field.ident, field.schema.name
+            );
+            let _ = writeln!(out, "                {variant} => {read},");
+            let _ = writeln!(
+                out,
+                "                other => return Err(mismatch({:?}, {:?}, other)),",
+                field.schema.name, field.schema.kind
+            );
+            out.push_str("            },\n");
+        }
+        out.push_str("        })\n    }\n\n");
+
+        out.push_str(
+            "    /// The writable properties, for creating or updating a page.\n    \
+             pub fn to_properties(&self) -> BTreeMap<String, PropertyValue> {\n",
+        );
+        let writes: Vec<_> = self
+            .fields
+            .iter()
+            .filter_map(|f| Some((f, write(&f.shape, &f.ident)?)))
+            .collect();
+        if writes.is_empty() {
+            out.push_str("        BTreeMap::new()\n");
+        } else {
+            out.push_str("        let mut properties = BTreeMap::new();\n");
+            for (field, value) in writes {
+                let _ = writeln!(
+                    out,
+                    "        properties.insert(\n            \
+                     {:?}.to_string(),\n            \
+                     {value},\n        \
+                     );",
+                    field.schema.name
+                );
+            }
+            if self.fields.iter().any(|f| matches!(f.shape, Shape::Files)) {
+                out.push_str(
+                    "        // Files Notion hosts cannot be written back, only uploaded anew.\n        \
+                     properties.retain(|_, value| !value.is_read_only());\n",
+                );
+            }
+            out.push_str("        properties\n");
+        }
+        out.push_str(
+            "    }\n\n    \
+             /// The writable properties as a `properties` object for the Notion API.\n    \
+             pub fn to_json(&self) -> Value {\n        \
+             let properties: Map<String, Value> = self\n            \
+             .to_properties()\n            \
+             .into_iter()\n            \
+             .map(|(name, value)| (name, serde_json::json!(Property::new(value))))\n            \
+             .collect();\n        \
+             Value::Object(properties)\n    \
+             }\n}\n",
+        );
+    }
+}
+
+/// A `use` of `items` from `path`, wrapped when it gets long.
+fn use_list(out: &mut String, path: &str, items: BTreeSet<&str>) {
+    let items: Vec<_> = items.into_iter().collect();
+    let line = format!("use {path}::{{{}}};", items.join(", "));
+    if line.len() <= 100 {
+        out.push_str(&line);
+        out.push('\n');
+        return;
+    }
+    let _ = writeln!(out, "use {path}::{{");
+    let mut row = String::new();
+    for item in items {
+        if !row.is_empty() && row.len() + item.len() + 2 > 96 {
+            let _ = writeln!(out, "    {}", row.trim_end());
+            row.clear();
+        }
+        let _ = write!(row, "{item}, ");
+    }
+    let _ = writeln!(out, "    {}", row.trim_end());
+    out.push_str("};\n");
+}
+
+const HELPERS: &str = "
+fn value<'a>(properties: &'a BTreeMap<String, Property>, name: &str) -> Result<&'a PropertyValue> {
+    properties
+        .get(name)
+        .map(|p| &p.value)
+        .ok_or_else(|| Error::InvalidInput(format!(\"page has no property named `{name}`\")))
+}
+
+fn mismatch(name: &str, expected: &str, found: &PropertyValue) -> Error {
+    Error::InvalidInput(format!(
+        \"property `{name}` is a {} property, expected {expected}\",
+        found.kind()
+    ))
+}
+";
+
+fn render_enum(
+    out: &mut String,
+    property: &str,
+    kind: OptionKind,
+    name: &str,
+    variants: &[(String, String)],
+) {
+    let kind = match kind {
+        OptionKind::Select => "select",
+        OptionKind::MultiSelect => "multi-select",
+        OptionKind::Status => "status",
+    };
+    let _ = writeln!(out, "/// The options of the {property:?} {kind} property.");
+    out.push_str("#[derive(Debug, Clone, PartialEq, Eq, Hash)]\n");
+    let _ = writeln!(out, "pub enum {name} {{");
+    for (variant, _) in variants {
+        let _ = writeln!(out, "    {variant},");
+    }
+    out.push_str(
+        "    /// An option added after this code was generated.\n    Unknown(String),\n}\n\n",
+    );
+
+    let _ = writeln!(out, "impl {name} {{");
+    out.push_str(
+        "    /// The option's name in Notion.\n    \
+         pub fn name(&self) -> &str {\n        \
+         match self {\n",
+    );
+    for (variant, option) in variants {
+        let _ = writeln!(out, "            Self::{variant} => {option:?},");
+    }
+    out.push_str(
+        "            Self::Unknown(name) => name,\n        \
+         }\n    \
+         }\n\n    \
+         pub fn from_name(name: &str) -> Self {\n        \
+         match name {\n",
+    );
+    for (variant, option) in variants {
+        let _ = writeln!(out, "            {option:?} => Self::{variant},");
+    }
+    out.push_str(
+        "            other => Self::Unknown(other.to_string()),\n        \
+         }\n    \
+         }\n}\n\n",
+    );
+    let _ = writeln!(out, "impl fmt::Display for {name} {{");
+    out.push_str(
+        "    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {\n        \
+         f.write_str(self.name())\n    \
+         }\n}\n",
+    );
+}
+
+fn shape(schema: &PropertySchema, types: &mut HashSet<String>) -> Shape {
+    let options = |kind: OptionKind, types: &mut HashSet<String>| {
+        let config = schema.extra.get(&schema.kind);
+        let names = config
+            .and_then(|c| c.get("options"))
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|o| o.get("name")?.as_str());
+        let mut seen = HashSet::from(["Unknown".to_string()]);
+        let variants = names
+            .enumerate()
+            .map(|(i, option)| {
+                let base = type_name(option, &format!("Option{}", i + 1));
+                (unique(base, "", &mut seen), option.to_string())
+            })
+            .collect();
+        let base = type_name(&schema.name, "Options");
+        Shape::Options {
+            kind,
+            enum_name: unique(base, "Option", types),
+            variants,
+        }
+    };
+    match schema.kind.as_str() {
+        "title" => Shape::Title,
+        "rich_text" => Shape::RichText,
+        "number" => Shape::Number,
+        "select" => options(OptionKind::Select, types),
+        "multi_select" => options(OptionKind::MultiSelect, types),
+        "status" => options(OptionKind::Status, types),
+        "date" => Shape::Date,
+        "people" => Shape::People,
+        "files" => Shape::Files,
+        "checkbox" => Shape::Checkbox,
+        "url" => Shape::Url,
+        "email" => Shape::Email,
+        "phone_number" => Shape::PhoneNumber,
+        "relation" => Shape::Relation,
+        "formula" => Shape::Formula,
+        "rollup" => Shape::Rollup,
+        "unique_id" => Shape::UniqueId,
+        "verification" => Shape::Verification,
+        "created_time" => Shape::CreatedTime,
+        "last_edited_time" => Shape::LastEditedTime,
+        "created_by" => Shape::CreatedBy,
+        "last_edited_by" => Shape::LastEditedBy,
+        _ => Shape::Other,
+    }
+}
+
+fn rust_type(shape: &Shape) -> String {
+    match shape {
+        Shape::Title | Shape::RichText => "String".into(),
+        Shape::Number => "Option<f64>".into(),
+        Shape::Options {
+            kind: OptionKind::MultiSelect,
+            enum_name,
+            ..
+        } => format!("Vec<{enum_name}>"),
+        Shape::Options { enum_name, .. } => format!("Option<{enum_name}>"),
+        Shape::Date => "Option<DateValue>".into(),
+        Shape::People => "Vec<User>".into(),
+        Shape::Files => "Vec<FileObject>".into(),
+        Shape::Checkbox => "bool".into(),
+        Shape::Url | Shape::Email | Shape::PhoneNumber => "Option<String>".into(),
+        Shape::Relation => "Vec<String>".into(),
+        Shape::Formula => "FormulaValue".into(),
+        Shape::Rollup => "Rollup".into(),
+        Shape::UniqueId => "UniqueId".into(),
+        Shape::Verification => "Option<Verification>".into(),
+        Shape::CreatedTime | Shape::LastEditedTime => "String".into(),
+        Shape::CreatedBy | Shape::LastEditedBy => "User".into(),
+        Shape::Other => "PropertyValue".into(),
+    }
+}
+
+/// The pattern that matches the property's value, and the expression that
+/// turns its binding `v` into the field; `None` for types kept as they are.
+fn read(shape: &Shape) -> Option<(&'static str, String)> {
+    Some(match shape {
+        Shape::Title => ("PropertyValue::Title(v)", "rich_text::plain_text(v)".into()),
+        Shape::RichText => (
+            "PropertyValue::RichText(v)",
+            "rich_text::plain_text(v)".into(),
+        ),
+        Shape::Number => (
+            "PropertyValue::Number(v)",
+            "v.as_ref().and_then(Number::as_f64)".into(),
+        ),
+        Shape::Options {
+            kind, enum_name, ..
+        } => match kind {
+            OptionKind::Select => (
+                "PropertyValue::Select(v)",
+                format!("v.as_ref().map(|o| {enum_name}::from_name(&o.name))"),
+            ),
+            OptionKind::MultiSelect => (
+                "PropertyValue::MultiSelect(v)",
+                format!("v.iter().map(|o| {enum_name}::from_name(&o.name)).collect()"),
+            ),
+            OptionKind::Status => (
+                "PropertyValue::Status(v)",
+                format!("v.as_ref().map(|o| {enum_name}::from_name(&o.name))"),
+            ),
+        },
+        Shape::Date => ("PropertyValue::Date(v)", "v.clone()".into()),
+        Shape::People => ("PropertyValue::People(v)", "v.clone()".into()),
+        Shape::Files => ("PropertyValue::Files(v)", "v.clone()".into()),
+        Shape::Checkbox => ("PropertyValue::Checkbox(v)", "*v".into()),
+        Shape::Url => ("PropertyValue::Url(v)", "v.clone()".into()),
+        Shape::Email => ("PropertyValue::Email(v)", "v.clone()".into()),
+        Shape::PhoneNumber => ("PropertyValue::PhoneNumber(v)", "v.clone()".into()),
+        Shape::Relation => (
+            "PropertyValue::Relation(v)",
+            "v.iter().map(|r| r.id.clone()).collect()".into(),
+        ),
+        Shape::Formula => ("PropertyValue::Formula(v)", "v.clone()".into()),
+        Shape::Rollup => ("PropertyValue::Rollup(v)", "v.clone()".into()),
+        Shape::UniqueId => ("PropertyValue::UniqueId(v)", "v.clone()".into()),
+        Shape::Verification => ("PropertyValue::Verification(v)", "v.clone()".into()),
+        Shape::CreatedTime => ("PropertyValue::CreatedTime(v)", "v.clone()".into()),
+        Shape::LastEditedTime => ("PropertyValue::LastEditedTime(v)", "v.clone()".into()),
+        Shape::CreatedBy => ("PropertyValue::CreatedBy(v)", "v.clone()".into()),
+        Shape::LastEditedBy => ("PropertyValue::LastEditedBy(v)", "v.clone()".into()),
+        Shape::Other => return None,
+    })
+}
+
+/// The value to send for field `ident`, or `None` when Notion computes it
+/// or, like a verification, only the app can set it.
+fn write(shape: &Shape, ident: &str) -> Option<String> {
+    let field = format!("self.{ident}");
+    Some(match shape {
+        Shape::Title => format!("PropertyValue::Title(rich_text::from_plain_text(&{field}))"),
+        Shape::RichText => {
+            format!("PropertyValue::RichText(rich_text::from_plain_text(&{field}))")
+        }
+        Shape::Number => format!("PropertyValue::Number({field}.and_then(Number::from_f64))"),
+        Shape::Options { kind, .. } => match kind {
+            OptionKind::Select => format!(
+                "PropertyValue::Select({field}.as_ref().map(|o| SelectOption::named(o.name())))"
+            ),
+            OptionKind::MultiSelect => format!(
+                "PropertyValue::MultiSelect(\n                \
+                 {field}.iter().map(|o| SelectOption::named(o.name())).collect(),\n            \
+                 )"
+            ),
+            OptionKind::Status => format!(
+                "PropertyValue::Status({field}.as_ref().map(|o| SelectOption::named(o.name())))"
+            ),
+        },
+        Shape::Date => format!("PropertyValue::Date({field}.clone())"),
+        Shape::People => format!("PropertyValue::People({field}.clone())"),
+        Shape::Files => format!("PropertyValue::Files({field}.clone())"),
+        Shape::Checkbox => format!("PropertyValue::Checkbox({field})"),
+        Shape::Url => format!("PropertyValue::Url({field}.clone())"),
+        Shape::Email => format!("PropertyValue::Email({field}.clone())"),
+        Shape::PhoneNumber => format!("PropertyValue::PhoneNumber({field}.clone())"),
+        Shape::Relation => format!(
+            "PropertyValue::Relation(\n                \
+             {field}.iter().map(|id| RelationRef {{ id: id.clone() }}).collect(),\n            \
+             )"
+        ),
+        _ => return None,
+    })
+}
+
+/// The field's doc line: the property name and type, and where a relation
+/// points.
+fn describe(field: &Field) -> String {
+    let schema = field.schema;
+    let kind = schema.kind.replace('_', " ");
+    let mut doc = format!("{:?}: {kind}", schema.name);
+    if let Shape::Relation = field.shape {
+        let config = schema.extra.get("relation");
+        let target = ["data_source_id", "database_id"]
+            .iter()
+            .find_map(|key| config?.get(key)?.as_str());
+        if let Some(target) = target {
+            let _ = write!(doc, " to `{target}`");
+        }
+    }
+    if write(&field.shape, "").is_none() {
+        doc.push_str(", read-only");
+    }
+    doc.push('.');
+    doc
+}
+
+/// The words of `name`, split at anything but ASCII letters and digits and
+/// at lower-to-upper case changes.
+fn words(name: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut prev_lower = false;
+    for c in name.chars() {
+        if !c.is_ascii_alphanumeric() {
+            words.extend((!word.is_empty()).then(|| std::mem::take(&mut word)));
+            prev_lower = false;
+            continue;
+        }
+        if c.is_ascii_uppercase() && prev_lower {
+            words.push(std::mem::take(&mut word));
+        }
+        prev_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        word.push(c);
+    }
+    words.extend((!word.is_empty()).then_some(word));
+    words
+}
+
+/// `name` in UpperCamelCase, or `fallback` if nothing is left of it.
+fn type_name(name: &str, fallback: &str) -> String {
+    let mut out: String = words(name)
+        .iter()
+        .map(|w| {
+            let mut chars = w.chars();
+            let first = chars.next().map(|c| c.to_ascii_uppercase());
+            first
+                .into_iter()
+                .chain(chars.map(|c| c.to_ascii_lowercase()))
+                .collect::<String>()
+        })
+        .collect();
+    if out.is_empty() {
+        return fallback.to_string();
+    }
+    if out.starts_with(|c: char| c.is_ascii_digit()) {
+        out.insert(0, 'V');
+    }
+    out
+}
+
+/// `name` in snake_case, made a valid field name.
+fn field_name(name: &str) -> String {
+    let mut out = words(name)
+        .iter()
+        .map(|w| w.to_ascii_lowercase())
+        .collect::<Vec<_>>()
+        .join("_");
+    if out.is_empty() {
+        out = "property".into();
+    } else if out.starts_with(|c: char| c.is_ascii_digit()) {
+        out.insert_str(0, "property_");
+    }
+    match out.as_str() {
+        "self" | "super" | "crate" => out + "_",
+        word if KEYWORDS.contains(&word) => format!("r#{out}"),
+        _ => out,
+    }
+}
+
+/// `name`, suffixed until it is neither reserved nor already in `taken`.
+fn unique(name: String, suffix: &str, taken: &mut HashSet<String>) -> String {
+    let mut candidate = name.clone();
+    if RESERVED.contains(&candidate.as_str()) {
+        candidate.push_str(suffix);
+    }
+    let base = candidate.clone();
+    let mut n = 2;
+    while taken.contains(&candidate) || RESERVED.contains(&candidate.as_str()) {
+        candidate = format!("{base}{n}");
+        n += 1;
+    }
+    taken.insert(candidate.clone());
+    candidate
+}
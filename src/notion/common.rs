@@ -0,0 +1,240 @@
+//! Small objects shared by pages, properties and blocks.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::keyed::keyed_enum;
+
+/// A user or bot, as referenced by `created_by`, `people` and mentions.
+///
+/// Partial user objects carry only `id`; everything else Notion sends is
+/// kept as-is and exposed through accessors, so users round-trip unchanged.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct User {
+    pub id: String,
+    /// Everything else Notion sent (`object`, `name`, `person`, `bot`, ...).
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl User {
+    /// A partial user reference, as used when writing `people` values.
+    pub fn id(id: impl Into<String>) -> Self {
+        let mut extra = Map::new();
+        extra.insert("object".into(), Value::from("user"));
+        Self {
+            id: id.into(),
+            extra,
+        }
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.extra.get("name")?.as_str()
+    }
+
+    pub fn avatar_url(&self) -> Option<&str> {
+        self.extra.get("avatar_url")?.as_str()
+    }
+
+    /// `"person"` or `"bot"`; `None` for partial users.
+    pub fn kind(&self) -> Option<&str> {
+        self.extra.get("type")?.as_str()
+    }
+
+    pub fn email(&self) -> Option<&str> {
+        self.extra.get("person")?.get("email")?.as_str()
+    }
+}
+
+/// A start date (or datetime) with an optional end, in ISO 8601.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DateValue {
+    pub start: String,
+    #[serde(default)]
+    pub end: Option<String>,
+    #[serde(default)]
+    pub time_zone: Option<String>,
+}
+
+impl DateValue {
+    pub fn new(start: impl Into<String>) -> Self {
+        Self {
+            start: start.into(),
+            end: None,
+            time_zone: None,
+        }
+    }
+}
+
+/// A file attached to a page, block or property: uploaded to Notion,
+/// linked externally, or referenced by a pending file upload.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileObject {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(flatten)]
+    pub source: FileSource,
+}
+
+impl FileObject {
+    /// Link an external URL.
+    pub fn external(url: impl Into<String>) -> Self {
+        Self {
+            name: None,
+            source: FileSource::External(ExternalFile { url: url.into() }),
+        }
+    }
+
+    /// Whether Notion hosts the file. Such files cannot be written back
+    /// through the API, only uploaded anew.
+    pub fn is_notion_hosted(&self) -> bool {
+        matches!(self.source, FileSource::File(_))
+    }
+
+    /// Where the file can be downloaded, if it has a URL.
+    pub fn url(&self) -> Option<&str> {
+        match &self.source {
+            FileSource::External(f) => Some(&f.url),
+            FileSource::File(f) => Some(&f.url),
+            _ => None,
+        }
+    }
+}
+
+keyed_enum! {
+    /// Where a [`FileObject`] lives.
+    pub enum FileSource {
+        External(ExternalFile) = "external",
+        /// Hosted by Notion; the URL expires after an hour.
+        File(HostedFile) = "file",
+        FileUpload(FileUpload) = "file_upload",
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExternalFile {
+    pub url: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HostedFile {
+    pub url: String,
+    #[serde(default)]
+    pub expiry_time: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileUpload {
+    pub id: String,
+}
+
+/// A page cover is a file object.
+pub type Cover = FileObject;
+
+keyed_enum! {
+    /// A page or callout icon.
+    pub enum Icon {
+        Emoji(String) = "emoji",
+        External(ExternalFile) = "external",
+        File(HostedFile) = "file",
+        CustomEmoji(CustomEmoji) = "custom_emoji",
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomEmoji {
+    pub id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+/// What a page, data source or block belongs to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "Map<String, Value>", into = "Map<String, Value>")]
+pub enum Parent {
+    /// A row of a data source. `database_id` is the data source's database.
+    DataSource {
+        data_source_id: String,
+        database_id: Option<String>,
+    },
+    Database {
+        database_id: String,
+    },
+    Page {
+        page_id: String,
+    },
+    Block {
+        block_id: String,
+    },
+    Workspace,
+    /// A parent type this crate does not know yet, kept verbatim.
+    Unknown(Map<String, Value>),
+}
+
+impl TryFrom<Map<String, Value>> for Parent {
+    type Error = String;
+
+    fn try_from(map: Map<String, Value>) -> Result<Self, Self::Error> {
+        let field = |name: &str| map.get(name).and_then(Value::as_str).map(String::from);
+        let required =
+            |name: &str| field(name).ok_or_else(|| format!("parent is missing `{name}`"));
+        Ok(match map.get("type").and_then(Value::as_str) {
+            Some("data_source_id") => Parent::DataSource {
+                data_source_id: required("data_source_id")?,
+                database_id: field("database_id"),
+            },
+            Some("database_id") => Parent::Database {
+                database_id: required("database_id")?,
+            },
+            Some("page_id") => Parent::Page {
+                page_id: required("page_id")?,
+            },
+            Some("block_id") => Parent::Block {
+                block_id: required("block_id")?,
+            },
+            Some("workspace") => Parent::Workspace,
+            _ => Parent::Unknown(map),
+        })
+    }
+}
+
+impl From<Parent> for Map<String, Value> {
+    fn from(parent: Parent) -> Self {
+        let mut map = Map::new();
+        let mut set = |k: &str, v: Value| {
+            map.insert(k.to_string(), v);
+        };
+        match parent {
+            Parent::DataSource {
+                data_source_id,
+                database_id,
+            } => {
+                set("type", "data_source_id".into());
+                set("data_source_id", data_source_id.into());
+                if let Some(database_id) = database_id {
+                    set("database_id", database_id.into());
+                }
+            }
+            Parent::Database { database_id } => {
+                set("type", "database_id".into());
+                set("database_id", database_id.into());
+            }
+            Parent::Page { page_id } => {
+                set("type", "page_id".into());
+                set("page_id", page_id.into());
+            }
+            Parent::Block { block_id } => {
+                set("type", "block_id".into());
+                set("block_id", block_id.into());
+            }
+            Parent::Workspace => {
+                set("type", "workspace".into());
+                set("workspace", true.into());
+            }
+            Parent::Unknown(raw) => return raw,
+        }
+        map
+    }
+}
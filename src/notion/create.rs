@@ -0,0 +1,157 @@
+use std::collections::BTreeMap;
+
+use serde_json::{json, Map, Value};
+
+use super::api;
+use super::common::{Cover, Icon, Parent};
+use super::pagination::List;
+use super::property::{Property, PropertyValue};
+use super::{Block, BlockNode, NotionClient};
+use crate::Result;
+
+/// Notion accepts at most this many blocks in one children array.
+pub const MAX_CHILDREN: usize = 100;
+
+/// Notion accepts at most this many blocks, at any depth, per request.
+const MAX_BLOCKS_PER_REQUEST: usize = 1000;
+
+/// A page to create with [`NotionClient::create_page`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewPage {
+    pub parent: Parent,
+    /// Property values keyed by property name. Under a page parent only the
+    /// title (named `"title"`) is allowed.
+    pub properties: BTreeMap<String, PropertyValue>,
+    pub icon: Option<Icon>,
+    pub cover: Option<Cover>,
+    /// Content, appended after the page is created.
+    pub children: Vec<BlockNode>,
+}
+
+impl NewPage {
+    pub fn new(parent: Parent) -> Self {
+        Self {
+            parent,
+            properties: BTreeMap::new(),
+            icon: None,
+            cover: None,
+            children: Vec::new(),
+        }
+    }
+
+    /// Set the property called `name`.
+    pub fn with_property(mut self, name: impl Into<String>, value: PropertyValue) -> Self {
+        self.properties.insert(name.into(), value);
+        self
+    }
+
+    pub fn with_icon(mut self, icon: Icon) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    pub fn with_cover(mut self, cover: Cover) -> Self {
+        self.cover = Some(cover);
+        self
+    }
+
+    pub fn with_children(mut self, children: Vec<BlockNode>) -> Self {
+        self.children = children;
+        self
+    }
+
+    /// The `POST /pages` body, without children.
+    pub(crate) fn body(&self) -> Value {
+        // Pages read back name both the data source and its database, but
+        // a create takes exactly one parent id.
+        let parent = match &self.parent {
+            Parent::DataSource { data_source_id, .. } => Parent::DataSource {
+                data_source_id: data_source_id.clone(),
+                database_id: None,
+            },
+            parent => parent.clone(),
+        };
+        let properties: Map<String, Value> = self
+            .properties
+            .iter()
+            .map(|(name, value)| (name.clone(), json!(Property::new(value.clone()))))
+            .collect();
+        let mut body = json!({ "parent": parent, "properties": properties });
+        if let Some(icon) = &self.icon {
+            body["icon"] = json!(icon);
+        }
+        if let Some(cover) = &self.cover {
+            body["cover"] = json!(cover);
+        }
+        body
+    }
+}
+
+/// Append `nodes` under `block_id`, in as few requests as Notion's limits
+/// allow: batches of at most [`MAX_CHILDREN`] blocks, each carrying up to
+/// two levels of children. Deeper or wider content is appended afterwards
+/// under the blocks just created.
+pub(crate) fn append(client: &NotionClient, block_id: &str, nodes: &[BlockNode]) -> Result<()> {
+    let mut start = 0;
+    while start < nodes.len() {
+        let mut batch = Vec::new();
+        let mut inlined = Vec::new();
+        let mut blocks = 0;
+        for node in &nodes[start..] {
+            let (count, size) = inline_prefix(node);
+            if batch.len() == MAX_CHILDREN
+                || (!batch.is_empty() && blocks + size > MAX_BLOCKS_PER_REQUEST)
+            {
+                break;
+            }
+            batch.push(block_json(&node.block, &node.children[..count])?);
+            inlined.push(count);
+            blocks += size;
+        }
+        let request = client.api().append_block_children(block_id, batch);
+        let created: List<Block> = client.transport().execute(&request, api::parse_json)?;
+        for ((node, count), block) in nodes[start..].iter().zip(&inlined).zip(&created.results) {
+            if *count < node.children.len() {
+                append(client, &block.id, &node.children[*count..])?;
+            }
+        }
+        start += inlined.len();
+    }
+    Ok(())
+}
+
+/// How many of `node`'s children go in the same request as `node`, and the
+/// number of blocks that makes: a prefix of children whose own children
+/// are leaves.
+fn inline_prefix(node: &BlockNode) -> (usize, usize) {
+    let mut size = 1;
+    let mut count = 0;
+    for child in node.children.iter().take(MAX_CHILDREN) {
+        let child_size = 1 + child.children.len();
+        let fits = child.children.len() <= MAX_CHILDREN
+            && child.children.iter().all(|c| c.children.is_empty())
+            && size + child_size <= MAX_BLOCKS_PER_REQUEST;
+        if !fits {
+            break;
+        }
+        size += child_size;
+        count += 1;
+    }
+    (count, size)
+}
+
+/// The creation form of a block, `{"type": kind, kind: {..., "children"}}`.
+fn block_json(block: &Block, children: &[BlockNode]) -> Result<Value> {
+    let mut payload = block.content.keyed_value()?;
+    if !children.is_empty() {
+        let children = children
+            .iter()
+            .map(|child| block_json(&child.block, &child.children))
+            .collect::<Result<Vec<_>>>()?;
+        if let Value::Object(map) = &mut payload {
+            map.insert("children".into(), Value::Array(children));
+        }
+    }
+    let kind = block.content.kind();
+    Ok(json!({ "object": "block", "type": kind, kind: payload }))
+}
@@ -0,0 +1,101 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::common::Parent;
+use super::rich_text::{self, RichText};
+use crate::{Error, Result};
+
+/// A Notion data source: its title and property schema.
+///
+/// The schema is what [`Filter::check`](super::Filter::check) validates
+/// filters against.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DataSource {
+    pub id: String,
+    #[serde(default)]
+    pub title: Vec<RichText>,
+    /// Property definitions keyed by property name.
+    #[serde(default)]
+    pub properties: BTreeMap<String, PropertySchema>,
+    /// The database this data source belongs to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<Parent>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Everything else Notion sent (`object`, `parent`, `created_time`, ...).
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl DataSource {
+    /// The plain text of the data source's title.
+    pub fn title(&self) -> String {
+        rich_text::plain_text(&self.title)
+    }
+
+    /// The type of the property called `name`, e.g. `"select"`.
+    pub fn property_type(&self, name: &str) -> Result<&str> {
+        self.properties
+            .get(name)
+            .map(|p| p.kind.as_str())
+            .ok_or_else(|| {
+                Error::InvalidInput(format!(
+                    "data source `{}` has no property named `{name}`",
+                    self.title()
+                ))
+            })
+    }
+}
+
+/// A Notion database: a container for one or more data sources, as
+/// returned by [`NotionClient::get_database`](super::NotionClient::get_database).
+///
+/// Before API version 2025-09-03 a database had no data sources and held
+/// its schema itself; its `properties` then end up in `extra`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NotionDatabase {
+    pub id: String,
+    #[serde(default)]
+    pub title: Vec<RichText>,
+    /// The data sources in this database, in Notion's order.
+    #[serde(default)]
+    pub data_sources: Vec<DataSourceRef>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<Parent>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Everything else Notion sent (`object`, `is_inline`, ...).
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl NotionDatabase {
+    /// The plain text of the database's title.
+    pub fn title(&self) -> String {
+        rich_text::plain_text(&self.title)
+    }
+}
+
+/// A data source as listed on its database.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DataSourceRef {
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+}
+
+/// The definition of one data source property.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PropertySchema {
+    pub id: String,
+    pub name: String,
+    /// The property type, e.g. `"number"` or `"multi_select"`.
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// The type-specific configuration (select options, number format, ...),
+    /// kept as Notion sent it.
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
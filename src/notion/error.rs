@@ -0,0 +1,93 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::Error;
+
+/// The JSON body Notion sends with every non-2xx response.
+///
+/// ```json
+/// { "object": "error", "status": 404, "code": "object_not_found",
+///   "message": "Could not find page ...", "request_id": "..." }
+/// ```
+#[derive(Debug, Default, Deserialize)]
+struct ErrorBody {
+    #[serde(default)]
+    code: String,
+    #[serde(default)]
+    message: String,
+    request_id: Option<String>,
+}
+
+/// Turn a failed Notion response into a typed [`Error`].
+///
+/// The `code` in the body wins over the HTTP status; the status is only
+/// used when the body is missing or not Notion's error shape.
+pub(crate) fn from_response(status: u16, retry_after: Option<Duration>, body: &str) -> Error {
+    let ErrorBody {
+        code,
+        message,
+        request_id,
+    } = serde_json::from_str(body).unwrap_or_default();
+    let message = if message.is_empty() {
+        body.trim().to_string()
+    } else {
+        message
+    };
+
+    match (code.as_str(), status) {
+        ("unauthorized", _) | (_, 401) => Error::Unauthorized {
+            message,
+            request_id,
+        },
+        ("restricted_resource", _) | (_, 403) => Error::RestrictedResource {
+            message,
+            request_id,
+        },
+        ("object_not_found", _) | (_, 404) => Error::ObjectNotFound {
+            message,
+            request_id,
+        },
+        ("rate_limited", _) | (_, 429) => Error::RateLimited {
+            retry_after,
+            request_id,
+        },
+        ("conflict_error", _) | (_, 409) => Error::Conflict {
+            message,
+            request_id,
+        },
+        (
+            "invalid_json"
+            | "invalid_request_url"
+            | "invalid_request"
+            | "invalid_grant"
+            | "validation_error"
+            | "missing_version",
+            _,
+        )
+        | (_, 400) => Error::Validation {
+            code: if code.is_empty() {
+                "bad_request".into()
+            } else {
+                code
+            },
+            message,
+            request_id,
+        },
+        (_, 500..=599) => Error::Server {
+            status,
+            code: if code.is_empty() {
+                "server_error".into()
+            } else {
+                code
+            },
+            message,
+            request_id,
+        },
+        _ => Error::Http {
+            status,
+            message,
+            request_id,
+        },
+    }
+}
@@ -0,0 +1,452 @@
+//! Render pages as GitHub-flavored Markdown.
+
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Value};
+
+use super::block::{Block, BlockContent, FileBlock};
+use super::common::{FileSource, Icon, User};
+use super::property::{FormulaValue, PropertyValue, RollupValue};
+use super::rich_text::{self, RichText};
+use super::{BlockNode, BlockTree, Page};
+
+/// How pages are rendered to Markdown.
+#[derive(Debug, Clone)]
+pub struct MarkdownOptions {
+    front_matter: bool,
+    assets_dir: Option<PathBuf>,
+}
+
+impl Default for MarkdownOptions {
+    fn default() -> Self {
+        Self {
+            front_matter: true,
+            assets_dir: None,
+        }
+    }
+}
+
+impl MarkdownOptions {
+    /// Start with the page's metadata and properties as YAML front matter
+    /// (the default); otherwise start with the title as a heading.
+    pub fn with_front_matter(mut self, front_matter: bool) -> Self {
+        self.front_matter = front_matter;
+        self
+    }
+
+    /// Link Notion-hosted images and files to local copies in `dir` instead
+    /// of their URLs, which expire after an hour. The files to fetch are
+    /// listed in [`Markdown::assets`].
+    pub fn with_assets_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.assets_dir = Some(dir.into());
+        self
+    }
+}
+
+/// A rendered page.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Markdown {
+    pub text: String,
+    /// Files the text links to locally, still to be downloaded.
+    pub assets: Vec<Asset>,
+}
+
+/// A Notion-hosted file and the local path the Markdown links to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Asset {
+    pub url: String,
+    pub path: PathBuf,
+}
+
+/// Render `page` and its content.
+pub fn page_to_markdown(page: &Page, tree: &BlockTree, options: &MarkdownOptions) -> Markdown {
+    let mut renderer = Renderer::new(options);
+    let mut text = if options.front_matter {
+        front_matter(page)
+    } else {
+        format!("# {}\n\n", rich_text::to_markdown(&title_runs(page)))
+    };
+    text.push_str(&renderer.blocks(&tree.blocks));
+    finish(text, renderer.assets)
+}
+
+/// Render a block tree on its own.
+pub fn blocks_to_markdown(tree: &BlockTree, options: &MarkdownOptions) -> Markdown {
+    let mut renderer = Renderer::new(options);
+    let text = renderer.blocks(&tree.blocks);
+    finish(text, renderer.assets)
+}
+
+fn finish(mut text: String, assets: Vec<Asset>) -> Markdown {
+    let trimmed = text.trim_end().len();
+    text.truncate(trimmed);
+    text.push('\n');
+    Markdown { text, assets }
+}
+
+fn title_runs(page: &Page) -> Vec<RichText> {
+    page.properties
+        .values()
+        .find_map(|p| p.value.as_title())
+        .map(<[RichText]>::to_vec)
+        .unwrap_or_default()
+}
+
+fn front_matter(page: &Page) -> String {
+    let mut out = String::from("---\n");
+    let mut line = |key: &str, value: Value| {
+        out.push_str(&format!("{}: {value}\n", yaml_key(key)));
+    };
+    line("title", json!(page.title()));
+    line("id", json!(page.id));
+    if let Some(url) = &page.url {
+        line("url", json!(url));
+    }
+    line("created_time", json!(page.created_time));
+    line("last_edited_time", json!(page.last_edited_time));
+    let properties: Vec<_> = page
+        .properties
+        .iter()
+        .filter(|(_, p)| !matches!(p.value, PropertyValue::Title(_)))
+        .collect();
+    if !properties.is_empty() {
+        out.push_str("properties:\n");
+        for (name, property) in properties {
+            out.push_str(&format!(
+                "  {}: {}\n",
+                yaml_key(name),
+                property_value(&property.value)
+            ));
+        }
+    }
+    out.push_str("---\n\n");
+    out
+}
+
+/// JSON scalars and flow collections are valid YAML, so values are written
+/// as JSON; keys are quoted unless they are plain identifiers.
+fn yaml_key(key: &str) -> String {
+    let plain = key.starts_with(|c: char| c.is_ascii_alphabetic())
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if plain {
+        key.to_string()
+    } else {
+        json!(key).to_string()
+    }
+}
+
+/// A property value reduced to what a reader of the front matter wants:
+/// option names rather than option objects, plain text rather than runs.
+pub(crate) fn property_value(value: &PropertyValue) -> Value {
+    let user = |u: &User| json!(u.name().unwrap_or(&u.id));
+    match value {
+        PropertyValue::Title(runs) | PropertyValue::RichText(runs) => {
+            json!(rich_text::plain_text(runs))
+        }
+        PropertyValue::Number(n) => json!(n),
+        PropertyValue::Select(o) | PropertyValue::Status(o) => json!(o.as_ref().map(|o| &o.name)),
+        PropertyValue::MultiSelect(os) => json!(os.iter().map(|o| &o.name).collect::<Vec<_>>()),
+        PropertyValue::Date(d) => match d {
+            Some(d) if d.end.is_some() => json!({ "start": d.start, "end": d.end }),
+            Some(d) => json!(d.start),
+            None => Value::Null,
+        },
+        PropertyValue::People(users) => Value::Array(users.iter().map(user).collect()),
+        PropertyValue::Files(files) => {
+            json!(files.iter().filter_map(|f| f.url()).collect::<Vec<_>>())
+        }
+        PropertyValue::Checkbox(b) => json!(b),
+        PropertyValue::Url(s) | PropertyValue::Email(s) | PropertyValue::PhoneNumber(s) => json!(s),
+        PropertyValue::Formula(f) => match f {
+            FormulaValue::String(s) => json!(s),
+            FormulaValue::Number(n) => json!(n),
+            FormulaValue::Boolean(b) => json!(b),
+            FormulaValue::Date(d) => json!(d.as_ref().map(|d| &d.start)),
+            FormulaValue::Unknown { value, .. } => value.clone(),
+        },
+        PropertyValue::Relation(refs) => json!(refs.iter().map(|r| &r.id).collect::<Vec<_>>()),
+        PropertyValue::Rollup(rollup) => match &rollup.value {
+            RollupValue::Number(n) => json!(n),
+            RollupValue::Date(d) => json!(d.as_ref().map(|d| &d.start)),
+            RollupValue::Array(items) => Value::Array(items.iter().map(property_value).collect()),
+            RollupValue::Unknown { value, .. } => value.clone(),
+        },
+        PropertyValue::UniqueId(id) => json!(id.to_string()),
+        PropertyValue::Verification(v) => json!(v.as_ref().map(|v| &v.state)),
+        PropertyValue::CreatedTime(t) | PropertyValue::LastEditedTime(t) => json!(t),
+        PropertyValue::CreatedBy(u) | PropertyValue::LastEditedBy(u) => user(u),
+        PropertyValue::Unknown { value, .. } => value.clone(),
+    }
+}
+
+struct Renderer<'a> {
+    assets_dir: Option<&'a Path>,
+    assets: Vec<Asset>,
+}
+
+impl<'a> Renderer<'a> {
+    fn new(options: &'a MarkdownOptions) -> Self {
+        Self {
+            assets_dir: options.assets_dir.as_deref(),
+            assets: Vec::new(),
+        }
+    }
+
+    /// Render sibling blocks. Items of the same list are joined by a single
+    /// newline so the list stays tight; everything else by a blank line.
+    fn blocks(&mut self, nodes: &[BlockNode]) -> String {
+        let mut out = String::new();
+        let mut number = 0;
+        let mut previous: Option<ListKind> = None;
+        for node in nodes {
+            let kind = ListKind::of(&node.block.content);
+            number = if kind == Some(ListKind::Numbered) && previous == kind {
+                number + 1
+            } else {
+                1
+            };
+            let rendered = self.block(node, number);
+            if rendered.is_empty() {
+                continue;
+            }
+            if !out.is_empty() {
+                out.push_str(if kind.is_some() && kind == previous {
+                    "\n"
+                } else {
+                    "\n\n"
+                });
+            }
+            out.push_str(&rendered);
+            previous = kind;
+        }
+        out
+    }
+
+    fn block(&mut self, node: &BlockNode, number: usize) -> String {
+        let block = &node.block;
+        let text = |runs: &[RichText]| rich_text::to_markdown(runs);
+        match &block.content {
+            BlockContent::Paragraph(p) => self.with_children(text(&p.rich_text), node),
+            BlockContent::Heading1(h) => {
+                self.with_children(format!("# {}", text(&h.rich_text)), node)
+            }
+            BlockContent::Heading2(h) => {
+                self.with_children(format!("## {}", text(&h.rich_text)), node)
+            }
+            BlockContent::Heading3(h) => {
+                self.with_children(format!("### {}", text(&h.rich_text)), node)
+            }
+            BlockContent::BulletedListItem(item) => {
+                self.list_item("- ", &text(&item.rich_text), node)
+            }
+            BlockContent::NumberedListItem(item) => {
+                self.list_item(&format!("{number}. "), &text(&item.rich_text), node)
+            }
+            BlockContent::ToDo(todo) => {
+                let check = if todo.checked { "x" } else { " " };
+                let line = format!("[{check}] {}", text(&todo.rich_text));
+                self.list_item("- ", &line, node)
+            }
+            BlockContent::Toggle(toggle) => {
+                let children = self.blocks(&node.children);
+                format!(
+                    "<details>\n<summary>{}</summary>\n\n{children}\n\n</details>",
+                    text(&toggle.rich_text)
+                )
+            }
+            BlockContent::Quote(quote) => {
+                let body = self.with_children(text(&quote.rich_text), node);
+                prefix_lines(&body, "> ")
+            }
+            BlockContent::Callout(callout) => {
+                let icon = match &callout.icon {
+                    Some(Icon::Emoji(emoji)) => format!("{emoji} "),
+                    _ => String::new(),
+                };
+                let body = self.with_children(format!("{icon}{}", text(&callout.rich_text)), node);
+                prefix_lines(&body, "> ")
+            }
+            BlockContent::Code(code) => {
+                let source = rich_text::plain_text(&code.rich_text);
+                let longest = source
+                    .lines()
+                    .map(|l| l.trim_start())
+                    .filter(|l| l.starts_with("```"))
+                    .map(|l| l.chars().take_while(|&c| c == '`').count())
+                    .max()
+                    .unwrap_or(0);
+                let fence = "`".repeat(longest.max(2) + 1);
+                let language = match code.language.as_str() {
+                    "plain text" => "",
+                    other => other,
+                };
+                format!("{fence}{language}\n{source}\n{fence}")
+            }
+            BlockContent::Equation(eq) => format!("$$\n{}\n$$", eq.expression),
+            BlockContent::Divider(_) => "---".to_string(),
+            BlockContent::Bookmark(b) | BlockContent::Embed(b) => {
+                let label = if b.caption.is_empty() {
+                    b.url.clone()
+                } else {
+                    text(&b.caption)
+                };
+                format!("[{label}]({})", rich_text::link_destination(&b.url))
+            }
+            BlockContent::Image(image) => {
+                let src = self.file_src(block, image);
+                format!(
+                    "![{}]({})",
+                    rich_text::plain_text(&image.caption),
+                    rich_text::link_destination(&src)
+                )
+            }
+            BlockContent::Video(file)
+            | BlockContent::File(file)
+            | BlockContent::Pdf(file)
+            | BlockContent::Audio(file) => {
+                let src = self.file_src(block, file);
+                let label = if !file.caption.is_empty() {
+                    text(&file.caption)
+                } else if let Some(name) = &file.file.name {
+                    name.clone()
+                } else {
+                    file_name(&src).to_string()
+                };
+                format!("[{label}]({})", rich_text::link_destination(&src))
+            }
+            BlockContent::Table(table) => table_markdown(table.has_column_header, &node.children),
+            BlockContent::ColumnList(_)
+            | BlockContent::Column(_)
+            | BlockContent::SyncedBlock(_) => self.blocks(&node.children),
+            BlockContent::ChildPage(child) | BlockContent::ChildDatabase(child) => {
+                format!("[{}]({})", child.title, notion_url(&block.id))
+            }
+            BlockContent::LinkToPage(target) => {
+                let id = ["page_id", "database_id"]
+                    .iter()
+                    .find_map(|key| target.get(key)?.as_str())
+                    .unwrap_or_default();
+                format!("[{}]({})", id, notion_url(id))
+            }
+            BlockContent::TableRow(_) => String::new(),
+            BlockContent::Unknown { kind, .. } => format!("<!-- unsupported block: {kind} -->"),
+        }
+    }
+
+    /// A block's own line followed by its children, unindented.
+    fn with_children(&mut self, line: String, node: &BlockNode) -> String {
+        if node.children.is_empty() {
+            return line;
+        }
+        format!("{line}\n\n{}", self.blocks(&node.children))
+    }
+
+    /// A list item with its children indented under the marker.
+    fn list_item(&mut self, marker: &str, line: &str, node: &BlockNode) -> String {
+        let mut out = format!("{marker}{line}");
+        if !node.children.is_empty() {
+            let indent = " ".repeat(marker.len());
+            out.push('\n');
+            out.push_str(&prefix_lines(&self.blocks(&node.children), &indent));
+        }
+        out
+    }
+
+    /// Where a file block should link: a local asset for Notion-hosted files
+    /// when an assets directory is set, the file's URL otherwise.
+    fn file_src(&mut self, block: &Block, file: &FileBlock) -> String {
+        let url = file.file.url().unwrap_or_default().to_string();
+        let (Some(dir), FileSource::File(_)) = (self.assets_dir, &file.file.source) else {
+            return url;
+        };
+        let stem = if block.id.is_empty() {
+            format!("asset-{}", self.assets.len() + 1)
+        } else {
+            block.id.replace('-', "")
+        };
+        let name = file_name(&url);
+        let path = match name.rsplit_once('.') {
+            Some((_, ext)) if !ext.is_empty() => dir.join(format!("{stem}.{ext}")),
+            _ => dir.join(stem),
+        };
+        let src = path.to_string_lossy().replace('\\', "/");
+        self.assets.push(Asset { url, path });
+        src
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ListKind {
+    /// Bulleted items and to-dos share the `-` marker.
+    Bulleted,
+    Numbered,
+}
+
+impl ListKind {
+    fn of(content: &BlockContent) -> Option<Self> {
+        match content {
+            BlockContent::BulletedListItem(_) | BlockContent::ToDo(_) => Some(Self::Bulleted),
+            BlockContent::NumberedListItem(_) => Some(Self::Numbered),
+            _ => None,
+        }
+    }
+}
+
+fn table_markdown(has_header: bool, rows: &[BlockNode]) -> String {
+    let rows: Vec<Vec<String>> = rows
+        .iter()
+        .filter_map(|row| match &row.block.content {
+            BlockContent::TableRow(row) => Some(
+                row.cells
+                    .iter()
+                    .map(|cell| {
+                        rich_text::to_markdown(cell)
+                            .replace('|', "\\|")
+                            .replace("\\\n", "<br>")
+                    })
+                    .collect(),
+            ),
+            _ => None,
+        })
+        .collect();
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    if width == 0 {
+        return String::new();
+    }
+    let line = |cells: &[String]| {
+        let padded = (0..width).map(|i| cells.get(i).map_or("", String::as_str));
+        format!("| {} |", padded.collect::<Vec<_>>().join(" | "))
+    };
+    let (header, body) = match rows.split_first() {
+        Some((first, rest)) if has_header => (line(first), rest),
+        _ => (line(&[]), &rows[..]),
+    };
+    let mut out = vec![header, format!("|{}", " --- |".repeat(width))];
+    out.extend(body.iter().map(|row| line(row)));
+    out.join("\n")
+}
+
+fn prefix_lines(text: &str, prefix: &str) -> String {
+    text.lines()
+        .map(|line| {
+            if line.is_empty() {
+                prefix.trim_end().to_string()
+            } else {
+                format!("{prefix}{line}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The last path segment of a URL, without its query string.
+fn file_name(url: &str) -> &str {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+fn notion_url(id: &str) -> String {
+    format!("https://www.notion.so/{}", id.replace('-', ""))
+}
@@ -0,0 +1,654 @@
+//! Typed builders for data source query filters and sorts.
+//!
+//! Each property type gets its own condition builder, so only the operators
+//! Notion accepts for that type can be written:
+//!
+//! ```
+//! use swivel::notion::Filter;
+//!
+//! let filter = Filter::prop("Status")
+//!     .status()
+//!     .equals("Done")
+//!     .and(Filter::prop("Due").date().on_or_before("2025-10-01"));
+//! assert_eq!(
+//!     serde_json::to_value(&filter).unwrap(),
+//!     serde_json::json!({ "and": [
+//!         { "property": "Status", "status": { "equals": "Done" } },
+//!         { "property": "Due", "date": { "on_or_before": "2025-10-01" } }
+//!     ] })
+//! );
+//! ```
+
+use serde::{Serialize, Serializer};
+use serde_json::{json, Map, Value};
+
+use super::DataSource;
+use crate::{Error, Result};
+
+/// Deepest compound nesting the query endpoint accepts: an `and` inside an
+/// `or` is fine, a third level is rejected.
+const MAX_NESTING: usize = 2;
+
+/// A filter for [`DataSourceQuery::filter`](super::DataSourceQuery::filter).
+///
+/// Build one with [`Filter::prop`], [`Filter::created_time`] or
+/// [`Filter::last_edited_time`], and combine them with [`and`](Filter::and)
+/// and [`or`](Filter::or). A hand-written JSON filter converts with `From`,
+/// and is sent as-is without validation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Filter(Node);
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Condition {
+        target: Target,
+        op: &'static str,
+        value: Value,
+    },
+    And(Vec<Filter>),
+    Or(Vec<Filter>),
+    Raw(Value),
+}
+
+/// What a condition applies to, plus the keys its operator nests under:
+/// `["number"]` for a number property, `["formula", "string"]` for a formula.
+#[derive(Debug, Clone, PartialEq)]
+struct Target {
+    subject: Subject,
+    path: Vec<&'static str>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Subject {
+    Property(String),
+    Timestamp(&'static str),
+}
+
+impl Filter {
+    /// Start a condition on the property called `name`.
+    pub fn prop(name: impl Into<String>) -> PropertyFilter {
+        PropertyFilter(name.into())
+    }
+
+    /// Start a condition on the page's creation time.
+    pub fn created_time() -> DateFilter {
+        DateFilter(Target::timestamp("created_time"))
+    }
+
+    /// Start a condition on the page's last edit time.
+    pub fn last_edited_time() -> DateFilter {
+        DateFilter(Target::timestamp("last_edited_time"))
+    }
+
+    /// Match pages that satisfy every filter.
+    pub fn all(filters: impl IntoIterator<Item = Filter>) -> Self {
+        Self(Node::And(filters.into_iter().collect()))
+    }
+
+    /// Match pages that satisfy at least one filter.
+    pub fn any(filters: impl IntoIterator<Item = Filter>) -> Self {
+        Self(Node::Or(filters.into_iter().collect()))
+    }
+
+    /// Both `self` and `other`. Chained calls stay one flat `and`.
+    pub fn and(self, other: Filter) -> Self {
+        match self.0 {
+            Node::And(mut filters) => {
+                filters.push(other);
+                Self(Node::And(filters))
+            }
+            node => Self::all([Self(node), other]),
+        }
+    }
+
+    /// Either `self` or `other`. Chained calls stay one flat `or`.
+    pub fn or(self, other: Filter) -> Self {
+        match self.0 {
+            Node::Or(mut filters) => {
+                filters.push(other);
+                Self(Node::Or(filters))
+            }
+            node => Self::any([Self(node), other]),
+        }
+    }
+
+    /// The filter in the JSON form the query endpoint expects.
+    pub fn to_value(&self) -> Value {
+        match &self.0 {
+            Node::Condition { target, op, value } => {
+                let mut condition = json!({ *op: value });
+                for key in target.path.iter().rev() {
+                    condition = json!({ *key: condition });
+                }
+                let Value::Object(mut map) = condition else {
+                    unreachable!("a condition is always an object")
+                };
+                match &target.subject {
+                    Subject::Property(name) => map.insert("property".into(), json!(name)),
+                    Subject::Timestamp(name) => map.insert("timestamp".into(), json!(name)),
+                };
+                Value::Object(map)
+            }
+            Node::And(filters) => {
+                json!({ "and": filters.iter().map(Filter::to_value).collect::<Vec<_>>() })
+            }
+            Node::Or(filters) => {
+                json!({ "or": filters.iter().map(Filter::to_value).collect::<Vec<_>>() })
+            }
+            Node::Raw(value) => value.clone(),
+        }
+    }
+
+    /// Check the parts Notion would reject without knowing the schema; for
+    /// now, compound filters nested more than two levels deep.
+    pub fn validate(&self) -> Result<()> {
+        if self.depth() > MAX_NESTING {
+            return Err(Error::InvalidInput(format!(
+                "compound filters may nest at most {MAX_NESTING} levels deep"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Check that every property the filter names exists in `schema` and
+    /// has a type the condition applies to.
+    pub fn check(&self, schema: &DataSource) -> Result<()> {
+        self.validate()?;
+        self.check_types(schema)
+    }
+
+    fn check_types(&self, schema: &DataSource) -> Result<()> {
+        match &self.0 {
+            Node::Condition { target, .. } => {
+                let Subject::Property(name) = &target.subject else {
+                    return Ok(());
+                };
+                let kind = schema.property_type(name)?;
+                let key = target.path[0];
+                if accepts(key, kind) {
+                    Ok(())
+                } else {
+                    Err(Error::InvalidInput(format!(
+                        "property `{name}` is of type `{kind}` and cannot take a `{key}` filter"
+                    )))
+                }
+            }
+            Node::And(filters) | Node::Or(filters) => {
+                filters.iter().try_for_each(|f| f.check_types(schema))
+            }
+            Node::Raw(_) => Ok(()),
+        }
+    }
+
+    fn depth(&self) -> usize {
+        match &self.0 {
+            Node::And(filters) | Node::Or(filters) => {
+                1 + filters.iter().map(Filter::depth).max().unwrap_or(0)
+            }
+            _ => 0,
+        }
+    }
+}
+
+/// Whether a filter keyed `key` applies to a property of type `kind`.
+fn accepts(key: &str, kind: &str) -> bool {
+    match key {
+        "date" => matches!(kind, "date" | "created_time" | "last_edited_time"),
+        "people" => matches!(kind, "people" | "created_by" | "last_edited_by"),
+        _ => key == kind,
+    }
+}
+
+impl From<Value> for Filter {
+    fn from(value: Value) -> Self {
+        Self(Node::Raw(value))
+    }
+}
+
+impl From<Filter> for Value {
+    fn from(filter: Filter) -> Self {
+        filter.to_value()
+    }
+}
+
+impl Serialize for Filter {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        self.to_value().serialize(serializer)
+    }
+}
+
+impl Target {
+    fn property(name: String, key: &'static str) -> Self {
+        Self {
+            subject: Subject::Property(name),
+            path: vec![key],
+        }
+    }
+
+    fn timestamp(name: &'static str) -> Self {
+        Self {
+            subject: Subject::Timestamp(name),
+            path: vec![name],
+        }
+    }
+
+    fn nested(mut self, key: &'static str) -> Self {
+        self.path.push(key);
+        self
+    }
+
+    fn op(self, op: &'static str, value: impl Into<Value>) -> Filter {
+        Filter(Node::Condition {
+            target: self,
+            op,
+            value: value.into(),
+        })
+    }
+}
+
+/// A JSON number, written as an integer when `n` is whole.
+fn number(n: f64) -> Value {
+    if n.fract() == 0.0 && n.abs() < i64::MAX as f64 {
+        json!(n as i64)
+    } else {
+        json!(n)
+    }
+}
+
+/// A condition on a named property; pick the property's type next.
+#[derive(Debug, Clone)]
+pub struct PropertyFilter(String);
+
+impl PropertyFilter {
+    pub fn title(self) -> TextFilter {
+        TextFilter(Target::property(self.0, "title"))
+    }
+
+    pub fn rich_text(self) -> TextFilter {
+        TextFilter(Target::property(self.0, "rich_text"))
+    }
+
+    pub fn url(self) -> TextFilter {
+        TextFilter(Target::property(self.0, "url"))
+    }
+
+    pub fn email(self) -> TextFilter {
+        TextFilter(Target::property(self.0, "email"))
+    }
+
+    pub fn phone_number(self) -> TextFilter {
+        TextFilter(Target::property(self.0, "phone_number"))
+    }
+
+    pub fn number(self) -> NumberFilter {
+        NumberFilter(Target::property(self.0, "number"))
+    }
+
+    pub fn checkbox(self) -> CheckboxFilter {
+        CheckboxFilter(Target::property(self.0, "checkbox"))
+    }
+
+    pub fn select(self) -> SelectFilter {
+        SelectFilter(Target::property(self.0, "select"))
+    }
+
+    pub fn status(self) -> SelectFilter {
+        SelectFilter(Target::property(self.0, "status"))
+    }
+
+    pub fn multi_select(self) -> ContainsFilter {
+        ContainsFilter(Target::property(self.0, "multi_select"))
+    }
+
+    /// Also matches `created_time` and `last_edited_time` properties.
+    pub fn date(self) -> DateFilter {
+        DateFilter(Target::property(self.0, "date"))
+    }
+
+    /// Also matches `created_by` and `last_edited_by` properties.
+    pub fn people(self) -> ContainsFilter {
+        ContainsFilter(Target::property(self.0, "people"))
+    }
+
+    pub fn relation(self) -> ContainsFilter {
+        ContainsFilter(Target::property(self.0, "relation"))
+    }
+
+    pub fn files(self) -> FilesFilter {
+        FilesFilter(Target::property(self.0, "files"))
+    }
+
+    pub fn formula(self) -> FormulaFilter {
+        FormulaFilter(Target::property(self.0, "formula"))
+    }
+
+    pub fn unique_id(self) -> UniqueIdFilter {
+        UniqueIdFilter(Target::property(self.0, "unique_id"))
+    }
+}
+
+/// Conditions on title, rich text, URL, email and phone number values.
+#[derive(Debug, Clone)]
+pub struct TextFilter(Target);
+
+impl TextFilter {
+    pub fn equals(self, value: impl Into<String>) -> Filter {
+        self.0.op("equals", value.into())
+    }
+
+    pub fn does_not_equal(self, value: impl Into<String>) -> Filter {
+        self.0.op("does_not_equal", value.into())
+    }
+
+    pub fn contains(self, value: impl Into<String>) -> Filter {
+        self.0.op("contains", value.into())
+    }
+
+    pub fn does_not_contain(self, value: impl Into<String>) -> Filter {
+        self.0.op("does_not_contain", value.into())
+    }
+
+    pub fn starts_with(self, value: impl Into<String>) -> Filter {
+        self.0.op("starts_with", value.into())
+    }
+
+    pub fn ends_with(self, value: impl Into<String>) -> Filter {
+        self.0.op("ends_with", value.into())
+    }
+
+    pub fn is_empty(self) -> Filter {
+        self.0.op("is_empty", true)
+    }
+
+    pub fn is_not_empty(self) -> Filter {
+        self.0.op("is_not_empty", true)
+    }
+}
+
+/// Conditions on number values.
+#[derive(Debug, Clone)]
+pub struct NumberFilter(Target);
+
+impl NumberFilter {
+    pub fn equals(self, value: impl Into<f64>) -> Filter {
+        self.0.op("equals", number(value.into()))
+    }
+
+    pub fn does_not_equal(self, value: impl Into<f64>) -> Filter {
+        self.0.op("does_not_equal", number(value.into()))
+    }
+
+    pub fn greater_than(self, value: impl Into<f64>) -> Filter {
+        self.0.op("greater_than", number(value.into()))
+    }
+
+    pub fn less_than(self, value: impl Into<f64>) -> Filter {
+        self.0.op("less_than", number(value.into()))
+    }
+
+    pub fn greater_than_or_equal_to(self, value: impl Into<f64>) -> Filter {
+        self.0.op("greater_than_or_equal_to", number(value.into()))
+    }
+
+    pub fn less_than_or_equal_to(self, value: impl Into<f64>) -> Filter {
+        self.0.op("less_than_or_equal_to", number(value.into()))
+    }
+
+    pub fn is_empty(self) -> Filter {
+        self.0.op("is_empty", true)
+    }
+
+    pub fn is_not_empty(self) -> Filter {
+        self.0.op("is_not_empty", true)
+    }
+}
+
+/// Conditions on checkbox values.
+#[derive(Debug, Clone)]
+pub struct CheckboxFilter(Target);
+
+impl CheckboxFilter {
+    pub fn equals(self, value: bool) -> Filter {
+        self.0.op("equals", value)
+    }
+
+    pub fn does_not_equal(self, value: bool) -> Filter {
+        self.0.op("does_not_equal", value)
+    }
+}
+
+/// Conditions on select and status values, by option name.
+#[derive(Debug, Clone)]
+pub struct SelectFilter(Target);
+
+impl SelectFilter {
+    pub fn equals(self, option: impl Into<String>) -> Filter {
+        self.0.op("equals", option.into())
+    }
+
+    pub fn does_not_equal(self, option: impl Into<String>) -> Filter {
+        self.0.op("does_not_equal", option.into())
+    }
+
+    pub fn is_empty(self) -> Filter {
+        self.0.op("is_empty", true)
+    }
+
+    pub fn is_not_empty(self) -> Filter {
+        self.0.op("is_not_empty", true)
+    }
+}
+
+/// Conditions on multi-select, people and relation values: option names,
+/// user ids and page ids respectively.
+#[derive(Debug, Clone)]
+pub struct ContainsFilter(Target);
+
+impl ContainsFilter {
+    pub fn contains(self, value: impl Into<String>) -> Filter {
+        self.0.op("contains", value.into())
+    }
+
+    pub fn does_not_contain(self, value: impl Into<String>) -> Filter {
+        self.0.op("does_not_contain", value.into())
+    }
+
+    pub fn is_empty(self) -> Filter {
+        self.0.op("is_empty", true)
+    }
+
+    pub fn is_not_empty(self) -> Filter {
+        self.0.op("is_not_empty", true)
+    }
+}
+
+/// Conditions on dates and timestamps, given as ISO 8601 strings.
+#[derive(Debug, Clone)]
+pub struct DateFilter(Target);
+
+impl DateFilter {
+    pub fn equals(self, date: impl Into<String>) -> Filter {
+        self.0.op("equals", date.into())
+    }
+
+    pub fn before(self, date: impl Into<String>) -> Filter {
+        self.0.op("before", date.into())
+    }
+
+    pub fn after(self, date: impl Into<String>) -> Filter {
+        self.0.op("after", date.into())
+    }
+
+    pub fn on_or_before(self, date: impl Into<String>) -> Filter {
+        self.0.op("on_or_before", date.into())
+    }
+
+    pub fn on_or_after(self, date: impl Into<String>) -> Filter {
+        self.0.op("on_or_after", date.into())
+    }
+
+    pub fn is_empty(self) -> Filter {
+        self.0.op("is_empty", true)
+    }
+
+    pub fn is_not_empty(self) -> Filter {
+        self.0.op("is_not_empty", true)
+    }
+
+    pub fn past_week(self) -> Filter {
+        self.0.op("past_week", Map::new())
+    }
+
+    pub fn past_month(self) -> Filter {
+        self.0.op("past_month", Map::new())
+    }
+
+    pub fn past_year(self) -> Filter {
+        self.0.op("past_year", Map::new())
+    }
+
+    pub fn this_week(self) -> Filter {
+        self.0.op("this_week", Map::new())
+    }
+
+    pub fn next_week(self) -> Filter {
+        self.0.op("next_week", Map::new())
+    }
+
+    pub fn next_month(self) -> Filter {
+        self.0.op("next_month", Map::new())
+    }
+
+    pub fn next_year(self) -> Filter {
+        self.0.op("next_year", Map::new())
+    }
+}
+
+/// Conditions on files values.
+#[derive(Debug, Clone)]
+pub struct FilesFilter(Target);
+
+impl FilesFilter {
+    pub fn is_empty(self) -> Filter {
+        self.0.op("is_empty", true)
+    }
+
+    pub fn is_not_empty(self) -> Filter {
+        self.0.op("is_not_empty", true)
+    }
+}
+
+/// Conditions on a formula, by the type of value it produces.
+#[derive(Debug, Clone)]
+pub struct FormulaFilter(Target);
+
+impl FormulaFilter {
+    pub fn string(self) -> TextFilter {
+        TextFilter(self.0.nested("string"))
+    }
+
+    pub fn number(self) -> NumberFilter {
+        NumberFilter(self.0.nested("number"))
+    }
+
+    pub fn checkbox(self) -> CheckboxFilter {
+        CheckboxFilter(self.0.nested("checkbox"))
+    }
+
+    pub fn date(self) -> DateFilter {
+        DateFilter(self.0.nested("date"))
+    }
+}
+
+/// Conditions on the number part of a unique id (`42` in `TASK-42`).
+#[derive(Debug, Clone)]
+pub struct UniqueIdFilter(Target);
+
+impl UniqueIdFilter {
+    pub fn equals(self, value: u64) -> Filter {
+        self.0.op("equals", value)
+    }
+
+    pub fn does_not_equal(self, value: u64) -> Filter {
+        self.0.op("does_not_equal", value)
+    }
+
+    pub fn greater_than(self, value: u64) -> Filter {
+        self.0.op("greater_than", value)
+    }
+
+    pub fn less_than(self, value: u64) -> Filter {
+        self.0.op("less_than", value)
+    }
+
+    pub fn greater_than_or_equal_to(self, value: u64) -> Filter {
+        self.0.op("greater_than_or_equal_to", value)
+    }
+
+    pub fn less_than_or_equal_to(self, value: u64) -> Filter {
+        self.0.op("less_than_or_equal_to", value)
+    }
+}
+
+/// Sort order for one key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    Ascending,
+    Descending,
+}
+
+/// One entry of a query's `sorts`; earlier entries take precedence.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Sort {
+    #[serde(flatten)]
+    key: SortKey,
+    direction: Direction,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+enum SortKey {
+    Property { property: String },
+    Timestamp { timestamp: &'static str },
+}
+
+impl Sort {
+    pub fn ascending(property: impl Into<String>) -> Self {
+        Self::property(property.into(), Direction::Ascending)
+    }
+
+    pub fn descending(property: impl Into<String>) -> Self {
+        Self::property(property.into(), Direction::Descending)
+    }
+
+    pub fn created_time(direction: Direction) -> Self {
+        Self::timestamp("created_time", direction)
+    }
+
+    pub fn last_edited_time(direction: Direction) -> Self {
+        Self::timestamp("last_edited_time", direction)
+    }
+
+    /// Check that the sorted property exists in `schema`.
+    pub fn check(&self, schema: &DataSource) -> Result<()> {
+        match &self.key {
+            SortKey::Property { property } => schema.property_type(property).map(drop),
+            SortKey::Timestamp { .. } => Ok(()),
+        }
+    }
+
+    fn property(property: String, direction: Direction) -> Self {
+        Self {
+            key: SortKey::Property { property },
+            direction,
+        }
+    }
+
+    fn timestamp(timestamp: &'static str, direction: Direction) -> Self {
+        Self {
+            key: SortKey::Timestamp { timestamp },
+            direction,
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{Error, Result};
+
+/// The id of a Notion page, data source, block or user, normalized to the
+/// lowercase dashed UUID form the API returns.
+///
+/// [`parse`](Self::parse) accepts what people copy out of Notion:
+///
+/// - dashed or undashed UUIDs, in any case;
+/// - page URLs on `notion.so` or a `notion.site` domain, where the id
+///   trails the slug (`.../Launch-plan-275a1865b187...`);
+/// - URLs with `?p=<id>`, the page open in a side peek, which wins over the
+///   path; with `?v=<view>` the path names the database and the view is
+///   ignored;
+/// - `collection://<id>` data source references.
+///
+/// ```
+/// # use swivel::notion::NotionId;
+/// let id: NotionId = "https://www.notion.so/acme/Launch-plan-275a1865b187807aadeaebaf36fb49b0"
+///     .parse()?;
+/// assert_eq!(id.as_str(), "275a1865-b187-807a-adea-ebaf36fb49b0");
+/// # Ok::<(), swivel::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NotionId(String);
+
+impl NotionId {
+    /// Parse any of the accepted forms, or fail with
+    /// [`Error::InvalidInput`].
+    pub fn parse(input: &str) -> Result<Self> {
+        let trimmed = input.trim();
+        let found = if let Some(rest) = trimmed.strip_prefix("collection://") {
+            uuid(rest.trim_end_matches('/'))
+        } else if let Some(url) = notion_url(trimmed) {
+            from_url(url)
+        } else {
+            uuid(trimmed)
+        };
+        found.ok_or_else(|| Error::InvalidInput(format!("`{input}` is not a Notion id or URL")))
+    }
+
+    /// The dashed form, e.g. `275a1865-b187-807a-adea-ebaf36fb49b0`.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The undashed form Notion uses in URLs.
+    pub fn simple(&self) -> String {
+        self.0.replace('-', "")
+    }
+}
+
+impl fmt::Display for NotionId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for NotionId {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl AsRef<str> for NotionId {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Serialize for NotionId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for NotionId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Self::parse(&s).map_err(D::Error::custom)
+    }
+}
+
+/// Anything that names a Notion object: a [`NotionId`], or a string that
+/// [`NotionId::parse`] accepts. Client methods take this, so a bad id fails
+/// before any request is sent.
+pub trait IntoNotionId {
+    fn into_notion_id(self) -> Result<NotionId>;
+}
+
+impl IntoNotionId for NotionId {
+    fn into_notion_id(self) -> Result<NotionId> {
+        Ok(self)
+    }
+}
+
+impl IntoNotionId for &NotionId {
+    fn into_notion_id(self) -> Result<NotionId> {
+        Ok(self.clone())
+    }
+}
+
+impl IntoNotionId for &str {
+    fn into_notion_id(self) -> Result<NotionId> {
+        NotionId::parse(self)
+    }
+}
+
+impl IntoNotionId for String {
+    fn into_notion_id(self) -> Result<NotionId> {
+        NotionId::parse(&self)
+    }
+}
+
+impl IntoNotionId for &String {
+    fn into_notion_id(self) -> Result<NotionId> {
+        NotionId::parse(self)
+    }
+}
+
+/// A dashed or undashed UUID, normalized.
+fn uuid(s: &str) -> Option<NotionId> {
+    let hex: String = match s.len() {
+        32 => s.to_string(),
+        36 if [8, 13, 18, 23].iter().all(|&i| s.as_bytes()[i] == b'-') => s.replace('-', ""),
+        _ => return None,
+    };
+    if hex.len() != 32 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    let hex = hex.to_ascii_lowercase();
+    Some(NotionId(format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )))
+}
+
+/// The part after the host of a Notion URL, scheme optional.
+fn notion_url(s: &str) -> Option<&str> {
+    let rest = s
+        .strip_prefix("https://")
+        .or_else(|| s.strip_prefix("http://"))
+        .unwrap_or(s);
+    let end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    let host = rest[..end].to_ascii_lowercase();
+    let notion = ["notion.so", "notion.site"]
+        .iter()
+        .any(|domain| host == *domain || host.ends_with(&format!(".{domain}")));
+    notion.then(|| &rest[end..])
+}
+
+fn from_url(rest: &str) -> Option<NotionId> {
+    let rest = rest.split('#').next().unwrap_or_default();
+    let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let peek = query
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("p="))
+        .filter(|p| !p.is_empty());
+    if let Some(peek) = peek {
+        return uuid(peek);
+    }
+    let segment = path.rsplit('/').find(|s| !s.is_empty())?;
+    // The id trails the slug, dashed or not.
+    [36, 32]
+        .iter()
+        .filter_map(|&len| segment.get(segment.len().checked_sub(len)?..))
+        .find_map(uuid)
+}
@@ -0,0 +1,792 @@
+//! Parse Markdown into Notion blocks and page properties.
+
+use pulldown_cmark::{
+    CodeBlockKind, Event, HeadingLevel, MetadataBlockKind, Options, Parser, Tag, TagEnd,
+};
+use serde_json::{json, Map, Number, Value};
+
+use super::block::{
+    Block, BlockContent, Code, FileBlock, Heading, Table, TableRow, TextBlock, ToDo,
+};
+use super::common::{DateValue, FileObject, Parent, User};
+use super::create::NewPage;
+use super::data_source::DataSource;
+use super::property::{PropertyValue, RelationRef, SelectOption};
+use super::rich_text::{
+    self, split_long_runs, Annotations, Equation, Link, RichText, RichTextContent, Text,
+};
+use super::BlockNode;
+use crate::{Error, Result};
+
+/// Front matter keys that describe the page rather than its properties; the
+/// Markdown export writes them.
+const METADATA_KEYS: &[&str] = &["title", "id", "url", "created_time", "last_edited_time"];
+
+/// A parsed Markdown file, ready for [`NotionClient::import_markdown`].
+///
+/// [`NotionClient::import_markdown`]: super::NotionClient::import_markdown
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MarkdownDocument {
+    /// The front matter `title`, or else the text of a leading `# heading`.
+    pub title: Option<String>,
+    /// Front matter entries to map onto page properties by name: top-level
+    /// keys other than page metadata, plus everything under `properties:`.
+    pub properties: Map<String, Value>,
+    pub blocks: Vec<BlockNode>,
+}
+
+/// Parse CommonMark plus the GitHub extensions (tables, task lists,
+/// strikethrough) and `$` math into blocks. `<details>` sections become
+/// toggles, so the Markdown export reads back in.
+///
+/// Front matter is read as the YAML subset the export writes: `key: value`
+/// lines whose values are JSON or bare strings, and one level of nested maps
+/// or `- item` lists.
+pub fn parse_markdown(source: &str) -> MarkdownDocument {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_MATH
+        | Options::ENABLE_YAML_STYLE_METADATA_BLOCKS;
+    let mut builder = Builder::default();
+    for event in Parser::new_ext(source, options) {
+        builder.event(event);
+    }
+    let mut blocks = builder.finish();
+
+    let mut front_matter = parse_front_matter(&builder.front_matter);
+    let mut title = match front_matter.remove("title") {
+        Some(Value::String(title)) => Some(title),
+        _ => None,
+    };
+    if title.is_none() {
+        if let Some(BlockContent::Heading1(h)) = blocks.first().map(|n| &n.block.content) {
+            title = Some(rich_text::plain_text(&h.rich_text));
+            blocks.remove(0);
+        }
+    }
+    let mut properties = Map::new();
+    for (key, value) in front_matter {
+        match (key.as_str(), value) {
+            ("properties", Value::Object(nested)) => properties.extend(nested),
+            (key, _) if METADATA_KEYS.contains(&key) => {}
+            (_, value) => {
+                properties.insert(key, value);
+            }
+        }
+    }
+    MarkdownDocument {
+        title,
+        properties,
+        blocks,
+    }
+}
+
+/// The page [`NotionClient::import_markdown`] creates for `doc`.
+///
+/// Under a data source, front matter entries are matched to the schema's
+/// properties by name and a name the schema lacks is an error; under a
+/// page, where only a title exists, they are ignored.
+///
+/// [`NotionClient::import_markdown`]: super::NotionClient::import_markdown
+pub(crate) fn new_page(
+    parent: Parent,
+    doc: &MarkdownDocument,
+    schema: Option<&DataSource>,
+) -> Result<NewPage> {
+    let title_property = schema
+        .and_then(|s| s.properties.values().find(|p| p.kind == "title"))
+        .map_or("title", |p| p.name.as_str());
+    let mut page = NewPage::new(parent).with_children(doc.blocks.clone());
+    if let Some(title) = &doc.title {
+        let title = PropertyValue::Title(plain_runs(title));
+        page = page.with_property(title_property, title);
+    }
+    let Some(schema) = schema else {
+        return Ok(page);
+    };
+    for (name, value) in &doc.properties {
+        if let Some(value) = property_value(schema.property_type(name)?, value)? {
+            page = page.with_property(name.as_str(), value);
+        }
+    }
+    Ok(page)
+}
+
+/// Build the value of a property of type `kind` from a plain JSON value, in
+/// the shapes the Markdown export writes to front matter. `Ok(None)` for
+/// types Notion computes itself.
+pub(crate) fn property_value(kind: &str, value: &Value) -> Result<Option<PropertyValue>> {
+    let invalid = || Error::InvalidInput(format!("value {value} does not fit a `{kind}` property"));
+    let string = || match value {
+        Value::Null => Ok(None),
+        Value::String(s) => Ok(Some(s.clone())),
+        Value::Number(n) => Ok(Some(n.to_string())),
+        Value::Bool(b) => Ok(Some(b.to_string())),
+        _ => Err(invalid()),
+    };
+    let strings = || match value {
+        Value::Array(items) => items
+            .iter()
+            .map(|v| v.as_str().map(String::from).ok_or_else(invalid))
+            .collect::<Result<Vec<_>>>(),
+        Value::String(s) => Ok(vec![s.clone()]),
+        Value::Null => Ok(Vec::new()),
+        _ => Err(invalid()),
+    };
+    let text = |s: Option<String>| s.map(|s| plain_runs(&s)).unwrap_or_default();
+    Ok(Some(match kind {
+        "title" => PropertyValue::Title(text(string()?)),
+        "rich_text" => PropertyValue::RichText(text(string()?)),
+        "number" => PropertyValue::Number(match value {
+            Value::Null => None,
+            Value::Number(n) => Some(n.clone()),
+            Value::String(s) => Some(s.parse::<Number>().map_err(|_| invalid())?),
+            _ => return Err(invalid()),
+        }),
+        "select" => PropertyValue::Select(string()?.map(SelectOption::named)),
+        "status" => PropertyValue::Status(string()?.map(SelectOption::named)),
+        "multi_select" => {
+            PropertyValue::MultiSelect(strings()?.into_iter().map(SelectOption::named).collect())
+        }
+        "date" => PropertyValue::Date(match value {
+            Value::Object(range) => {
+                let field = |k: &str| range.get(k).and_then(Value::as_str).map(String::from);
+                let mut date = DateValue::new(field("start").ok_or_else(invalid)?);
+                date.end = field("end");
+                Some(date)
+            }
+            _ => string()?.map(DateValue::new),
+        }),
+        "checkbox" => PropertyValue::Checkbox(match value {
+            Value::Bool(b) => *b,
+            Value::String(s) => s.parse().map_err(|_| invalid())?,
+            _ => return Err(invalid()),
+        }),
+        "url" => PropertyValue::Url(string()?),
+        "email" => PropertyValue::Email(string()?),
+        "phone_number" => PropertyValue::PhoneNumber(string()?),
+        "people" => PropertyValue::People(strings()?.into_iter().map(User::id).collect()),
+        "relation" => PropertyValue::Relation(
+            strings()?
+                .into_iter()
+                .map(|id| RelationRef { id })
+                .collect(),
+        ),
+        "files" => PropertyValue::Files(strings()?.into_iter().map(FileObject::external).collect()),
+        _ => return Ok(None),
+    }))
+}
+
+fn plain_run(text: &str) -> RichText {
+    run(text, Annotations::default(), None)
+}
+
+/// Unstyled text, split into runs Notion accepts.
+fn plain_runs(text: &str) -> Vec<RichText> {
+    split_long_runs(vec![plain_run(text)])
+}
+
+/// A run of `text`, linked to `link` when Notion accepts it as a link: an
+/// absolute `http(s)` or `mailto` URL. Relative and anchor links such as
+/// `./other.md` or `#setup` would fail the whole import, so they stay text.
+fn run(text: &str, annotations: Annotations, link: Option<&str>) -> RichText {
+    let link = link.filter(|url| {
+        ["http://", "https://", "mailto:"]
+            .iter()
+            .any(|scheme| url.starts_with(scheme))
+    });
+    RichText {
+        content: RichTextContent::Text(Text {
+            content: text.to_string(),
+            link: link.map(|url| Link {
+                url: url.to_string(),
+            }),
+        }),
+        annotations,
+        plain_text: text.to_string(),
+        href: link.map(String::from),
+    }
+}
+
+/// An open container block while its content is being parsed.
+enum Frame {
+    Root,
+    List {
+        ordered: bool,
+    },
+    /// `text` is the item's first paragraph; later blocks become children.
+    Item {
+        checked: Option<bool>,
+        text: Option<Vec<RichText>>,
+    },
+    Quote {
+        text: Option<Vec<RichText>>,
+    },
+    Toggle {
+        summary: Vec<RichText>,
+    },
+    Table {
+        rows: Vec<Vec<Vec<RichText>>>,
+        row: Vec<Vec<RichText>>,
+    },
+}
+
+/// Turns the parser's event stream into blocks, keeping a stack of open
+/// containers and the inline runs of the paragraph being read.
+#[derive(Default)]
+struct Builder {
+    stack: Vec<(Frame, Vec<BlockNode>)>,
+    runs: Vec<RichText>,
+    bold: usize,
+    italic: usize,
+    strikethrough: usize,
+    underline: usize,
+    links: Vec<String>,
+    /// An image that so far makes up its whole paragraph.
+    image: Option<(String, String)>,
+    /// Alt text of the image being read.
+    alt: Option<String>,
+    code: Option<(String, String)>,
+    in_front_matter: bool,
+    front_matter: String,
+}
+
+impl Builder {
+    fn event(&mut self, event: Event<'_>) {
+        if self.stack.is_empty() {
+            self.stack.push((Frame::Root, Vec::new()));
+        }
+        match event {
+            Event::Start(tag) => self.start(tag),
+            Event::End(tag) => self.end(tag),
+            Event::Text(text) if self.in_front_matter => self.front_matter.push_str(&text),
+            Event::Text(text) => match (&mut self.code, &mut self.alt) {
+                (Some((_, code)), _) => code.push_str(&text),
+                (_, Some(alt)) => alt.push_str(&text),
+                _ => self.text(&text),
+            },
+            Event::Code(code) => {
+                let mut annotations = self.annotations();
+                annotations.code = true;
+                self.push_run(run(
+                    &code,
+                    annotations,
+                    self.links.last().map(String::as_str),
+                ));
+            }
+            Event::InlineMath(expression) => self.push_run(equation(&expression)),
+            Event::DisplayMath(expression) => {
+                self.flush_paragraph();
+                self.push_block(BlockContent::Equation(Equation {
+                    expression: expression.trim().to_string(),
+                }));
+            }
+            Event::Html(html) => self.html(&html),
+            Event::InlineHtml(html) => match html.trim() {
+                "<u>" => self.underline += 1,
+                "</u>" => self.underline = self.underline.saturating_sub(1),
+                "<br>" | "<br/>" | "<br />" => self.text("\n"),
+                other => self.text(other),
+            },
+            Event::SoftBreak => self.text(" "),
+            Event::HardBreak => self.text("\n"),
+            Event::Rule => self.push_block(BlockContent::Divider(Map::new())),
+            Event::TaskListMarker(checked) => {
+                if let Some((Frame::Item { checked: c, .. }, _)) = self.stack.last_mut() {
+                    *c = Some(checked);
+                }
+            }
+            Event::FootnoteReference(_) => {}
+        }
+    }
+
+    fn start(&mut self, tag: Tag<'_>) {
+        match tag {
+            Tag::List(start) => {
+                self.settle();
+                self.stack.push((
+                    Frame::List {
+                        ordered: start.is_some(),
+                    },
+                    Vec::new(),
+                ));
+            }
+            Tag::Item => self.stack.push((
+                Frame::Item {
+                    checked: None,
+                    text: None,
+                },
+                Vec::new(),
+            )),
+            Tag::BlockQuote(_) => {
+                self.settle();
+                self.stack.push((Frame::Quote { text: None }, Vec::new()));
+            }
+            Tag::CodeBlock(kind) => {
+                self.settle();
+                let language = match kind {
+                    CodeBlockKind::Fenced(info) => {
+                        info.split_whitespace().next().unwrap_or("").to_string()
+                    }
+                    CodeBlockKind::Indented => String::new(),
+                };
+                self.code = Some((language, String::new()));
+            }
+            Tag::Table(_) => self.stack.push((
+                Frame::Table {
+                    rows: Vec::new(),
+                    row: Vec::new(),
+                },
+                Vec::new(),
+            )),
+            Tag::Emphasis => self.italic += 1,
+            Tag::Strong => self.bold += 1,
+            Tag::Strikethrough => self.strikethrough += 1,
+            Tag::Link { dest_url, .. } => self.links.push(dest_url.to_string()),
+            Tag::Image { dest_url, .. } => {
+                self.alt = Some(String::new());
+                self.links.push(dest_url.to_string());
+            }
+            Tag::MetadataBlock(MetadataBlockKind::YamlStyle) => self.in_front_matter = true,
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, tag: TagEnd) {
+        match tag {
+            TagEnd::Paragraph => self.flush_paragraph(),
+            TagEnd::Heading(level) => {
+                let heading = Heading {
+                    rich_text: self.take_runs(),
+                    color: "default".into(),
+                    is_toggleable: false,
+                };
+                self.push_block(match level {
+                    HeadingLevel::H1 => BlockContent::Heading1(heading),
+                    HeadingLevel::H2 => BlockContent::Heading2(heading),
+                    _ => BlockContent::Heading3(heading),
+                });
+            }
+            TagEnd::List(_) => {
+                if let Some((Frame::List { .. }, items)) = self.stack.pop() {
+                    self.children().extend(items);
+                }
+            }
+            TagEnd::Item => {
+                self.settle();
+                let Some((Frame::Item { checked, text }, children)) = self.stack.pop() else {
+                    return;
+                };
+                let rich_text = text.unwrap_or_default();
+                let ordered = matches!(self.stack.last(), Some((Frame::List { ordered: true }, _)));
+                let content = match checked {
+                    Some(checked) => BlockContent::ToDo(ToDo {
+                        rich_text,
+                        checked,
+                        color: "default".into(),
+                    }),
+                    None if ordered => BlockContent::NumberedListItem(text_block(rich_text)),
+                    None => BlockContent::BulletedListItem(text_block(rich_text)),
+                };
+                self.push_node(content, children);
+            }
+            TagEnd::BlockQuote(_) => {
+                self.settle();
+                if let Some((Frame::Quote { text }, children)) = self.stack.pop() {
+                    let quote = text_block(text.unwrap_or_default());
+                    self.push_node(BlockContent::Quote(quote), children);
+                }
+            }
+            TagEnd::CodeBlock => {
+                if let Some((language, mut source)) = self.code.take() {
+                    if source.ends_with('\n') {
+                        source.pop();
+                    }
+                    self.push_block(BlockContent::Code(Code {
+                        rich_text: plain_runs(&source),
+                        caption: Vec::new(),
+                        language: notion_language(&language).to_string(),
+                    }));
+                }
+            }
+            TagEnd::TableCell => {
+                let cell = self.take_runs();
+                if let Some((Frame::Table { row, .. }, _)) = self.stack.last_mut() {
+                    row.push(cell);
+                }
+            }
+            TagEnd::TableHead | TagEnd::TableRow => {
+                if let Some((Frame::Table { rows, row }, _)) = self.stack.last_mut() {
+                    rows.push(std::mem::take(row));
+                }
+            }
+            TagEnd::Table => {
+                if let Some((Frame::Table { rows, .. }, _)) = self.stack.pop() {
+                    self.push_table(rows);
+                }
+            }
+            TagEnd::Emphasis => self.italic = self.italic.saturating_sub(1),
+            TagEnd::Strong => self.bold = self.bold.saturating_sub(1),
+            TagEnd::Strikethrough => self.strikethrough = self.strikethrough.saturating_sub(1),
+            TagEnd::Link => {
+                self.links.pop();
+            }
+            TagEnd::Image => {
+                let alt = self.alt.take().unwrap_or_default();
+                let url = self.links.pop().unwrap_or_default();
+                if self.runs.is_empty() && self.image.is_none() {
+                    self.image = Some((url, alt));
+                } else {
+                    let annotations = self.annotations();
+                    self.push_run(run(&alt, annotations, Some(&url)));
+                }
+            }
+            TagEnd::MetadataBlock(_) => self.in_front_matter = false,
+            _ => {}
+        }
+    }
+
+    /// `<details>` and `<summary>` open a toggle, `</details>` closes it;
+    /// other HTML and comments are dropped.
+    fn html(&mut self, html: &str) {
+        if html.contains("<details") {
+            self.settle();
+            self.stack.push((
+                Frame::Toggle {
+                    summary: Vec::new(),
+                },
+                Vec::new(),
+            ));
+        }
+        if let Some(rest) = html.split_once("<summary>").map(|(_, rest)| rest) {
+            let text = rest.split("</summary>").next().unwrap_or_default().trim();
+            if let Some((Frame::Toggle { summary }, _)) = self.stack.last_mut() {
+                *summary = plain_runs(text);
+            }
+        }
+        if html.contains("</details>") {
+            self.settle();
+            if let Some((Frame::Toggle { summary }, children)) = self.stack.pop() {
+                self.push_node(BlockContent::Toggle(text_block(summary)), children);
+            }
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        let annotations = self.annotations();
+        let link = self.links.last().cloned();
+        self.push_run(run(text, annotations, link.as_deref()));
+    }
+
+    fn annotations(&self) -> Annotations {
+        Annotations {
+            bold: self.bold > 0,
+            italic: self.italic > 0,
+            strikethrough: self.strikethrough > 0,
+            underline: self.underline > 0,
+            ..Annotations::default()
+        }
+    }
+
+    /// Append a run, merging it into the previous one when they look alike.
+    fn push_run(&mut self, next: RichText) {
+        if let Some((url, alt)) = self.image.take() {
+            let annotations = Annotations::default();
+            self.runs.push(run(&alt, annotations, Some(&url)));
+        }
+        if let Some(last) = self.runs.last_mut() {
+            if let (RichTextContent::Text(a), RichTextContent::Text(b)) =
+                (&mut last.content, &next.content)
+            {
+                if last.annotations == next.annotations && a.link == b.link {
+                    a.content.push_str(&b.content);
+                    last.plain_text.push_str(&next.plain_text);
+                    return;
+                }
+            }
+        }
+        self.runs.push(next);
+    }
+
+    fn take_runs(&mut self) -> Vec<RichText> {
+        if let Some((url, alt)) = self.image.take() {
+            self.runs
+                .push(run(&alt, Annotations::default(), Some(&url)));
+        }
+        split_long_runs(std::mem::take(&mut self.runs))
+    }
+
+    /// End a paragraph: an image on its own becomes an image block, and the
+    /// first paragraph of a list item or quote becomes that block's text.
+    fn flush_paragraph(&mut self) {
+        if self.runs.is_empty() {
+            if let Some((url, alt)) = self.image.take() {
+                self.push_image(url, alt);
+            }
+            return;
+        }
+        let runs = self.take_runs();
+        if !self.set_container_text(runs.clone()) {
+            self.push_block(BlockContent::Paragraph(text_block(runs)));
+        }
+    }
+
+    /// Give pending inline text (a tight list item's text) to the open
+    /// container before a nested block starts.
+    fn settle(&mut self) {
+        if !self.runs.is_empty() || self.image.is_some() {
+            self.flush_paragraph();
+        }
+    }
+
+    fn set_container_text(&mut self, runs: Vec<RichText>) -> bool {
+        match self.stack.last_mut() {
+            Some((Frame::Item { text, .. } | Frame::Quote { text }, children))
+                if text.is_none() && children.is_empty() =>
+            {
+                *text = Some(runs);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn push_image(&mut self, url: String, alt: String) {
+        if url.starts_with("http://") || url.starts_with("https://") {
+            let caption = if alt.is_empty() {
+                Vec::new()
+            } else {
+                plain_runs(&alt)
+            };
+            self.push_block(BlockContent::Image(FileBlock {
+                caption,
+                file: FileObject::external(url),
+            }));
+        } else {
+            // Local files cannot be linked from Notion; keep the reference.
+            let runs = plain_runs(&format!("{alt} ({url})"));
+            self.push_block(BlockContent::Paragraph(text_block(runs)));
+        }
+    }
+
+    fn push_table(&mut self, rows: Vec<Vec<Vec<RichText>>>) {
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let children = rows
+            .into_iter()
+            .map(|mut cells| {
+                cells.resize(width, Vec::new());
+                node(BlockContent::TableRow(TableRow { cells }), Vec::new())
+            })
+            .collect();
+        self.push_node(
+            BlockContent::Table(Table {
+                table_width: width,
+                has_column_header: true,
+                has_row_header: false,
+            }),
+            children,
+        );
+    }
+
+    fn push_block(&mut self, content: BlockContent) {
+        self.push_node(content, Vec::new());
+    }
+
+    fn push_node(&mut self, content: BlockContent, children: Vec<BlockNode>) {
+        let node = node(content, children);
+        self.children().push(node);
+    }
+
+    fn children(&mut self) -> &mut Vec<BlockNode> {
+        &mut self
+            .stack
+            .last_mut()
+            .expect("the root frame is never popped")
+            .1
+    }
+
+    fn finish(&mut self) -> Vec<BlockNode> {
+        self.settle();
+        // Close anything left open, e.g. a `<details>` without its end tag.
+        while self.stack.len() > 1 {
+            match self.stack.pop() {
+                Some((Frame::Toggle { summary }, children)) => {
+                    self.push_node(BlockContent::Toggle(text_block(summary)), children)
+                }
+                Some((_, children)) => self.children().extend(children),
+                None => break,
+            }
+        }
+        self.stack
+            .pop()
+            .map(|(_, blocks)| blocks)
+            .unwrap_or_default()
+    }
+}
+
+fn node(content: BlockContent, children: Vec<BlockNode>) -> BlockNode {
+    let mut block = Block::new(content);
+    block.has_children = !children.is_empty();
+    BlockNode { block, children }
+}
+
+fn text_block(rich_text: Vec<RichText>) -> TextBlock {
+    TextBlock {
+        rich_text,
+        color: "default".into(),
+    }
+}
+
+fn equation(expression: &str) -> RichText {
+    RichText {
+        content: RichTextContent::Equation(Equation {
+            expression: expression.to_string(),
+        }),
+        annotations: Annotations::default(),
+        plain_text: expression.to_string(),
+        href: None,
+    }
+}
+
+/// Map a fence info string onto one of the languages Notion accepts.
+fn notion_language(info: &str) -> &'static str {
+    const LANGUAGES: &[&str] = &[
+        "bash",
+        "c",
+        "c#",
+        "c++",
+        "clojure",
+        "css",
+        "dart",
+        "diff",
+        "docker",
+        "elixir",
+        "erlang",
+        "go",
+        "graphql",
+        "haskell",
+        "html",
+        "java",
+        "javascript",
+        "json",
+        "kotlin",
+        "latex",
+        "lua",
+        "makefile",
+        "markdown",
+        "mermaid",
+        "nix",
+        "ocaml",
+        "perl",
+        "php",
+        "powershell",
+        "python",
+        "r",
+        "ruby",
+        "rust",
+        "scala",
+        "shell",
+        "sql",
+        "swift",
+        "typescript",
+        "xml",
+        "yaml",
+    ];
+    let info = info.to_ascii_lowercase();
+    let alias = match info.as_str() {
+        "rs" => "rust",
+        "js" | "jsx" => "javascript",
+        "ts" | "tsx" => "typescript",
+        "py" => "python",
+        "sh" | "zsh" | "console" => "shell",
+        "yml" => "yaml",
+        "md" => "markdown",
+        "cpp" | "cc" => "c++",
+        "cs" | "csharp" => "c#",
+        "golang" => "go",
+        "dockerfile" => "docker",
+        "tex" => "latex",
+        other => other,
+    };
+    LANGUAGES
+        .iter()
+        .find(|&&l| l == alias)
+        .copied()
+        .unwrap_or("plain text")
+}
+
+fn parse_front_matter(text: &str) -> Map<String, Value> {
+    let mut map = Map::new();
+    let mut open: Option<String> = None;
+    for line in text.lines() {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        let nested = line.starts_with(' ') || line.starts_with('\t');
+        let line = line.trim();
+        match (nested, open.as_ref()) {
+            (true, Some(parent)) => {
+                let entry = map.entry(parent.clone()).or_insert(Value::Null);
+                if let Some(item) = line.strip_prefix("- ") {
+                    if !entry.is_array() {
+                        *entry = json!([]);
+                    }
+                    entry.as_array_mut().unwrap().push(scalar(item));
+                } else if let Some((key, value)) = split_entry(line) {
+                    if !entry.is_object() {
+                        *entry = json!({});
+                    }
+                    entry.as_object_mut().unwrap().insert(key, scalar(value));
+                }
+            }
+            _ => {
+                let Some((key, value)) = split_entry(line) else {
+                    continue;
+                };
+                if value.is_empty() {
+                    open = Some(key.clone());
+                    map.insert(key, Value::Null);
+                } else {
+                    open = None;
+                    map.insert(key, scalar(value));
+                }
+            }
+        }
+    }
+    map
+}
+
+/// Split `key: value`, where the key may be a quoted string.
+fn split_entry(line: &str) -> Option<(String, &str)> {
+    if line.starts_with('"') {
+        let mut stream = serde_json::Deserializer::from_str(line).into_iter::<String>();
+        let key = stream.next()?.ok()?;
+        let rest = line[stream.byte_offset()..]
+            .trim_start()
+            .strip_prefix(':')?;
+        return Some((key, rest.trim()));
+    }
+    let (key, value) = line.split_once(':')?;
+    Some((key.trim().to_string(), value.trim()))
+}
+
+/// A JSON value, a bare `[a, b]` list, or else a plain string.
+fn scalar(value: &str) -> Value {
+    if let Ok(value) = serde_json::from_str(value) {
+        return value;
+    }
+    if let Some(items) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+        return items
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(scalar)
+            .collect();
+    }
+    let unquoted = value
+        .strip_prefix('\'')
+        .and_then(|v| v.strip_suffix('\''))
+        .unwrap_or(value);
+    Value::String(unquoted.to_string())
+}
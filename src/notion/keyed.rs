@@ -0,0 +1,94 @@
+//! Notion tags most unions as `{"type": "x", "x": <payload>}`. `keyed_enum!`
+//! declares a Rust enum for such a union, with serde impls and an `Unknown`
+//! variant that keeps types this crate does not know yet as raw JSON, so a
+//! new Notion feature never breaks deserialization.
+
+use serde_json::{Map, Value};
+
+/// Pull the `type` tag and its payload out of a keyed object, leaving any
+/// other fields in `map`.
+pub(crate) fn split(map: &mut Map<String, Value>) -> Result<(String, Value), String> {
+    let kind = match map.remove("type") {
+        Some(Value::String(kind)) => kind,
+        _ => return Err("missing string field `type`".into()),
+    };
+    let value = map.remove(&kind).unwrap_or(Value::Null);
+    Ok((kind, value))
+}
+
+macro_rules! keyed_enum {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $(
+                $(#[$vmeta:meta])*
+                $variant:ident($ty:ty) = $tag:literal,
+            )*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq)]
+        pub enum $name {
+            $(
+                $(#[$vmeta])*
+                $variant($ty),
+            )*
+            /// A type this crate does not know yet, kept verbatim.
+            Unknown {
+                kind: String,
+                value: serde_json::Value,
+            },
+        }
+
+        impl $name {
+            /// The Notion `type` tag, e.g. `"rich_text"`.
+            pub fn kind(&self) -> &str {
+                match self {
+                    $(Self::$variant(_) => $tag,)*
+                    Self::Unknown { kind, .. } => kind,
+                }
+            }
+
+            #[allow(dead_code)]
+            pub(crate) fn from_keyed(
+                kind: String,
+                value: serde_json::Value,
+            ) -> Result<Self, serde_json::Error> {
+                Ok(match kind.as_str() {
+                    $($tag => Self::$variant(serde_json::from_value(value)?),)*
+                    _ => Self::Unknown { kind, value },
+                })
+            }
+
+            pub(crate) fn keyed_value(&self) -> Result<serde_json::Value, serde_json::Error> {
+                match self {
+                    $(Self::$variant(v) => serde_json::to_value(v),)*
+                    Self::Unknown { value, .. } => Ok(value.clone()),
+                }
+            }
+        }
+
+        impl serde::Serialize for $name {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                use serde::ser::{Error as _, SerializeMap};
+                let value = self.keyed_value().map_err(S::Error::custom)?;
+                let mut map = serializer.serialize_map(Some(2))?;
+                map.serialize_entry("type", self.kind())?;
+                map.serialize_entry(self.kind(), &value)?;
+                map.end()
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $name {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                use serde::de::Error as _;
+                let mut map = serde_json::Map::deserialize(deserializer)?;
+                let (kind, value) =
+                    $crate::notion::keyed::split(&mut map).map_err(D::Error::custom)?;
+                Self::from_keyed(kind, value).map_err(D::Error::custom)
+            }
+        }
+    };
+}
+
+pub(crate) use keyed_enum;
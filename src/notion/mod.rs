@@ -0,0 +1,70 @@
+//! Notion backend.
+//!
+//! [`NotionClient`] talks to the public Notion REST API and implements
+//! [`Database`](crate::Database) with pages as records. With the `async`
+//! feature, `AsyncNotionClient` does the same for
+//! `AsyncDatabase`.
+
+mod api;
+#[cfg(feature = "async")]
+mod async_client;
+mod block;
+mod client;
+mod codegen;
+mod common;
+mod create;
+mod data_source;
+mod error;
+mod export;
+mod filter;
+mod id;
+mod import;
+pub(crate) mod keyed;
+mod page;
+mod pagination;
+mod property;
+mod property_item;
+mod query;
+mod records;
+pub mod rich_text;
+mod schema;
+mod search;
+mod tree;
+mod update;
+
+pub use api::{DEFAULT_BASE_URL, LEGACY_NOTION_VERSION, NOTION_VERSION};
+#[cfg(feature = "async")]
+pub use async_client::AsyncNotionClient;
+pub use block::{
+    Block, BlockContent, Bookmark, Callout, ChildTitle, Code, FileBlock, Heading, SyncedBlock,
+    Table, TableRow, TextBlock, ToDo,
+};
+pub use client::NotionClient;
+pub use codegen::{generate_rust, CodegenOptions};
+pub use common::{
+    Cover, CustomEmoji, DateValue, ExternalFile, FileObject, FileSource, FileUpload, HostedFile,
+    Icon, Parent, User,
+};
+pub use create::{NewPage, MAX_CHILDREN};
+pub use data_source::{DataSource, DataSourceRef, NotionDatabase, PropertySchema};
+pub use export::{blocks_to_markdown, page_to_markdown, Asset, Markdown, MarkdownOptions};
+pub use filter::{
+    CheckboxFilter, ContainsFilter, DateFilter, Direction, FilesFilter, Filter, FormulaFilter,
+    NumberFilter, PropertyFilter, SelectFilter, Sort, TextFilter, UniqueIdFilter,
+};
+pub use id::{IntoNotionId, NotionId};
+pub use import::{parse_markdown, MarkdownDocument};
+pub use page::Page;
+pub use pagination::Paginator;
+pub use property::{
+    FormulaValue, Property, PropertyValue, RelationRef, Rollup, RollupValue, SelectOption,
+    UniqueId, Verification,
+};
+pub use property_item::{PageOptions, MAX_INLINE_ITEMS};
+pub use query::{DataSourceQuery, MAX_PAGE_SIZE};
+pub use records::DataSourceRecords;
+pub use rich_text::{Mention, RichText, RichTextBuilder};
+pub use schema::{OptionSpec, PropertySpec, SchemaChange, SchemaPlan, SchemaSpec, SchemaTarget};
+pub use search::{Search, SearchObject, SearchResult};
+pub use tree::{BlockNode, BlockTree, ContentOptions};
+pub use update::{PageInput, PageUpdate};
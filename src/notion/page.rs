@@ -0,0 +1,90 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::common::{Cover, Icon, Parent, User};
+use super::property::{Property, PropertyValue};
+
+/// A Notion page object.
+///
+/// ```no_run
+/// # use swivel::notion::NotionClient;
+/// let notion = NotionClient::from_env()?;
+/// let page = notion.get_page("275a1865-b187-807a-adea-ebaf36fb49b0")?;
+/// if let Some(status) = page.prop("Status").and_then(|p| p.as_status()) {
+///     println!("{}: {}", page.title(), status.name);
+/// }
+/// # Ok::<(), swivel::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Page {
+    pub id: String,
+    pub created_time: String,
+    pub last_edited_time: String,
+    #[serde(default)]
+    pub created_by: Option<User>,
+    #[serde(default)]
+    pub last_edited_by: Option<User>,
+    pub parent: Parent,
+    #[serde(default)]
+    pub in_trash: bool,
+    #[serde(default)]
+    pub icon: Option<Icon>,
+    #[serde(default)]
+    pub cover: Option<Cover>,
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub public_url: Option<String>,
+    /// Property values keyed by property name.
+    #[serde(default)]
+    pub properties: BTreeMap<String, Property>,
+    /// Everything else Notion sent (`object`, `archived`, ...).
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Page {
+    /// The value of the property called `name`.
+    pub fn prop(&self, name: &str) -> Option<&PropertyValue> {
+        self.properties.get(name).map(|p| &p.value)
+    }
+
+    /// Mutable access to the value of the property called `name`.
+    pub fn prop_mut(&mut self, name: &str) -> Option<&mut PropertyValue> {
+        self.properties.get_mut(name).map(|p| &mut p.value)
+    }
+
+    /// The names of the properties whose value the page object may have cut
+    /// off (see [`Property::is_truncated`]), plus every rollup when a
+    /// relation was cut off, since Notion computes rollups from the
+    /// relation's first items only.
+    ///
+    /// [`NotionClient::complete_page`](super::NotionClient::complete_page)
+    /// fetches their full values.
+    pub fn truncated_properties(&self) -> Vec<String> {
+        let relation_cut = self
+            .properties
+            .values()
+            .any(|p| matches!(p.value, PropertyValue::Relation(_)) && p.is_truncated());
+        self.properties
+            .iter()
+            .filter(|(_, p)| {
+                p.is_truncated() || (relation_cut && matches!(p.value, PropertyValue::Rollup(_)))
+            })
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    /// The plain text of the page's title property, empty if it has none.
+    pub fn title(&self) -> String {
+        self.properties
+            .values()
+            .find_map(|p| match &p.value {
+                PropertyValue::Title(_) => p.value.plain_text(),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,65 @@
+use serde::Deserialize;
+
+use crate::Result;
+
+/// One page of a paginated Notion list response.
+#[derive(Debug, Deserialize)]
+pub(crate) struct List<T> {
+    pub results: Vec<T>,
+    pub next_cursor: Option<String>,
+    #[serde(default)]
+    pub has_more: bool,
+}
+
+type Fetch<'a, T> = Box<dyn FnMut(Option<&str>) -> Result<List<T>> + 'a>;
+
+/// Lazily walks a cursor-paginated endpoint, one request per page of
+/// results, yielding items as they arrive.
+///
+/// Only one page is held in memory at a time. After an error the iterator
+/// yields it once and then stops.
+pub struct Paginator<'a, T> {
+    fetch: Fetch<'a, T>,
+    buffer: std::vec::IntoIter<T>,
+    cursor: Option<String>,
+    done: bool,
+}
+
+impl<'a, T> Paginator<'a, T> {
+    /// `fetch` is called with the `start_cursor` of the next page, `None`
+    /// for the first one.
+    pub(crate) fn new(fetch: impl FnMut(Option<&str>) -> Result<List<T>> + 'a) -> Self {
+        Self {
+            fetch: Box::new(fetch),
+            buffer: Vec::new().into_iter(),
+            cursor: None,
+            done: false,
+        }
+    }
+}
+
+impl<T> Iterator for Paginator<'_, T> {
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.buffer.next() {
+                return Some(Ok(item));
+            }
+            if self.done {
+                return None;
+            }
+            match (self.fetch)(self.cursor.as_deref()) {
+                Ok(list) => {
+                    self.done = !list.has_more || list.next_cursor.is_none();
+                    self.cursor = list.next_cursor;
+                    self.buffer = list.results.into_iter();
+                }
+                Err(err) => {
+                    self.done = true;
+                    return Some(Err(err));
+                }
+            }
+        }
+    }
+}
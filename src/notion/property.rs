@@ -0,0 +1,377 @@
+use serde::de::Error as _;
+use serde::ser::{Error as _, SerializeMap};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Number, Value};
+
+use super::common::{DateValue, FileObject, User};
+use super::keyed::{self, keyed_enum};
+use super::property_item::MAX_INLINE_ITEMS;
+use super::rich_text::{self, RichText};
+
+/// One property of a page: its stable id plus its typed value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Property {
+    /// Stable property id; survives renames. Empty for values built locally.
+    pub id: String,
+    pub value: PropertyValue,
+    /// Other fields Notion sent next to the value, e.g. `has_more` on
+    /// relations.
+    pub extra: Map<String, Value>,
+}
+
+impl Property {
+    pub fn new(value: PropertyValue) -> Self {
+        Self {
+            id: String::new(),
+            value,
+            extra: Map::new(),
+        }
+    }
+
+    /// Whether the page object may hold only part of this value: a relation
+    /// Notion marked `has_more`, or a list value with exactly
+    /// [`MAX_INLINE_ITEMS`] items. A value fetched in full is longer, or
+    /// shorter, and no longer counts. Rollups over a truncated relation are
+    /// not detected here; see
+    /// [`Page::truncated_properties`](super::Page::truncated_properties).
+    pub fn is_truncated(&self) -> bool {
+        let full = |len: usize| len == MAX_INLINE_ITEMS;
+        match &self.value {
+            PropertyValue::Relation(v) => {
+                self.extra.get("has_more") == Some(&Value::Bool(true)) || full(v.len())
+            }
+            PropertyValue::Title(v) | PropertyValue::RichText(v) => full(v.len()),
+            PropertyValue::People(v) => full(v.len()),
+            PropertyValue::Rollup(Rollup {
+                value: RollupValue::Array(v),
+                ..
+            }) => full(v.len()),
+            _ => false,
+        }
+    }
+}
+
+impl Serialize for Property {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let value = self.value.keyed_value().map_err(S::Error::custom)?;
+        let mut map = serializer.serialize_map(None)?;
+        if !self.id.is_empty() {
+            map.serialize_entry("id", &self.id)?;
+        }
+        map.serialize_entry("type", self.value.kind())?;
+        map.serialize_entry(self.value.kind(), &value)?;
+        for (k, v) in &self.extra {
+            map.serialize_entry(k, v)?;
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Property {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut map = Map::deserialize(deserializer)?;
+        let id = match map.remove("id") {
+            Some(Value::String(id)) => id,
+            _ => String::new(),
+        };
+        let (kind, value) = keyed::split(&mut map).map_err(D::Error::custom)?;
+        let value = PropertyValue::from_keyed(kind, value).map_err(D::Error::custom)?;
+        Ok(Self {
+            id,
+            value,
+            extra: map,
+        })
+    }
+}
+
+keyed_enum! {
+    /// The typed value of a page property.
+    ///
+    /// Read-only types (formula, rollup, created/last edited time and by,
+    /// unique id, verification) and types swivel does not know are ignored
+    /// on write; see [`PropertyValue::is_read_only`].
+    pub enum PropertyValue {
+        Title(Vec<RichText>) = "title",
+        RichText(Vec<RichText>) = "rich_text",
+        Number(Option<Number>) = "number",
+        Select(Option<SelectOption>) = "select",
+        MultiSelect(Vec<SelectOption>) = "multi_select",
+        Status(Option<SelectOption>) = "status",
+        Date(Option<DateValue>) = "date",
+        People(Vec<User>) = "people",
+        Files(Vec<FileObject>) = "files",
+        Checkbox(bool) = "checkbox",
+        Url(Option<String>) = "url",
+        Email(Option<String>) = "email",
+        PhoneNumber(Option<String>) = "phone_number",
+        Formula(FormulaValue) = "formula",
+        Relation(Vec<RelationRef>) = "relation",
+        Rollup(Rollup) = "rollup",
+        UniqueId(UniqueId) = "unique_id",
+        Verification(Option<Verification>) = "verification",
+        CreatedTime(String) = "created_time",
+        LastEditedTime(String) = "last_edited_time",
+        CreatedBy(User) = "created_by",
+        LastEditedBy(User) = "last_edited_by",
+    }
+}
+
+/// An option of a select, multi-select or status property.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SelectOption {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+}
+
+impl SelectOption {
+    /// Refer to an option by name, as when writing.
+    pub fn named(name: impl Into<String>) -> Self {
+        Self {
+            id: None,
+            name: name.into(),
+            color: None,
+        }
+    }
+}
+
+keyed_enum! {
+    /// The computed result of a formula.
+    pub enum FormulaValue {
+        String(Option<String>) = "string",
+        Number(Option<Number>) = "number",
+        Boolean(Option<bool>) = "boolean",
+        Date(Option<DateValue>) = "date",
+    }
+}
+
+/// A related page.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RelationRef {
+    pub id: String,
+}
+
+/// The result of a rollup, and the function that produced it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rollup {
+    #[serde(flatten)]
+    pub value: RollupValue,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub function: Option<String>,
+}
+
+keyed_enum! {
+    /// What a rollup computed.
+    pub enum RollupValue {
+        Number(Option<Number>) = "number",
+        Date(Option<DateValue>) = "date",
+        /// One value per related page, each shaped like a property value.
+        Array(Vec<PropertyValue>) = "array",
+    }
+}
+
+/// An auto-incrementing id such as `TASK-42`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UniqueId {
+    #[serde(default)]
+    pub prefix: Option<String>,
+    #[serde(default)]
+    pub number: Option<i64>,
+}
+
+impl std::fmt::Display for UniqueId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.prefix, self.number) {
+            (Some(prefix), Some(n)) => write!(f, "{prefix}-{n}"),
+            (None, Some(n)) => write!(f, "{n}"),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Verification state of a wiki page.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Verification {
+    /// `"verified"`, `"expired"` or `"unverified"`.
+    pub state: String,
+    #[serde(default)]
+    pub verified_by: Option<User>,
+    #[serde(default)]
+    pub date: Option<DateValue>,
+}
+
+impl PropertyValue {
+    /// Whether this value cannot be written back, so it is never sent on
+    /// create or update: Notion computes it, the API cannot set it
+    /// (verification, and types swivel does not know, such as buttons), or
+    /// it holds files Notion hosts.
+    pub fn is_read_only(&self) -> bool {
+        match self {
+            Self::Files(files) => files.iter().any(FileObject::is_notion_hosted),
+            _ => matches!(
+                self,
+                Self::Formula(_)
+                    | Self::Rollup(_)
+                    | Self::UniqueId(_)
+                    | Self::Verification(_)
+                    | Self::CreatedTime(_)
+                    | Self::LastEditedTime(_)
+                    | Self::CreatedBy(_)
+                    | Self::LastEditedBy(_)
+                    | Self::Unknown { .. }
+            ),
+        }
+    }
+
+    pub fn as_title(&self) -> Option<&[RichText]> {
+        match self {
+            Self::Title(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_rich_text(&self) -> Option<&[RichText]> {
+        match self {
+            Self::RichText(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_number(&self) -> Option<f64> {
+        match self {
+            Self::Number(v) => v.as_ref()?.as_f64(),
+            _ => None,
+        }
+    }
+
+    pub fn as_select(&self) -> Option<&SelectOption> {
+        match self {
+            Self::Select(v) => v.as_ref(),
+            _ => None,
+        }
+    }
+
+    pub fn as_multi_select(&self) -> Option<&[SelectOption]> {
+        match self {
+            Self::MultiSelect(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_status(&self) -> Option<&SelectOption> {
+        match self {
+            Self::Status(v) => v.as_ref(),
+            _ => None,
+        }
+    }
+
+    pub fn as_date(&self) -> Option<&DateValue> {
+        match self {
+            Self::Date(v) => v.as_ref(),
+            _ => None,
+        }
+    }
+
+    pub fn as_people(&self) -> Option<&[User]> {
+        match self {
+            Self::People(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_files(&self) -> Option<&[FileObject]> {
+        match self {
+            Self::Files(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_checkbox(&self) -> Option<bool> {
+        match self {
+            Self::Checkbox(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_url(&self) -> Option<&str> {
+        match self {
+            Self::Url(v) => v.as_deref(),
+            _ => None,
+        }
+    }
+
+    pub fn as_email(&self) -> Option<&str> {
+        match self {
+            Self::Email(v) => v.as_deref(),
+            _ => None,
+        }
+    }
+
+    pub fn as_phone_number(&self) -> Option<&str> {
+        match self {
+            Self::PhoneNumber(v) => v.as_deref(),
+            _ => None,
+        }
+    }
+
+    pub fn as_formula(&self) -> Option<&FormulaValue> {
+        match self {
+            Self::Formula(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_relation(&self) -> Option<&[RelationRef]> {
+        match self {
+            Self::Relation(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_rollup(&self) -> Option<&Rollup> {
+        match self {
+            Self::Rollup(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_unique_id(&self) -> Option<&UniqueId> {
+        match self {
+            Self::UniqueId(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_verification(&self) -> Option<&Verification> {
+        match self {
+            Self::Verification(v) => v.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// `created_time` or `last_edited_time`, as ISO 8601.
+    pub fn as_timestamp(&self) -> Option<&str> {
+        match self {
+            Self::CreatedTime(v) | Self::LastEditedTime(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// `created_by` or `last_edited_by`.
+    pub fn as_user(&self) -> Option<&User> {
+        match self {
+            Self::CreatedBy(v) | Self::LastEditedBy(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// The text of a title or rich text property.
+    pub fn plain_text(&self) -> Option<String> {
+        match self {
+            Self::Title(v) | Self::RichText(v) => Some(rich_text::plain_text(v)),
+            _ => None,
+        }
+    }
+}
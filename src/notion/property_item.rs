@@ -0,0 +1,132 @@
+//! Full values for the properties a page object truncates.
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use super::keyed;
+use super::{PropertyValue, Rollup, RollupValue};
+use crate::{Error, Result};
+
+/// Most items a page object holds for one title, rich text, people or
+/// relation property. Longer values are cut off there, and rollups over such
+/// a relation may be computed from the first items only.
+pub const MAX_INLINE_ITEMS: usize = 25;
+
+/// How [`NotionClient::get_page_with`](super::NotionClient::get_page_with)
+/// retrieves a page.
+///
+/// By default the page object is returned as Notion sent it.
+#[derive(Debug, Clone, Default)]
+pub struct PageOptions {
+    fetch_complete: bool,
+}
+
+impl PageOptions {
+    /// Fetch the full value of every property the page object may have
+    /// truncated (see [`Page::truncated_properties`]) and merge it back
+    /// into the page. Costs one request per 100 items of each such
+    /// property.
+    ///
+    /// [`Page::truncated_properties`]: super::Page::truncated_properties
+    pub fn with_fetch_complete(mut self, fetch: bool) -> Self {
+        self.fetch_complete = fetch;
+        self
+    }
+
+    pub(crate) fn fetch_complete(&self) -> bool {
+        self.fetch_complete
+    }
+}
+
+/// One page of a paginated property item response.
+#[derive(Debug, Deserialize)]
+struct ItemList {
+    results: Vec<Value>,
+    next_cursor: Option<String>,
+    #[serde(default)]
+    has_more: bool,
+    /// The property's type, and for rollups the value computed over every
+    /// item.
+    property_item: Map<String, Value>,
+}
+
+/// Collects the responses of `GET /pages/{id}/properties/{property_id}`
+/// into one [`PropertyValue`].
+///
+/// Simple properties come back as a single property item. Title, rich text,
+/// people, relation and rollup properties come back as a list of items, one
+/// rich text run, user or related page each.
+#[derive(Debug, Default)]
+pub(crate) struct PropertyItems {
+    single: Option<Value>,
+    kind: Option<String>,
+    items: Vec<Value>,
+    rollup: Option<Value>,
+}
+
+impl PropertyItems {
+    /// Take one response and return the cursor of the next, if there is
+    /// one.
+    pub fn add(&mut self, response: Value) -> Result<Option<String>> {
+        if response.get("object").and_then(Value::as_str) != Some("list") {
+            self.single = Some(response);
+            return Ok(None);
+        }
+        let mut list: ItemList = serde_json::from_value(response)?;
+        let (kind, value) = keyed::split(&mut list.property_item)
+            .map_err(|err| Error::InvalidInput(format!("property item list: {err}")))?;
+        if kind == "rollup" {
+            self.rollup = Some(value);
+        }
+        self.kind = Some(kind);
+        self.items.extend(list.results);
+        Ok(list.next_cursor.filter(|_| list.has_more))
+    }
+
+    /// The complete value.
+    pub fn finish(self) -> Result<PropertyValue> {
+        if let Some(single) = self.single {
+            return item_value(single);
+        }
+        let kind = self
+            .kind
+            .ok_or_else(|| Error::InvalidInput("property item response had no items".into()))?;
+        if kind == "rollup" {
+            let rollup = self.rollup.unwrap_or(Value::Null);
+            let mut rollup: Rollup = serde_json::from_value(rollup)?;
+            if let RollupValue::Array(values) = &mut rollup.value {
+                *values = self
+                    .items
+                    .into_iter()
+                    .map(item_value)
+                    .collect::<Result<_>>()?;
+            }
+            return Ok(PropertyValue::Rollup(rollup));
+        }
+        let values = self
+            .items
+            .into_iter()
+            .map(|mut item| item.get_mut(&kind).map(Value::take).unwrap_or_default())
+            .collect();
+        Ok(PropertyValue::from_keyed(kind, Value::Array(values))?)
+    }
+}
+
+/// A property item as a value. List items hold one element of their
+/// property (a rich text run, a user), which is wrapped back into a list.
+fn item_value(item: Value) -> Result<PropertyValue> {
+    let Value::Object(mut map) = item else {
+        return Err(Error::InvalidInput(format!(
+            "expected a property item, got {item}"
+        )));
+    };
+    let (kind, value) = keyed::split(&mut map)
+        .map_err(|err| Error::InvalidInput(format!("property item: {err}")))?;
+    let list_kind = matches!(kind.as_str(), "title" | "rich_text" | "people" | "relation");
+    let value = if list_kind && !value.is_array() {
+        Value::Array(vec![value])
+    } else {
+        value
+    };
+    Ok(PropertyValue::from_keyed(kind, value)?)
+}
@@ -0,0 +1,156 @@
+use serde_json::{json, Map, Value};
+
+use super::api;
+use super::pagination::Paginator;
+use super::{DataSource, Filter, NotionClient, NotionId, Page, Sort};
+use crate::{Error, Result};
+
+/// Largest `page_size` the query endpoint accepts.
+pub const MAX_PAGE_SIZE: u32 = 100;
+
+/// A query against one data source, built with
+/// [`NotionClient::query_data_source`]. A database id queries the
+/// database's data source.
+///
+/// Nothing is sent until the query is iterated; each page of results is then
+/// fetched on demand, following `next_cursor` until `has_more` is false.
+///
+/// ```no_run
+/// # use swivel::notion::{Filter, NotionClient, Sort};
+/// let notion = NotionClient::from_env()?;
+/// let query = notion
+///     .query_data_source("b5ad9a34-...")
+///     .filter(Filter::prop("Done").checkbox().equals(false))
+///     .sort(Sort::ascending("Due"))
+///     .checked()?;
+/// for page in query {
+///     let page = page?;
+///     println!("{}", page.id);
+/// }
+/// # Ok::<(), swivel::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct DataSourceQuery<'a> {
+    client: &'a NotionClient,
+    /// The error message when the id given was not a valid one.
+    data_source_id: std::result::Result<NotionId, String>,
+    page_size: u32,
+    filter: Option<Filter>,
+    sorts: Vec<Sort>,
+}
+
+impl<'a> DataSourceQuery<'a> {
+    pub(crate) fn new(client: &'a NotionClient, data_source_id: Result<NotionId>) -> Self {
+        Self {
+            client,
+            data_source_id: data_source_id.map_err(|err| match err {
+                Error::InvalidInput(message) => message,
+                other => other.to_string(),
+            }),
+            page_size: MAX_PAGE_SIZE,
+            filter: None,
+            sorts: Vec::new(),
+        }
+    }
+
+    /// Results per request, 1 to [`MAX_PAGE_SIZE`] (the default).
+    pub fn page_size(mut self, page_size: u32) -> Self {
+        self.page_size = page_size;
+        self
+    }
+
+    /// Only return pages matching `filter`: a [`Filter`], or a filter object
+    /// in Notion's JSON format.
+    pub fn filter(mut self, filter: impl Into<Filter>) -> Self {
+        self.filter = Some(filter.into());
+        self
+    }
+
+    /// Add a sort after any already given.
+    pub fn sort(mut self, sort: Sort) -> Self {
+        self.sorts.push(sort);
+        self
+    }
+
+    /// Replace the sorts.
+    pub fn sorts(mut self, sorts: impl IntoIterator<Item = Sort>) -> Self {
+        self.sorts = sorts.into_iter().collect();
+        self
+    }
+
+    /// Check the filter and sorts against `schema`: every property they name
+    /// must exist and have a type the condition applies to.
+    pub fn check(&self, schema: &DataSource) -> Result<()> {
+        if let Some(filter) = &self.filter {
+            filter.check(schema)?;
+        }
+        self.sorts.iter().try_for_each(|sort| sort.check(schema))
+    }
+
+    /// Fetch the data source's schema and [`check`](Self::check) against it,
+    /// so a mistyped filter fails here rather than as a Notion validation
+    /// error.
+    pub fn checked(self) -> Result<Self> {
+        let schema = self.client.get_data_source(self.id()?)?;
+        self.check(&schema)?;
+        Ok(self)
+    }
+
+    /// Lazily iterate over every matching page.
+    pub fn pages(self) -> Paginator<'a, Page> {
+        let client = self.client;
+        let mut invalid = self.validate().err();
+        Paginator::new(move |cursor| {
+            if let Some(err) = invalid.take() {
+                return Err(err);
+            }
+            let id = self.id()?;
+            client.with_data_source(id.as_str(), |id| {
+                let req = client.api().query_data_source(id, self.body(cursor));
+                client.transport().execute(&req, api::parse_json)
+            })
+        })
+    }
+
+    fn id(&self) -> Result<&NotionId> {
+        self.data_source_id
+            .as_ref()
+            .map_err(|message| Error::InvalidInput(message.clone()))
+    }
+
+    /// Problems that can be found without a round trip.
+    fn validate(&self) -> Result<()> {
+        self.id()?;
+        if !(1..=MAX_PAGE_SIZE).contains(&self.page_size) {
+            return Err(Error::InvalidInput(format!(
+                "page_size must be between 1 and {MAX_PAGE_SIZE}, got {}",
+                self.page_size
+            )));
+        }
+        self.filter.as_ref().map_or(Ok(()), Filter::validate)
+    }
+
+    fn body(&self, cursor: Option<&str>) -> Value {
+        let mut body = Map::new();
+        body.insert("page_size".into(), json!(self.page_size));
+        if let Some(cursor) = cursor {
+            body.insert("start_cursor".into(), json!(cursor));
+        }
+        if let Some(filter) = &self.filter {
+            body.insert("filter".into(), filter.to_value());
+        }
+        if !self.sorts.is_empty() {
+            body.insert("sorts".into(), json!(self.sorts));
+        }
+        Value::Object(body)
+    }
+}
+
+impl<'a> IntoIterator for DataSourceQuery<'a> {
+    type Item = Result<Page>;
+    type IntoIter = Paginator<'a, Page>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.pages()
+    }
+}
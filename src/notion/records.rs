@@ -0,0 +1,123 @@
+use std::marker::PhantomData;
+use std::sync::OnceLock;
+
+use serde_json::{Map, Value};
+
+use super::{
+    export, import, DataSource, NewPage, NotionClient, NotionId, Page, PageUpdate, Parent,
+    PropertyValue,
+};
+use crate::{Backend, Database, Error, Record, Result};
+
+/// The pages of one data source as a [`Database`] of `T`, built with
+/// [`NotionClient::records`].
+///
+/// Fields are matched to properties by their Notion names and converted in
+/// the shapes the Markdown front matter uses: plain text for text, option
+/// names for selects, ids for people and relations, `YYYY-MM-DD` strings or
+/// `{start, end}` objects for dates. The id field is the page id. The
+/// schema is fetched once, on the first write, to pick each property's type.
+///
+/// `put` creates a page when the record has no id and otherwise updates
+/// the page's mapped properties, leaving the rest alone.
+pub struct DataSourceRecords<'a, T> {
+    client: &'a NotionClient,
+    data_source_id: Result<NotionId, String>,
+    schema: OnceLock<DataSource>,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<'a, T: Record> DataSourceRecords<'a, T> {
+    pub(crate) fn new(client: &'a NotionClient, data_source_id: Result<NotionId>) -> Self {
+        Self {
+            client,
+            data_source_id: data_source_id.map_err(|err| err.to_string()),
+            schema: OnceLock::new(),
+            marker: PhantomData,
+        }
+    }
+
+    fn schema(&self) -> Result<&DataSource> {
+        if let Some(schema) = self.schema.get() {
+            return Ok(schema);
+        }
+        let id = self
+            .data_source_id
+            .as_ref()
+            .map_err(|message| Error::InvalidInput(message.clone()))?;
+        let schema = self.client.get_data_source(id)?;
+        Ok(self.schema.get_or_init(|| schema))
+    }
+
+    /// The record's fields as property values, typed by the schema;
+    /// properties Notion computes are left out.
+    fn properties(&self, fields: Map<String, Value>) -> Result<Vec<(String, PropertyValue)>> {
+        let schema = self.schema()?;
+        let mut properties = Vec::new();
+        for (name, value) in fields {
+            if let Some(value) = import::property_value(schema.property_type(&name)?, &value)? {
+                properties.push((name, value));
+            }
+        }
+        Ok(properties)
+    }
+}
+
+impl<T: Record> Database for DataSourceRecords<'_, T> {
+    type Record = T;
+
+    fn get(&self, id: &str) -> Result<T> {
+        let page = self.client.get_page(id)?;
+        T::from_fields(Backend::Notion, fields(&page, T::key(Backend::Notion)))
+    }
+
+    fn put(&self, rec: T) -> Result<()> {
+        let id = rec.id();
+        let mut fields = rec.to_fields(Backend::Notion)?;
+        fields.remove(T::key(Backend::Notion));
+        let properties = self.properties(fields)?;
+        match id {
+            Some(id) => {
+                let update = properties
+                    .into_iter()
+                    .fold(PageUpdate::new(), |update, (name, value)| {
+                        update.with_property(name, value)
+                    });
+                self.client.update_page(id.as_str(), &update)?;
+            }
+            None => {
+                let parent = Parent::DataSource {
+                    data_source_id: self.schema()?.id.clone(),
+                    database_id: None,
+                };
+                let page = properties
+                    .into_iter()
+                    .fold(NewPage::new(parent), |page, (name, value)| {
+                        page.with_property(name, value)
+                    });
+                self.client.create_page(&page)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A page's properties as plain JSON keyed by name, plus its id under
+/// `key`.
+fn fields(page: &Page, key: &str) -> Map<String, Value> {
+    let mut fields: Map<String, Value> = page
+        .properties
+        .iter()
+        .map(|(name, property)| {
+            let value = match &property.value {
+                // Ids rather than display names, so the value can be
+                // written back.
+                PropertyValue::People(users) => users.iter().map(|u| u.id.clone()).collect(),
+                value => export::property_value(value),
+            };
+            (name.clone(), value)
+        })
+        .collect();
+    fields.insert(key.into(), Value::String(page.id.clone()));
+    fields
+}
@@ -0,0 +1,567 @@
+use serde::{Deserialize, Serialize};
+
+use super::keyed::keyed_enum;
+use super::{DateValue, User};
+
+/// Notion rejects text runs longer than this many UTF-16 code units: an
+/// emoji or other character outside the Basic Multilingual Plane counts twice.
+pub const MAX_TEXT_LENGTH: usize = 2000;
+
+/// One run of Notion rich text: a piece of text, a mention or an equation,
+/// with its styling.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RichText {
+    #[serde(flatten)]
+    pub content: RichTextContent,
+    #[serde(default)]
+    pub annotations: Annotations,
+    /// The run rendered as plain text, as Notion computed it.
+    #[serde(default)]
+    pub plain_text: String,
+    #[serde(default)]
+    pub href: Option<String>,
+}
+
+impl RichText {
+    /// Start building a sequence of runs, as in `RichText::new().bold(..)`;
+    /// see [`RichTextBuilder`].
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new() -> RichTextBuilder {
+        RichTextBuilder::default()
+    }
+
+    /// A run holding `content`, unstyled, with `plain_text` and `href` set
+    /// the way Notion would set them.
+    pub fn from_content(content: RichTextContent) -> Self {
+        let (plain_text, href) = match &content {
+            RichTextContent::Text(text) => (
+                text.content.clone(),
+                text.link.as_ref().map(|link| link.url.clone()),
+            ),
+            RichTextContent::Equation(eq) => (eq.expression.clone(), None),
+            RichTextContent::Mention(mention) => (mention.plain_text(), mention.href()),
+            RichTextContent::Unknown { .. } => (String::new(), None),
+        };
+        Self {
+            content,
+            annotations: Annotations::default(),
+            plain_text,
+            href,
+        }
+    }
+}
+
+keyed_enum! {
+    /// What a [`RichText`] run contains.
+    pub enum RichTextContent {
+        Text(Text) = "text",
+        Mention(Mention) = "mention",
+        Equation(Equation) = "equation",
+    }
+}
+
+keyed_enum! {
+    /// What a mention run points to.
+    pub enum Mention {
+        User(User) = "user",
+        Page(ObjectRef) = "page",
+        Database(ObjectRef) = "database",
+        DataSource(ObjectRef) = "data_source",
+        Date(DateValue) = "date",
+        LinkPreview(LinkPreview) = "link_preview",
+        TemplateMention(TemplateMention) = "template_mention",
+    }
+}
+
+keyed_enum! {
+    /// A placeholder in a template, filled in when the template is used.
+    pub enum TemplateMention {
+        /// `"today"` or `"now"`.
+        Date(String) = "template_mention_date",
+        /// `"me"`.
+        User(String) = "template_mention_user",
+    }
+}
+
+impl Mention {
+    /// A stand-in for the text Notion shows. Notion renders page and
+    /// database mentions with their title, which only it knows; runs read
+    /// from Notion carry that in [`RichText::plain_text`].
+    fn plain_text(&self) -> String {
+        match self {
+            Self::User(user) => format!("@{}", user.name().unwrap_or(&user.id)),
+            Self::Page(r) | Self::Database(r) | Self::DataSource(r) => r.id.clone(),
+            Self::Date(date) => match &date.end {
+                Some(end) => format!("{} → {end}", date.start),
+                None => date.start.clone(),
+            },
+            Self::LinkPreview(preview) => preview.url.clone(),
+            Self::TemplateMention(TemplateMention::Date(when)) => format!("@{when}"),
+            Self::TemplateMention(TemplateMention::User(who)) => format!("@{who}"),
+            _ => String::new(),
+        }
+    }
+
+    fn href(&self) -> Option<String> {
+        match self {
+            Self::Page(r) | Self::Database(r) | Self::DataSource(r) => {
+                Some(format!("https://www.notion.so/{}", r.id.replace('-', "")))
+            }
+            Self::LinkPreview(preview) => Some(preview.url.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// The page, database or data source a mention points to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ObjectRef {
+    pub id: String,
+}
+
+/// A link Notion unfurled into a preview.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LinkPreview {
+    pub url: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Text {
+    pub content: String,
+    #[serde(default)]
+    pub link: Option<Link>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Link {
+    pub url: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Equation {
+    pub expression: String,
+}
+
+/// Styling of a rich text run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Annotations {
+    #[serde(default)]
+    pub bold: bool,
+    #[serde(default)]
+    pub italic: bool,
+    #[serde(default)]
+    pub strikethrough: bool,
+    #[serde(default)]
+    pub underline: bool,
+    #[serde(default)]
+    pub code: bool,
+    #[serde(default = "default_color")]
+    pub color: String,
+}
+
+impl Default for Annotations {
+    fn default() -> Self {
+        Self {
+            bold: false,
+            italic: false,
+            strikethrough: false,
+            underline: false,
+            code: false,
+            color: default_color(),
+        }
+    }
+}
+
+fn default_color() -> String {
+    "default".to_string()
+}
+
+/// Builds a sequence of runs, one call per run:
+///
+/// ```
+/// use swivel::notion::{rich_text, RichText};
+///
+/// let runs = RichText::new()
+///     .bold("Note:")
+///     .text(" see ")
+///     .page_mention("275a1865-b187-807a-adea-ebaf36fb49b0")
+///     .build();
+/// assert_eq!(runs.len(), 3);
+/// assert!(rich_text::to_markdown(&runs).starts_with("**Note:** see ["));
+/// ```
+///
+/// [`build`](Self::build) splits text longer than [`MAX_TEXT_LENGTH`] into
+/// several runs, so the result can be sent to Notion as is.
+#[derive(Debug, Clone, Default)]
+pub struct RichTextBuilder {
+    runs: Vec<RichText>,
+}
+
+impl RichTextBuilder {
+    /// Append a run as is.
+    pub fn run(mut self, run: RichText) -> Self {
+        self.runs.push(run);
+        self
+    }
+
+    /// Unstyled text.
+    pub fn text(self, text: impl Into<String>) -> Self {
+        self.styled(text, Annotations::default())
+    }
+
+    /// Text with the given styling, e.g. a color.
+    pub fn styled(self, text: impl Into<String>, annotations: Annotations) -> Self {
+        let mut run = RichText::from_content(RichTextContent::Text(Text {
+            content: text.into(),
+            link: None,
+        }));
+        run.annotations = annotations;
+        self.run(run)
+    }
+
+    pub fn bold(self, text: impl Into<String>) -> Self {
+        self.annotated(text, |a| a.bold = true)
+    }
+
+    pub fn italic(self, text: impl Into<String>) -> Self {
+        self.annotated(text, |a| a.italic = true)
+    }
+
+    pub fn strikethrough(self, text: impl Into<String>) -> Self {
+        self.annotated(text, |a| a.strikethrough = true)
+    }
+
+    pub fn underline(self, text: impl Into<String>) -> Self {
+        self.annotated(text, |a| a.underline = true)
+    }
+
+    /// Inline code.
+    pub fn code(self, text: impl Into<String>) -> Self {
+        self.annotated(text, |a| a.code = true)
+    }
+
+    /// Text linking to `url`.
+    pub fn link(self, text: impl Into<String>, url: impl Into<String>) -> Self {
+        self.run(RichText::from_content(RichTextContent::Text(Text {
+            content: text.into(),
+            link: Some(Link { url: url.into() }),
+        })))
+    }
+
+    /// An inline KaTeX equation.
+    pub fn equation(self, expression: impl Into<String>) -> Self {
+        self.run(RichText::from_content(RichTextContent::Equation(
+            Equation {
+                expression: expression.into(),
+            },
+        )))
+    }
+
+    /// Any mention.
+    pub fn mention(self, mention: Mention) -> Self {
+        self.run(RichText::from_content(RichTextContent::Mention(mention)))
+    }
+
+    pub fn user_mention(self, user_id: impl Into<String>) -> Self {
+        self.mention(Mention::User(User::id(user_id)))
+    }
+
+    pub fn page_mention(self, page_id: impl Into<String>) -> Self {
+        self.mention(Mention::Page(ObjectRef { id: page_id.into() }))
+    }
+
+    pub fn database_mention(self, database_id: impl Into<String>) -> Self {
+        self.mention(Mention::Database(ObjectRef {
+            id: database_id.into(),
+        }))
+    }
+
+    pub fn date_mention(self, date: DateValue) -> Self {
+        self.mention(Mention::Date(date))
+    }
+
+    pub fn link_preview(self, url: impl Into<String>) -> Self {
+        self.mention(Mention::LinkPreview(LinkPreview { url: url.into() }))
+    }
+
+    pub fn template_mention(self, template: TemplateMention) -> Self {
+        self.mention(Mention::TemplateMention(template))
+    }
+
+    /// The runs, with long text split to fit Notion's limit.
+    pub fn build(self) -> Vec<RichText> {
+        split_long_runs(self.runs)
+    }
+
+    fn annotated(self, text: impl Into<String>, style: impl FnOnce(&mut Annotations)) -> Self {
+        let mut annotations = Annotations::default();
+        style(&mut annotations);
+        self.styled(text, annotations)
+    }
+}
+
+impl From<RichTextBuilder> for Vec<RichText> {
+    fn from(builder: RichTextBuilder) -> Self {
+        builder.build()
+    }
+}
+
+/// Concatenate the plain text of a sequence of runs.
+pub fn plain_text(runs: &[RichText]) -> String {
+    runs.iter().map(|r| r.plain_text.as_str()).collect()
+}
+
+/// Unstyled text as runs, split to stay under Notion's per-run length
+/// limit. Empty text gives no runs.
+pub fn from_plain_text(text: &str) -> Vec<RichText> {
+    if text.is_empty() {
+        return Vec::new();
+    }
+    let run = RichText {
+        content: RichTextContent::Text(Text {
+            content: text.to_string(),
+            link: None,
+        }),
+        annotations: Annotations::default(),
+        plain_text: text.to_string(),
+        href: None,
+    };
+    split_long_runs(vec![run])
+}
+
+/// Split text runs longer than [`MAX_TEXT_LENGTH`] into several runs with
+/// the same styling. Length is counted in UTF-16 code units, as Notion
+/// counts it, so an emoji takes two; runs are only split between characters.
+pub fn split_long_runs(runs: Vec<RichText>) -> Vec<RichText> {
+    let mut out = Vec::with_capacity(runs.len());
+    for run in runs {
+        let RichTextContent::Text(text) = &run.content else {
+            out.push(run);
+            continue;
+        };
+        if text.content.encode_utf16().count() <= MAX_TEXT_LENGTH {
+            out.push(run);
+            continue;
+        }
+        let mut chunks = Vec::new();
+        let (mut chunk, mut units) = (String::new(), 0);
+        for c in text.content.chars() {
+            if units + c.len_utf16() > MAX_TEXT_LENGTH {
+                chunks.push(std::mem::take(&mut chunk));
+                units = 0;
+            }
+            chunk.push(c);
+            units += c.len_utf16();
+        }
+        chunks.push(chunk);
+        for chunk in chunks {
+            let mut piece = run.clone();
+            piece.plain_text = chunk.clone();
+            piece.content = RichTextContent::Text(Text {
+                content: chunk,
+                link: text.link.clone(),
+            });
+            out.push(piece);
+        }
+    }
+    out
+}
+
+/// Render a sequence of runs as GitHub-flavored Markdown inline content.
+///
+/// Adjacent runs with the same styling and link are merged so the output
+/// reads `**bold text**` rather than `**bold** **text**`. Underlines, which
+/// Markdown lacks, become `<u>` tags; mentions become links when Notion gave
+/// them an `href`.
+pub fn to_markdown(runs: &[RichText]) -> String {
+    let mut out = String::new();
+    let mut i = 0;
+    while i < runs.len() {
+        if let RichTextContent::Equation(eq) = &runs[i].content {
+            out.push_str(&format!("${}$", eq.expression));
+            i += 1;
+            continue;
+        }
+        let (annotations, link) = (&runs[i].annotations, link_of(&runs[i]));
+        let mut text = String::new();
+        while let Some(run) = runs.get(i) {
+            let same = !matches!(run.content, RichTextContent::Equation(_))
+                && run.annotations == *annotations
+                && link_of(run) == link;
+            if !same {
+                break;
+            }
+            let raw = match &run.content {
+                RichTextContent::Text(t) => t.content.as_str(),
+                _ => run.plain_text.as_str(),
+            };
+            text.push_str(raw);
+            i += 1;
+        }
+        out.push_str(&styled(&text, annotations, link));
+    }
+    out
+}
+
+fn link_of(run: &RichText) -> Option<&str> {
+    match &run.content {
+        RichTextContent::Text(Text {
+            link: Some(link), ..
+        }) => Some(&link.url),
+        _ => run.href.as_deref(),
+    }
+}
+
+/// Render a sequence of runs as HTML inline content.
+///
+/// Annotations become `<strong>`, `<em>`, `<s>`, `<u>` and `<code>`, and
+/// colors a `<span class="notion-{color}">`. Equations are wrapped in
+/// `<span class="equation">` for a math renderer to pick up; mentions become
+/// links when they have an `href`. Only `http`, `https`, `mailto`, `tel` and
+/// relative links are rendered; a `javascript:` or `data:` link leaves its
+/// text unlinked.
+pub fn to_html(runs: &[RichText]) -> String {
+    let mut out = String::new();
+    for run in runs {
+        let mut inner = match &run.content {
+            RichTextContent::Equation(eq) => {
+                out.push_str(&format!(
+                    "<span class=\"equation\">{}</span>",
+                    escape_html(&eq.expression)
+                ));
+                continue;
+            }
+            RichTextContent::Text(t) => escape_html(&t.content),
+            _ => escape_html(&run.plain_text),
+        }
+        .replace('\n', "<br>");
+        let annotations = &run.annotations;
+        for (on, tag) in [
+            (annotations.code, "code"),
+            (annotations.underline, "u"),
+            (annotations.strikethrough, "s"),
+            (annotations.italic, "em"),
+            (annotations.bold, "strong"),
+        ] {
+            if on {
+                inner = format!("<{tag}>{inner}</{tag}>");
+            }
+        }
+        if annotations.color != "default" {
+            inner = format!(
+                "<span class=\"notion-{}\">{inner}</span>",
+                escape_html(&annotations.color)
+            );
+        }
+        if let Some(url) = link_of(run).filter(|url| is_safe_href(url)) {
+            inner = format!("<a href=\"{}\">{inner}</a>", escape_html(url));
+        }
+        out.push_str(&inner);
+    }
+    out
+}
+
+/// Whether `url` is safe to put in an `href`: relative, or with a scheme
+/// that cannot run script. Browsers skip whitespace and control characters
+/// inside a scheme, so they are dropped before checking.
+fn is_safe_href(url: &str) -> bool {
+    let url: String = url
+        .chars()
+        .filter(|c| !c.is_whitespace() && !c.is_control())
+        .collect();
+    match url.split_once(':') {
+        Some((scheme, _)) if !scheme.contains(['/', '?', '#']) => matches!(
+            scheme.to_ascii_lowercase().as_str(),
+            "http" | "https" | "mailto" | "tel"
+        ),
+        _ => true,
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Wrap `text` in the Markdown for its annotations and link, keeping
+/// surrounding whitespace outside the markers so they stay valid.
+fn styled(text: &str, annotations: &Annotations, link: Option<&str>) -> String {
+    let body = text.trim();
+    if body.is_empty() {
+        return escape(text);
+    }
+    let start = text.len() - text.trim_start().len();
+    let (lead, trail) = (&text[..start], &text[start + body.len()..]);
+
+    let mut inner = if annotations.code {
+        code_span(body)
+    } else {
+        escape(body)
+    };
+    for (on, open, close) in [
+        (annotations.underline, "<u>", "</u>"),
+        (annotations.strikethrough, "~~", "~~"),
+        (annotations.italic, "*", "*"),
+        (annotations.bold, "**", "**"),
+    ] {
+        if on {
+            inner = format!("{open}{inner}{close}");
+        }
+    }
+    if let Some(url) = link {
+        inner = format!("[{inner}]({})", link_destination(url));
+    }
+    format!("{}{inner}{}", escape(lead), escape(trail))
+}
+
+/// A URL as a Markdown link destination. One holding whitespace,
+/// parentheses or angle brackets goes inside `<...>`, where only the
+/// brackets themselves and line breaks need escaping.
+pub(crate) fn link_destination(url: &str) -> String {
+    if !url.contains(|c: char| c.is_whitespace() || "()<>".contains(c)) {
+        return url.to_string();
+    }
+    let escaped = url
+        .replace('<', "%3C")
+        .replace('>', "%3E")
+        .replace('\n', "%0A")
+        .replace('\r', "%0D");
+    format!("<{escaped}>")
+}
+
+fn code_span(text: &str) -> String {
+    let longest = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest + 1);
+    let pad = if text.starts_with('`') || text.ends_with('`') {
+        " "
+    } else {
+        ""
+    };
+    format!("{fence}{pad}{text}{pad}{fence}")
+}
+
+/// Backslash-escape characters Markdown would treat as syntax, and turn
+/// line breaks inside a run into hard breaks.
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '~' | '$' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '\n' => out.push_str("\\\n"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
@@ -0,0 +1,638 @@
+//! Declarative data source schemas: compare a schema file with what is in
+//! Notion and create or update the data source to match.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use super::rich_text;
+use super::{DataSource, NotionId, PropertySchema};
+use crate::{Error, Result};
+
+/// The layout a data source should have, read from a TOML or JSON file.
+///
+/// ```toml
+/// title = "Tasks"
+/// parent = "https://www.notion.so/acme/Projects-275a1865b187807aadeaebaf36fb49b0"
+///
+/// [properties.Name]
+/// type = "title"
+///
+/// [properties.Status]
+/// type = "select"
+/// options = [
+///     "Todo",
+///     { name = "Done", color = "green" },
+///     { name = "Won't do", renamed_from = "Dropped" },
+/// ]
+///
+/// [properties."Blocked by"]
+/// type = "relation"
+/// data_source = "b5ad9a34-4e2f-4f8e-9c3c-1b0e6d4f7a21"
+///
+/// [properties.Estimate]
+/// type = "number"
+/// format = "number_with_commas"
+/// renamed_from = "Points"
+/// ```
+///
+/// Applying a schema only adds and renames: properties, select options and
+/// configuration it does not mention are left as they are. A property or a
+/// select option with `renamed_from` is renamed in place, keeping its id
+/// and the values that use it. Status options cannot be set through the
+/// API, so a `status` property takes no `options`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SchemaSpec {
+    /// The data source's title, and its database's when one is created.
+    /// Without an `id`, the data source is looked up by this title.
+    pub title: String,
+    /// The data source, or its database, to update.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// The page to create the database under when there is none yet.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    /// Property definitions keyed by property name.
+    #[serde(default)]
+    pub properties: BTreeMap<String, PropertySpec>,
+}
+
+/// One property of a [`SchemaSpec`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PropertySpec {
+    /// The property type, e.g. `"number"` or `"multi_select"`.
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Select and multi-select options.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<OptionSpec>,
+    /// The data source a relation points to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_source: Option<String>,
+    /// A formula's expression.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expression: Option<String>,
+    /// A number's format, e.g. `"dollar"`; Notion's `"number"` when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    /// The property's current name, to rename it instead of adding a new
+    /// one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub renamed_from: Option<String>,
+}
+
+/// A select option: a bare name, or a name with a color and the option's
+/// current name when it is to be renamed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "Value")]
+pub struct OptionSpec {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    /// The option's current name, to rename it instead of adding a new one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub renamed_from: Option<String>,
+}
+
+impl OptionSpec {
+    /// The option as Notion takes it when it is added.
+    fn to_json(&self) -> Value {
+        match &self.color {
+            Some(color) => json!({ "name": self.name, "color": color }),
+            None => json!({ "name": self.name }),
+        }
+    }
+}
+
+/// The table form of an option, `{ name = "Done", color = "green" }`.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct OptionFields {
+    name: String,
+    #[serde(default)]
+    color: Option<String>,
+    #[serde(default)]
+    renamed_from: Option<String>,
+}
+
+impl TryFrom<Value> for OptionSpec {
+    type Error = String;
+
+    fn try_from(value: Value) -> std::result::Result<Self, String> {
+        if let Value::String(name) = value {
+            return Ok(Self {
+                name,
+                color: None,
+                renamed_from: None,
+            });
+        }
+        let OptionFields {
+            name,
+            color,
+            renamed_from,
+        } = serde_json::from_value(value).map_err(|err| format!("select option: {err}"))?;
+        Ok(Self {
+            name,
+            color,
+            renamed_from,
+        })
+    }
+}
+
+/// Types whose configuration is empty, so a spec needs nothing but the
+/// type.
+const PLAIN_KINDS: &[&str] = &[
+    "title",
+    "rich_text",
+    "status",
+    "date",
+    "people",
+    "files",
+    "checkbox",
+    "url",
+    "email",
+    "phone_number",
+    "created_time",
+    "created_by",
+    "last_edited_time",
+    "last_edited_by",
+];
+
+impl SchemaSpec {
+    pub fn from_toml(source: &str) -> Result<Self> {
+        let spec: Self = toml::from_str(source)
+            .map_err(|err| Error::InvalidInput(format!("schema file: {err}")))?;
+        spec.validate()?;
+        Ok(spec)
+    }
+
+    pub fn from_json(source: &str) -> Result<Self> {
+        let spec: Self = serde_json::from_str(source)
+            .map_err(|err| Error::InvalidInput(format!("schema file: {err}")))?;
+        spec.validate()?;
+        Ok(spec)
+    }
+
+    /// Problems that can be found without a round trip: exactly one title
+    /// property, known types, and each setting on the type it belongs to.
+    pub fn validate(&self) -> Result<()> {
+        let titles = self.properties.values().filter(|p| p.kind == "title");
+        if titles.count() != 1 {
+            return Err(Error::InvalidInput(format!(
+                "schema `{}` needs exactly one `title` property",
+                self.title
+            )));
+        }
+        for id in [&self.id, &self.parent].into_iter().flatten() {
+            id.parse::<NotionId>()?;
+        }
+        for (name, property) in &self.properties {
+            property
+                .validate()
+                .map_err(|message| Error::InvalidInput(format!("property `{name}`: {message}")))?;
+        }
+        Ok(())
+    }
+}
+
+impl PropertySpec {
+    fn validate(&self) -> std::result::Result<(), String> {
+        let kind = self.kind.as_str();
+        let known = PLAIN_KINDS.contains(&kind)
+            || matches!(
+                kind,
+                "number" | "select" | "multi_select" | "relation" | "formula"
+            );
+        if !known {
+            return Err(format!("type `{kind}` cannot be set up from a schema file"));
+        }
+        let misplaced = [
+            (
+                "options",
+                !self.options.is_empty(),
+                kind == "select" || kind == "multi_select",
+            ),
+            (
+                "data_source",
+                self.data_source.is_some(),
+                kind == "relation",
+            ),
+            ("expression", self.expression.is_some(), kind == "formula"),
+            ("format", self.format.is_some(), kind == "number"),
+        ];
+        for (setting, given, allowed) in misplaced {
+            if given && !allowed {
+                return Err(format!("`{setting}` does not apply to a `{kind}` property"));
+            }
+        }
+        match kind {
+            "relation" => match &self.data_source {
+                Some(id) => id
+                    .parse::<NotionId>()
+                    .map(drop)
+                    .map_err(|err| err.to_string()),
+                None => Err("a relation needs `data_source`".into()),
+            },
+            "formula" if self.expression.is_none() => Err("a formula needs `expression`".into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// The type's configuration object, as sent on create and update.
+    /// Before data sources, relations point to a database instead.
+    fn config(&self, legacy: bool) -> Value {
+        match self.kind.as_str() {
+            "number" => json!({ "format": self.format.as_deref().unwrap_or("number") }),
+            "select" | "multi_select" => {
+                let options: Vec<_> = self.options.iter().map(OptionSpec::to_json).collect();
+                json!({ "options": options })
+            }
+            "relation" => {
+                let target = self.target().unwrap_or_default();
+                let key = if legacy {
+                    "database_id"
+                } else {
+                    "data_source_id"
+                };
+                json!({ key: target, "type": "single_property", "single_property": {} })
+            }
+            "formula" => json!({ "expression": self.expression }),
+            _ => json!({}),
+        }
+    }
+
+    /// A relation's target in canonical form.
+    fn target(&self) -> Option<String> {
+        let id = self.data_source.as_deref()?.parse::<NotionId>().ok()?;
+        Some(id.to_string())
+    }
+}
+
+/// Where a [`SchemaPlan`] applies.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SchemaTarget {
+    /// Create a database with one data source under a page.
+    Create { parent: String },
+    /// Update an existing data source.
+    Update { data_source_id: String },
+}
+
+/// One difference between a [`SchemaSpec`] and the live schema.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SchemaChange {
+    AddProperty {
+        name: String,
+        kind: String,
+    },
+    RenameProperty {
+        from: String,
+        to: String,
+    },
+    RetypeProperty {
+        name: String,
+        from: String,
+        to: String,
+    },
+    AddOptions {
+        name: String,
+        options: Vec<String>,
+    },
+    /// A select option renamed in place, keeping its id.
+    RenameOption {
+        name: String,
+        from: String,
+        to: String,
+    },
+    /// A formula expression, number format or relation target that differs.
+    UpdateProperty {
+        name: String,
+        setting: String,
+    },
+}
+
+impl fmt::Display for SchemaChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AddProperty { name, kind } => write!(f, "+ {name}: {kind}"),
+            Self::RenameProperty { from, to } => write!(f, "~ {from}: rename to {to}"),
+            Self::RetypeProperty { name, from, to } => write!(f, "~ {name}: {from} -> {to}"),
+            Self::AddOptions { name, options } => {
+                write!(f, "+ {name}: options {}", options.join(", "))
+            }
+            Self::RenameOption { name, from, to } => {
+                write!(f, "~ {name}: rename option {from} to {to}")
+            }
+            Self::UpdateProperty { name, setting } => write!(f, "~ {name}: {setting}"),
+        }
+    }
+}
+
+/// What applying a [`SchemaSpec`] would do, built with
+/// [`NotionClient::plan_schema`](super::NotionClient::plan_schema) or
+/// [`SchemaPlan::new`] and carried out with
+/// [`NotionClient::apply_schema`](super::NotionClient::apply_schema).
+///
+/// Its `Display` is the plan as a diff, one change per line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaPlan {
+    pub target: SchemaTarget,
+    pub changes: Vec<SchemaChange>,
+    spec: SchemaSpec,
+    existing: Option<DataSource>,
+}
+
+impl SchemaPlan {
+    /// Compare `spec` with `existing`, the data source as it is now, or
+    /// plan to create it under `spec.parent` when there is none.
+    pub fn new(spec: &SchemaSpec, existing: Option<&DataSource>) -> Result<Self> {
+        spec.validate()?;
+        let Some(existing) = existing else {
+            let parent = spec.parent.as_deref().ok_or_else(|| {
+                Error::InvalidInput(format!(
+                    "no data source `{}` yet, and the schema has no `parent` to create it under",
+                    spec.title
+                ))
+            })?;
+            let changes = spec
+                .properties
+                .iter()
+                .map(|(name, p)| SchemaChange::AddProperty {
+                    name: name.clone(),
+                    kind: p.kind.clone(),
+                })
+                .collect();
+            return Ok(Self {
+                target: SchemaTarget::Create {
+                    parent: parent.parse::<NotionId>()?.to_string(),
+                },
+                changes,
+                spec: spec.clone(),
+                existing: None,
+            });
+        };
+
+        let mut changes = Vec::new();
+        for (name, property) in &spec.properties {
+            let Some(current) = current(existing, name, property) else {
+                changes.push(SchemaChange::AddProperty {
+                    name: name.clone(),
+                    kind: property.kind.clone(),
+                });
+                continue;
+            };
+            if &current.name != name {
+                if existing.properties.contains_key(name) {
+                    return Err(Error::InvalidInput(format!(
+                        "cannot rename `{}` to `{name}`: that name is taken",
+                        current.name
+                    )));
+                }
+                changes.push(SchemaChange::RenameProperty {
+                    from: current.name.clone(),
+                    to: name.clone(),
+                });
+            }
+            if current.kind != property.kind {
+                changes.push(SchemaChange::RetypeProperty {
+                    name: name.clone(),
+                    from: current.kind.clone(),
+                    to: property.kind.clone(),
+                });
+                continue;
+            }
+            changes.extend(differences(name, current, property));
+        }
+        Ok(Self {
+            target: SchemaTarget::Update {
+                data_source_id: existing.id.clone(),
+            },
+            changes,
+            spec: spec.clone(),
+            existing: Some(existing.clone()),
+        })
+    }
+
+    /// Whether there is nothing to do.
+    pub fn is_empty(&self) -> bool {
+        matches!(self.target, SchemaTarget::Update { .. }) && self.changes.is_empty()
+    }
+
+    /// The data source as it is now, when it exists.
+    pub fn existing(&self) -> Option<&DataSource> {
+        self.existing.as_ref()
+    }
+
+    /// The `POST /databases` body that creates the database and its data
+    /// source with every property.
+    pub(crate) fn create_body(&self, parent: &str, legacy: bool) -> Value {
+        let properties: Map<String, Value> = self
+            .spec
+            .properties
+            .iter()
+            .map(|(name, p)| (name.clone(), json!({ &p.kind: p.config(legacy) })))
+            .collect();
+        let mut body = json!({
+            "parent": { "type": "page_id", "page_id": parent },
+            "title": rich_text::from_plain_text(&self.spec.title),
+        });
+        if legacy {
+            body["properties"] = Value::Object(properties);
+        } else {
+            body["initial_data_source"] = json!({ "properties": properties });
+        }
+        body
+    }
+
+    /// The name a property in the spec has in Notion before the changes.
+    fn current_name<'a>(&'a self, name: &'a str) -> &'a str {
+        self.changes
+            .iter()
+            .find_map(|change| match change {
+                SchemaChange::RenameProperty { from, to } if to == name => Some(from.as_str()),
+                _ => None,
+            })
+            .unwrap_or(name)
+    }
+
+    /// The `PATCH` body for the changes, each property addressed by its
+    /// current name.
+    pub(crate) fn update_body(&self, legacy: bool) -> Value {
+        let mut properties = Map::new();
+        for change in &self.changes {
+            match change {
+                SchemaChange::RenameProperty { to, .. } => {
+                    entry(&mut properties, self.current_name(to)).insert("name".into(), json!(to));
+                }
+                SchemaChange::AddProperty { name, .. }
+                | SchemaChange::RetypeProperty { name, .. }
+                | SchemaChange::UpdateProperty { name, .. } => {
+                    let spec = &self.spec.properties[name];
+                    entry(&mut properties, self.current_name(name))
+                        .insert(spec.kind.clone(), spec.config(legacy));
+                }
+                SchemaChange::AddOptions { name, .. } | SchemaChange::RenameOption { name, .. } => {
+                    let spec = &self.spec.properties[name];
+                    entry(&mut properties, self.current_name(name))
+                        .insert(spec.kind.clone(), json!({ "options": self.options(name) }));
+                }
+            }
+        }
+        json!({ "properties": properties })
+    }
+
+    /// The full option list to send for property `name`. Options left out
+    /// are deleted, so the current ones go first, as Notion sent them:
+    /// renamed ones keep their id under the new name. Added ones follow.
+    fn options(&self, name: &str) -> Vec<Value> {
+        let spec = &self.spec.properties[name];
+        let mut all = self
+            .existing
+            .as_ref()
+            .and_then(|existing| current(existing, name, spec))
+            .map(|current| current_options(current).to_vec())
+            .unwrap_or_default();
+        for change in &self.changes {
+            match change {
+                SchemaChange::RenameOption { name: n, from, to } if n == name => {
+                    let renamed = all
+                        .iter_mut()
+                        .find(|o| o.get("name").and_then(Value::as_str) == Some(from));
+                    if let Some(option) = renamed {
+                        option["name"] = json!(to);
+                    }
+                }
+                SchemaChange::AddOptions { name: n, options } if n == name => all.extend(
+                    spec.options
+                        .iter()
+                        .filter(|o| options.contains(&o.name))
+                        .map(OptionSpec::to_json),
+                ),
+                _ => {}
+            }
+        }
+        all
+    }
+}
+
+impl fmt::Display for SchemaPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let title = &self.spec.title;
+        match &self.target {
+            SchemaTarget::Create { parent } => {
+                writeln!(f, "create database {title:?} under page {parent}")?
+            }
+            SchemaTarget::Update { .. } if self.changes.is_empty() => {
+                return writeln!(f, "data source {title:?} is up to date");
+            }
+            SchemaTarget::Update { data_source_id } => {
+                writeln!(f, "update data source {title:?} ({data_source_id})")?
+            }
+        }
+        for change in &self.changes {
+            writeln!(f, "  {change}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The object for `name` in a `properties` body, added when missing.
+fn entry<'m>(properties: &'m mut Map<String, Value>, name: &str) -> &'m mut Map<String, Value> {
+    let value = properties.entry(name).or_insert_with(|| json!({}));
+    value.as_object_mut().expect("an object")
+}
+
+/// The live property `spec` describes: the one with its name, the one it
+/// was `renamed_from`, or for a title whatever the title is called now.
+fn current<'a>(
+    existing: &'a DataSource,
+    name: &str,
+    spec: &PropertySpec,
+) -> Option<&'a PropertySchema> {
+    existing
+        .properties
+        .get(name)
+        .or_else(|| existing.properties.get(spec.renamed_from.as_deref()?))
+        .or_else(|| {
+            (spec.kind == "title")
+                .then(|| existing.properties.values().find(|p| p.kind == "title"))
+                .flatten()
+        })
+}
+
+fn current_options(current: &PropertySchema) -> &[Value] {
+    current
+        .extra
+        .get(&current.kind)
+        .and_then(|c| c.get("options"))
+        .and_then(Value::as_array)
+        .map_or(&[], Vec::as_slice)
+}
+
+/// Changes to a property that keeps its type.
+fn differences(name: &str, current: &PropertySchema, spec: &PropertySpec) -> Vec<SchemaChange> {
+    let config = current
+        .extra
+        .get(&current.kind)
+        .cloned()
+        .unwrap_or_default();
+    let setting = |key: &str| config.get(key).and_then(Value::as_str).map(String::from);
+    let update = |setting: &str| SchemaChange::UpdateProperty {
+        name: name.to_string(),
+        setting: setting.to_string(),
+    };
+    match spec.kind.as_str() {
+        "select" | "multi_select" => {
+            let have: Vec<_> = current_options(current)
+                .iter()
+                .filter_map(|o| o.get("name")?.as_str())
+                .collect();
+            let mut changes = Vec::new();
+            let mut missing = Vec::new();
+            for option in &spec.options {
+                if have.contains(&option.name.as_str()) {
+                    continue;
+                }
+                match &option.renamed_from {
+                    Some(from) if have.contains(&from.as_str()) => {
+                        changes.push(SchemaChange::RenameOption {
+                            name: name.to_string(),
+                            from: from.clone(),
+                            to: option.name.clone(),
+                        })
+                    }
+                    _ => missing.push(option.name.clone()),
+                }
+            }
+            if !missing.is_empty() {
+                changes.push(SchemaChange::AddOptions {
+                    name: name.to_string(),
+                    options: missing,
+                });
+            }
+            changes
+        }
+        "number" => match &spec.format {
+            Some(format) if setting("format").as_ref() != Some(format) => {
+                vec![update(&format!("format {format}"))]
+            }
+            _ => Vec::new(),
+        },
+        "formula" if setting("expression") != spec.expression => vec![update("expression")],
+        "relation" => {
+            let target = setting("data_source_id").or_else(|| setting("database_id"));
+            let same = target
+                .and_then(|t| t.parse::<NotionId>().ok())
+                .is_some_and(|t| Some(t.to_string()) == spec.target());
+            if same {
+                Vec::new()
+            } else {
+                vec![update("relation target")]
+            }
+        }
+        _ => Vec::new(),
+    }
+}
@@ -0,0 +1,216 @@
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{json, Map, Value};
+
+use super::api;
+use super::pagination::Paginator;
+use super::query::MAX_PAGE_SIZE;
+use super::{DataSource, Direction, NotionClient, Page, Parent};
+use crate::{Error, Result};
+
+/// The object types a search can be narrowed to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchObject {
+    Page,
+    DataSource,
+}
+
+/// A title search across everything shared with the integration, built with
+/// [`NotionClient::search`].
+///
+/// Like [`DataSourceQuery`](super::DataSourceQuery), nothing is sent until
+/// the search is iterated, and pages of results are fetched on demand.
+///
+/// ```no_run
+/// # use swivel::notion::{Direction, NotionClient, SearchObject};
+/// let notion = NotionClient::from_env()?;
+/// let hits = notion
+///     .search("Launch plan")
+///     .object(SearchObject::Page)
+///     .sort(Direction::Descending);
+/// for hit in hits {
+///     let hit = hit?;
+///     println!("{} {}", hit.id(), hit.title());
+/// }
+/// # Ok::<(), swivel::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct Search<'a> {
+    client: &'a NotionClient,
+    query: String,
+    page_size: u32,
+    object: Option<SearchObject>,
+    direction: Option<Direction>,
+}
+
+impl<'a> Search<'a> {
+    pub(crate) fn new(client: &'a NotionClient, query: &str) -> Self {
+        Self {
+            client,
+            query: query.to_string(),
+            page_size: MAX_PAGE_SIZE,
+            object: None,
+            direction: None,
+        }
+    }
+
+    /// Results per request, 1 to [`MAX_PAGE_SIZE`] (the default).
+    pub fn page_size(mut self, page_size: u32) -> Self {
+        self.page_size = page_size;
+        self
+    }
+
+    /// Only return pages, or only data sources.
+    pub fn object(mut self, object: SearchObject) -> Self {
+        self.object = Some(object);
+        self
+    }
+
+    /// Order by last edited time, the only sort search supports. Without
+    /// it, Notion orders by relevance.
+    pub fn sort(mut self, direction: Direction) -> Self {
+        self.direction = Some(direction);
+        self
+    }
+
+    /// Lazily iterate over every result.
+    pub fn results(self) -> Paginator<'a, SearchResult> {
+        let client = self.client;
+        let mut invalid = self.validate().err();
+        Paginator::new(move |cursor| {
+            if let Some(err) = invalid.take() {
+                return Err(err);
+            }
+            let req = client.api().search(self.body(cursor));
+            client.transport().execute(&req, api::parse_json)
+        })
+    }
+
+    fn validate(&self) -> Result<()> {
+        if !(1..=MAX_PAGE_SIZE).contains(&self.page_size) {
+            return Err(Error::InvalidInput(format!(
+                "page_size must be between 1 and {MAX_PAGE_SIZE}, got {}",
+                self.page_size
+            )));
+        }
+        Ok(())
+    }
+
+    fn body(&self, cursor: Option<&str>) -> Value {
+        let mut body = Map::new();
+        if !self.query.is_empty() {
+            body.insert("query".into(), json!(self.query));
+        }
+        body.insert("page_size".into(), json!(self.page_size));
+        if let Some(cursor) = cursor {
+            body.insert("start_cursor".into(), json!(cursor));
+        }
+        if let Some(object) = self.object {
+            body.insert(
+                "filter".into(),
+                json!({ "property": "object", "value": object }),
+            );
+        }
+        if let Some(direction) = self.direction {
+            body.insert(
+                "sort".into(),
+                json!({ "timestamp": "last_edited_time", "direction": direction }),
+            );
+        }
+        Value::Object(body)
+    }
+}
+
+impl<'a> IntoIterator for Search<'a> {
+    type Item = Result<SearchResult>;
+    type IntoIter = Paginator<'a, SearchResult>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.results()
+    }
+}
+
+/// One search result, typed by its `object` field.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SearchResult {
+    Page(Box<Page>),
+    DataSource(Box<DataSource>),
+    /// An object type this crate does not know yet, kept verbatim.
+    Unknown(Map<String, Value>),
+}
+
+impl SearchResult {
+    pub fn id(&self) -> &str {
+        match self {
+            Self::Page(page) => &page.id,
+            Self::DataSource(data_source) => &data_source.id,
+            Self::Unknown(map) => map.get("id").and_then(Value::as_str).unwrap_or_default(),
+        }
+    }
+
+    /// The `object` tag, e.g. `"page"`.
+    pub fn object(&self) -> &str {
+        match self {
+            Self::Page(_) => "page",
+            Self::DataSource(_) => "data_source",
+            Self::Unknown(map) => map
+                .get("object")
+                .and_then(Value::as_str)
+                .unwrap_or_default(),
+        }
+    }
+
+    /// The plain text title, empty if there is none.
+    pub fn title(&self) -> String {
+        match self {
+            Self::Page(page) => page.title(),
+            Self::DataSource(data_source) => data_source.title(),
+            Self::Unknown(_) => String::new(),
+        }
+    }
+
+    pub fn url(&self) -> Option<&str> {
+        match self {
+            Self::Page(page) => page.url.as_deref(),
+            Self::DataSource(data_source) => data_source.url.as_deref(),
+            Self::Unknown(map) => map.get("url").and_then(Value::as_str),
+        }
+    }
+
+    pub fn parent(&self) -> Option<&Parent> {
+        match self {
+            Self::Page(page) => Some(&page.parent),
+            Self::DataSource(data_source) => data_source.parent.as_ref(),
+            Self::Unknown(_) => None,
+        }
+    }
+}
+
+impl Serialize for SearchResult {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Page(page) => page.serialize(serializer),
+            Self::DataSource(data_source) => data_source.serialize(serializer),
+            Self::Unknown(map) => map.serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SearchResult {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let map = Map::deserialize(deserializer)?;
+        let object = map.get("object").and_then(Value::as_str);
+        Ok(match object {
+            Some("page") => {
+                Self::Page(serde_json::from_value(map.into()).map_err(D::Error::custom)?)
+            }
+            // Before data sources, search returns databases with their
+            // schema, which is what those versions query.
+            Some("data_source") | Some("database") if map.contains_key("properties") => {
+                Self::DataSource(serde_json::from_value(map.into()).map_err(D::Error::custom)?)
+            }
+            _ => Self::Unknown(map),
+        })
+    }
+}
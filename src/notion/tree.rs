@@ -0,0 +1,222 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use super::{Block, BlockContent, NotionClient};
+use crate::Result;
+
+/// How [`NotionClient::page_content`] walks a page.
+///
+/// By default the whole page is fetched, four requests at a time, without
+/// descending into child pages or child databases but following synced
+/// blocks, since their content is part of the page.
+#[derive(Debug, Clone)]
+pub struct ContentOptions {
+    max_depth: Option<usize>,
+    concurrency: usize,
+    child_pages: bool,
+    child_databases: bool,
+    synced_blocks: bool,
+}
+
+impl Default for ContentOptions {
+    fn default() -> Self {
+        Self {
+            max_depth: None,
+            concurrency: 4,
+            child_pages: false,
+            child_databases: false,
+            synced_blocks: true,
+        }
+    }
+}
+
+impl ContentOptions {
+    /// Fetch at most `depth` levels; 1 means the page's top-level blocks only.
+    pub fn with_max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Requests in flight at once (at least 1). The client's rate limiter
+    /// still applies on top.
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Descend into child pages.
+    pub fn with_child_pages(mut self, descend: bool) -> Self {
+        self.child_pages = descend;
+        self
+    }
+
+    /// Descend into child databases.
+    pub fn with_child_databases(mut self, descend: bool) -> Self {
+        self.child_databases = descend;
+        self
+    }
+
+    /// Descend into synced blocks.
+    pub fn with_synced_blocks(mut self, descend: bool) -> Self {
+        self.synced_blocks = descend;
+        self
+    }
+
+    fn descends_into(&self, block: &Block) -> bool {
+        block.has_children
+            && match block.content {
+                BlockContent::ChildPage(_) => self.child_pages,
+                BlockContent::ChildDatabase(_) => self.child_databases,
+                BlockContent::SyncedBlock(_) => self.synced_blocks,
+                _ => true,
+            }
+    }
+}
+
+/// The content of a page: its blocks, each with its own children.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct BlockTree {
+    pub blocks: Vec<BlockNode>,
+}
+
+/// A block together with its fetched children.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockNode {
+    pub block: Block,
+    pub children: Vec<BlockNode>,
+}
+
+impl BlockNode {
+    /// Whether the block has children that were not fetched, because of a
+    /// stop point or the depth limit.
+    pub fn is_truncated(&self) -> bool {
+        self.block.has_children && self.children.is_empty()
+    }
+}
+
+impl BlockTree {
+    /// Every block, depth first, with its depth (0 for top-level blocks).
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &Block)> {
+        let mut stack: Vec<(usize, &BlockNode)> =
+            self.blocks.iter().rev().map(|node| (0, node)).collect();
+        std::iter::from_fn(move || {
+            let (depth, node) = stack.pop()?;
+            stack.extend(node.children.iter().rev().map(|child| (depth + 1, child)));
+            Some((depth, &node.block))
+        })
+    }
+
+    /// Total number of blocks in the tree.
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+}
+
+/// A fetched block and the arena indices of its children.
+struct Slot {
+    block: Block,
+    children: Vec<usize>,
+}
+
+/// Fetch the tree under `root` level by level: each level's children
+/// requests run in parallel, at most `options.concurrency` at a time.
+pub(crate) fn fetch(
+    client: &NotionClient,
+    root: &str,
+    options: &ContentOptions,
+) -> Result<BlockTree> {
+    let mut arena: Vec<Slot> = Vec::new();
+    let push = |arena: &mut Vec<Slot>, blocks: Vec<Block>| -> Vec<usize> {
+        let start = arena.len();
+        arena.extend(blocks.into_iter().map(|block| Slot {
+            block,
+            children: Vec::new(),
+        }));
+        (start..arena.len()).collect()
+    };
+
+    let top = push(&mut arena, children(client, root)?);
+    let mut frontier = top.clone();
+    let mut depth = 1;
+    while options.max_depth.is_none_or(|max| depth < max) {
+        frontier.retain(|&i| options.descends_into(&arena[i].block));
+        if frontier.is_empty() {
+            break;
+        }
+        let ids: Vec<&str> = frontier
+            .iter()
+            .map(|&i| arena[i].block.id.as_str())
+            .collect();
+        let fetched = in_parallel(&ids, options.concurrency, |id| children(client, id))?;
+        let mut next = Vec::new();
+        for (parent, blocks) in frontier.iter().zip(fetched) {
+            let kids = push(&mut arena, blocks);
+            next.extend(&kids);
+            arena[*parent].children = kids;
+        }
+        frontier = next;
+        depth += 1;
+    }
+
+    let mut slots: Vec<Option<Slot>> = arena.into_iter().map(Some).collect();
+    Ok(BlockTree {
+        blocks: top.into_iter().map(|i| assemble(&mut slots, i)).collect(),
+    })
+}
+
+fn assemble(slots: &mut [Option<Slot>], i: usize) -> BlockNode {
+    let slot = slots[i].take().expect("each block has one parent");
+    BlockNode {
+        block: slot.block,
+        children: slot
+            .children
+            .into_iter()
+            .map(|child| assemble(slots, child))
+            .collect(),
+    }
+}
+
+/// Every child of one block, following pagination.
+fn children(client: &NotionClient, block_id: &str) -> Result<Vec<Block>> {
+    client.child_blocks(block_id.to_string()).collect()
+}
+
+/// Run `job` over `inputs` on up to `workers` threads, keeping input order.
+/// Stops handing out work after the first error and returns it.
+fn in_parallel<T: Send>(
+    inputs: &[&str],
+    workers: usize,
+    job: impl Fn(&str) -> Result<T> + Sync,
+) -> Result<Vec<T>> {
+    let next = AtomicUsize::new(0);
+    let failed = AtomicBool::new(false);
+    let results: Mutex<Vec<Option<Result<T>>>> = Mutex::new(inputs.iter().map(|_| None).collect());
+    thread::scope(|scope| {
+        for _ in 0..workers.min(inputs.len()) {
+            scope.spawn(|| {
+                while !failed.load(Ordering::SeqCst) {
+                    let i = next.fetch_add(1, Ordering::SeqCst);
+                    let Some(input) = inputs.get(i) else { break };
+                    let result = job(input);
+                    if result.is_err() {
+                        failed.store(true, Ordering::SeqCst);
+                    }
+                    results.lock().unwrap()[i] = Some(result);
+                }
+            });
+        }
+    });
+    // Slots left unfilled after a failure are skipped; collecting stops at
+    // the first error.
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .flatten()
+        .collect()
+}
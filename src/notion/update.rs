@@ -0,0 +1,211 @@
+use std::collections::BTreeMap;
+
+use serde::de::{DeserializeOwned, Error as _};
+use serde::{Deserialize, Deserializer};
+use serde_json::{json, Map, Value};
+
+use super::common::{Cover, Icon, Parent};
+use super::create::NewPage;
+use super::property::{Property, PropertyValue};
+use super::Page;
+
+/// Changes to an existing page, for [`NotionClient::update_page`]. Fields
+/// left unset are not sent, so Notion leaves them as they are.
+///
+/// [`NotionClient::update_page`]: super::NotionClient::update_page
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PageUpdate {
+    /// New property values keyed by property name.
+    pub properties: BTreeMap<String, PropertyValue>,
+    /// `Some(None)` removes the icon.
+    pub icon: Option<Option<Icon>>,
+    /// `Some(None)` removes the cover.
+    pub cover: Option<Option<Cover>>,
+    /// Move the page to the trash, or restore it.
+    pub in_trash: Option<bool>,
+}
+
+impl PageUpdate {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The changes that turn `current` into `desired`: every writable
+    /// property whose value differs, plus the icon, cover and trash state
+    /// when they differ. Properties missing from `desired` are left alone.
+    pub fn diff(current: &Page, desired: &Page) -> Self {
+        let properties = desired
+            .properties
+            .iter()
+            .filter(|(_, p)| !p.value.is_read_only())
+            .filter(|(name, p)| current.prop(name) != Some(&p.value))
+            .map(|(name, p)| (name.clone(), p.value.clone()))
+            .collect();
+        Self {
+            properties,
+            icon: changed(&current.icon, &desired.icon),
+            cover: changed(&current.cover, &desired.cover),
+            in_trash: changed(&current.in_trash, &desired.in_trash),
+        }
+    }
+
+    /// Set the property called `name`.
+    pub fn with_property(mut self, name: impl Into<String>, value: PropertyValue) -> Self {
+        self.properties.insert(name.into(), value);
+        self
+    }
+
+    pub fn with_icon(mut self, icon: Option<Icon>) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    pub fn with_cover(mut self, cover: Option<Cover>) -> Self {
+        self.cover = Some(cover);
+        self
+    }
+
+    pub fn with_in_trash(mut self, in_trash: bool) -> Self {
+        self.in_trash = Some(in_trash);
+        self
+    }
+
+    /// Whether there is nothing to send.
+    pub fn is_empty(&self) -> bool {
+        self.properties.is_empty()
+            && self.icon.is_none()
+            && self.cover.is_none()
+            && self.in_trash.is_none()
+    }
+
+    /// The `PATCH /pages/{id}` body.
+    pub(crate) fn body(&self) -> Value {
+        let mut body = Map::new();
+        if !self.properties.is_empty() {
+            let properties: Map<String, Value> = self
+                .properties
+                .iter()
+                .map(|(name, value)| (name.clone(), json!(Property::new(value.clone()))))
+                .collect();
+            body.insert("properties".into(), properties.into());
+        }
+        if let Some(icon) = &self.icon {
+            body.insert("icon".into(), json!(icon));
+        }
+        if let Some(cover) = &self.cover {
+            body.insert("cover".into(), json!(cover));
+        }
+        if let Some(in_trash) = self.in_trash {
+            body.insert("in_trash".into(), in_trash.into());
+        }
+        Value::Object(body)
+    }
+}
+
+fn changed<T: PartialEq + Clone>(current: &T, desired: &T) -> Option<T> {
+    (current != desired).then(|| desired.clone())
+}
+
+impl From<&Page> for NewPage {
+    /// A page with `page`'s parent, writable properties, icon and cover.
+    /// An icon or cover Notion hosts cannot be set through the API and is
+    /// left out.
+    fn from(page: &Page) -> Self {
+        let mut new = NewPage::new(page.parent.clone());
+        new.properties = page
+            .properties
+            .iter()
+            .filter(|(_, p)| !p.value.is_read_only())
+            .map(|(name, p)| (name.clone(), p.value.clone()))
+            .collect();
+        new.icon = page
+            .icon
+            .clone()
+            .filter(|icon| !matches!(icon, Icon::File(_)));
+        new.cover = page.cover.clone().filter(|cover| !cover.is_notion_hosted());
+        new
+    }
+}
+
+/// A page to write, read from JSON the way `swivel notion put` takes it:
+/// a [`NewPage`] when there is no `id`, otherwise a [`PageUpdate`] of the
+/// page with that id.
+///
+/// Only the fields Notion's create and update endpoints take are read:
+/// `parent`, `properties`, `icon`, `cover` and `in_trash`. Read-only
+/// property values and a Notion-hosted icon or cover are left out, so a
+/// page printed by `swivel notion get` can be edited and written back.
+///
+/// ```
+/// # use swivel::notion::PageInput;
+/// let input: PageInput = serde_json::from_str(r#"{
+///     "parent": { "type": "page_id", "page_id": "275a1865b187807aadeaebaf36fb49b0" },
+///     "properties": { "title": { "type": "title", "title": [
+///         { "type": "text", "text": { "content": "Launch plan" } }
+///     ] } }
+/// }"#)?;
+/// assert!(matches!(input, PageInput::Create(_)));
+/// # Ok::<(), serde_json::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum PageInput {
+    Create(NewPage),
+    Update { id: String, update: PageUpdate },
+}
+
+impl<'de> Deserialize<'de> for PageInput {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        fn field<T: DeserializeOwned, E: serde::de::Error>(
+            map: &mut Map<String, Value>,
+            name: &str,
+        ) -> Result<Option<T>, E> {
+            map.remove(name)
+                .map(|value| {
+                    serde_json::from_value(value).map_err(|e| E::custom(format!("`{name}`: {e}")))
+                })
+                .transpose()
+        }
+
+        let mut map = Map::deserialize(deserializer)?;
+        let id = match map.remove("id") {
+            Some(Value::String(id)) if !id.is_empty() => Some(id),
+            Some(Value::String(_) | Value::Null) | None => None,
+            Some(other) => {
+                return Err(D::Error::custom(format!(
+                    "`id` must be a string, got {other}"
+                )))
+            }
+        };
+        let properties: BTreeMap<String, Property> =
+            field(&mut map, "properties")?.unwrap_or_default();
+        let properties: BTreeMap<String, PropertyValue> = properties
+            .into_iter()
+            .filter(|(_, p)| !p.value.is_read_only())
+            .map(|(name, p)| (name, p.value))
+            .collect();
+        // `null` removes an icon or cover; a hosted one cannot be set.
+        let icon: Option<Option<Icon>> = field::<Option<Icon>, D::Error>(&mut map, "icon")?
+            .filter(|icon| !matches!(icon, Some(Icon::File(_))));
+        let cover: Option<Option<Cover>> = field::<Option<Cover>, D::Error>(&mut map, "cover")?
+            .filter(|cover| !cover.as_ref().is_some_and(Cover::is_notion_hosted));
+
+        let Some(id) = id else {
+            let parent: Parent = field(&mut map, "parent")?
+                .ok_or_else(|| D::Error::custom("a page without an `id` needs a `parent`"))?;
+            let mut new = NewPage::new(parent);
+            new.properties = properties;
+            new.icon = icon.flatten();
+            new.cover = cover.flatten();
+            return Ok(Self::Create(new));
+        };
+        Ok(Self::Update {
+            id,
+            update: PageUpdate {
+                properties,
+                icon,
+                cover,
+                in_trash: field(&mut map, "in_trash")?,
+            },
+        })
+    }
+}
@@ -0,0 +1,93 @@
+//! Postgres backend.
+//!
+//! [`PostgresClient`] stores records as rows of one table, addressed by a key
+//! column. Records are JSON objects keyed by column name; Postgres itself
+//! converts them to and from the table's row type.
+
+use std::env;
+use std::sync::Mutex;
+
+use serde_json::Value;
+
+use crate::sql::{self, quote_ident};
+use crate::{Backend, Database, Error, Record, Records, Result};
+
+/// Blocking Postgres client bound to one table.
+pub struct PostgresClient {
+    conn: Mutex<postgres::Client>,
+    table: String,
+    key: String,
+}
+
+impl PostgresClient {
+    /// Connect with a `postgres://` URL (no TLS) and use `table`, keyed by `id`.
+    pub fn connect(url: &str, table: impl Into<String>) -> Result<Self> {
+        let conn = postgres::Client::connect(url, postgres::NoTls)?;
+        Ok(Self::from_client(conn, table))
+    }
+
+    /// Connect using the `DATABASE_URL` environment variable.
+    pub fn from_env(table: impl Into<String>) -> Result<Self> {
+        let url = env::var("DATABASE_URL")
+            .map_err(|_| Error::Config("DATABASE_URL is not set in the environment".into()))?;
+        Self::connect(&url, table)
+    }
+
+    /// Wrap an existing connection (e.g. one opened with TLS).
+    pub fn from_client(conn: postgres::Client, table: impl Into<String>) -> Self {
+        Self {
+            conn: Mutex::new(conn),
+            table: table.into(),
+            key: "id".to_string(),
+        }
+    }
+
+    /// Address rows by `column` instead of `id`.
+    pub fn with_key(mut self, column: impl Into<String>) -> Self {
+        self.key = column.into();
+        self
+    }
+
+    /// Read and write rows as `T`, keyed by its id column.
+    pub fn records<T: Record>(self) -> Records<Self, T> {
+        let key = T::key(Backend::Postgres);
+        Records::new(self.with_key(key), Backend::Postgres)
+    }
+}
+
+impl Database for PostgresClient {
+    type Record = Value;
+
+    fn get(&self, id: &str) -> Result<Self::Record> {
+        // Compare as text so any key type (int, uuid, text) can be addressed.
+        let query = format!(
+            "SELECT row_to_json(t)::text FROM {} t WHERE t.{}::text = $1",
+            quote_ident(&self.table),
+            quote_ident(&self.key)
+        );
+        let mut conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        let row = conn
+            .query_opt(&query, &[&id])?
+            .ok_or_else(|| sql::not_found(&self.table, &self.key, id))?;
+        let json: String = row.get(0);
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    fn put(&self, rec: Self::Record) -> Result<()> {
+        let obj = sql::record_columns(&rec, &self.key)?;
+        let table = quote_ident(&self.table);
+        let columns = obj
+            .keys()
+            .map(|c| quote_ident(c))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let statement = format!(
+            "INSERT INTO {table} ({columns}) \
+             SELECT {columns} FROM json_populate_record(NULL::{table}, $1::text::json) {}",
+            sql::upsert_clause(&self.key, obj.keys())
+        );
+        let mut conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        conn.execute(&statement, &[&rec.to_string()])?;
+        Ok(())
+    }
+}
@@ -0,0 +1,140 @@
+//! Domain structs stored through any backend.
+
+use std::marker::PhantomData;
+
+use serde_json::{Map, Value};
+
+use crate::{Database, Error, Result};
+
+/// The backends a [`Record`] can name its fields for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Backend {
+    Notion,
+    Supabase,
+    Postgres,
+}
+
+/// A domain type that can be stored through the backends, one field per
+/// column or property.
+///
+/// Derive it with `#[derive(Record)]` (feature `derive`) rather than by
+/// hand:
+///
+/// ```ignore
+/// use swivel::Record;
+///
+/// #[derive(Record)]
+/// struct Task {
+///     #[swivel(id)]
+///     id: String,
+///     #[swivel(notion = "Name")]
+///     title: String,
+///     #[swivel(notion = "Due Date", postgres = "due_date", supabase = "due_date")]
+///     due: Option<String>,
+///     #[swivel(skip)]
+///     dirty: bool,
+/// }
+/// ```
+///
+/// Fields travel as JSON values keyed by each backend's name for them;
+/// [`Records`] and the Notion client's `records` turn a backend into a
+/// [`Database`] of the struct.
+pub trait Record: Sized {
+    /// The name of the id field on `backend`.
+    fn key(backend: Backend) -> &'static str;
+
+    /// The id as a string, or `None` if the record has not been stored yet
+    /// (a null or empty id).
+    fn id(&self) -> Option<String>;
+
+    /// The stored fields, the id included, keyed by `backend`'s names.
+    fn to_fields(&self, backend: Backend) -> Result<Map<String, Value>>;
+
+    /// Build a record from `backend`'s fields. Missing fields read as null;
+    /// skipped ones get their default.
+    fn from_fields(backend: Backend, fields: Map<String, Value>) -> Result<Self>;
+}
+
+/// A [`Database`] of `T` on top of a backend that stores JSON rows, such as
+/// `PostgresClient`.
+///
+/// ```ignore
+/// let tasks = PostgresClient::from_env("tasks")?.records::<Task>();
+/// let task: Task = tasks.get("42")?;
+/// ```
+pub struct Records<D, T> {
+    db: D,
+    backend: Backend,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<D, T> Records<D, T> {
+    /// Read and write `T`s through `db`, using `backend`'s field names.
+    pub fn new(db: D, backend: Backend) -> Self {
+        Self {
+            db,
+            backend,
+            marker: PhantomData,
+        }
+    }
+
+    /// The backend underneath, for untyped access.
+    pub fn inner(&self) -> &D {
+        &self.db
+    }
+
+    pub fn into_inner(self) -> D {
+        self.db
+    }
+}
+
+impl<D: Database<Record = Value>, T: Record> Database for Records<D, T> {
+    type Record = T;
+
+    fn get(&self, id: &str) -> Result<T> {
+        match self.db.get(id)? {
+            Value::Object(fields) => T::from_fields(self.backend, fields),
+            other => Err(Error::InvalidInput(format!(
+                "expected a row object, got {other}"
+            ))),
+        }
+    }
+
+    fn put(&self, rec: T) -> Result<()> {
+        let fields = rec.to_fields(self.backend)?;
+        self.db.put(Value::Object(fields))
+    }
+}
+
+/// Support for the code `#[derive(Record)]` generates. Not public API.
+#[doc(hidden)]
+pub mod __private {
+    use serde::de::DeserializeOwned;
+    use serde::Serialize;
+    use serde_json::Value;
+
+    use crate::{Error, Result};
+
+    pub type Map = serde_json::Map<String, Value>;
+
+    pub fn to_field<T: Serialize>(value: &T, name: &str) -> Result<Value> {
+        serde_json::to_value(value)
+            .map_err(|err| Error::InvalidInput(format!("field `{name}`: {err}")))
+    }
+
+    pub fn from_field<T: DeserializeOwned>(fields: &mut Map, name: &str) -> Result<T> {
+        let value = fields.remove(name).unwrap_or(Value::Null);
+        serde_json::from_value(value)
+            .map_err(|err| Error::InvalidInput(format!("field `{name}`: {err}")))
+    }
+
+    pub fn id_string<T: Serialize>(value: &T) -> Option<String> {
+        match serde_json::to_value(value).ok()? {
+            Value::Null => None,
+            Value::String(s) if s.is_empty() => None,
+            Value::String(s) => Some(s),
+            other => Some(other.to_string()),
+        }
+    }
+}
@@ -0,0 +1,183 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::Error;
+
+/// When and how long to wait before re-sending a failed request.
+///
+/// Rate limits (429), transient server failures (500, 502, 503, 504) and
+/// connection errors are retried with exponential backoff. A `Retry-After`
+/// header, when the server sends one, replaces the computed delay. Only
+/// idempotent requests are ever retried.
+///
+/// ```
+/// use std::time::Duration;
+/// use swivel::RetryPolicy;
+///
+/// let policy = RetryPolicy::default()
+///     .with_max_attempts(5)
+///     .with_base_delay(Duration::from_millis(250));
+/// assert_eq!(policy.max_attempts(), 5);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    jitter: bool,
+    respect_retry_after: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 4,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            jitter: true,
+            respect_retry_after: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Send every request exactly once.
+    pub fn none() -> Self {
+        Self::default().with_max_attempts(1)
+    }
+
+    /// Total attempts, including the first one. Clamped to at least 1.
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Delay before the first retry; doubled for each one after.
+    pub fn with_base_delay(mut self, base_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self
+    }
+
+    /// Upper bound for the computed backoff (not for `Retry-After`).
+    pub fn with_max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Randomize each backoff between half and all of its computed value.
+    pub fn with_jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Whether a `Retry-After` header overrides the computed backoff.
+    pub fn with_retry_after(mut self, respect: bool) -> Self {
+        self.respect_retry_after = respect;
+        self
+    }
+
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Should the request that just failed with `err` on attempt number
+    /// `attempt` (1-based) be sent again?
+    pub fn should_retry(&self, attempt: u32, err: &Error, idempotent: bool) -> bool {
+        idempotent && attempt < self.max_attempts && err.is_retryable()
+    }
+
+    /// How long to wait after failed attempt number `attempt` (1-based).
+    pub fn delay(&self, attempt: u32, err: &Error) -> Duration {
+        if self.respect_retry_after {
+            if let Error::RateLimited {
+                retry_after: Some(retry_after),
+                ..
+            } = err
+            {
+                return *retry_after;
+            }
+        }
+
+        let exp = attempt.saturating_sub(1).min(16);
+        let backoff = self.base_delay.saturating_mul(1 << exp).min(self.max_delay);
+        if self.jitter && !backoff.is_zero() {
+            let half = backoff / 2;
+            half + backoff.mul_f64(rand::random_range(0.0..0.5))
+        } else {
+            backoff
+        }
+    }
+}
+
+/// Client-side token bucket shared by every request a client makes.
+///
+/// Clones share the same bucket, so cloning a client does not double its
+/// budget.
+///
+/// ```
+/// use swivel::RateLimiter;
+///
+/// // Notion's documented average: three requests per second.
+/// let limiter = RateLimiter::new(3.0, 3);
+/// limiter.acquire();
+/// ```
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    bucket: Arc<Mutex<Bucket>>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    rate: f64,
+    capacity: f64,
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl RateLimiter {
+    /// Allow `per_second` requests on average, with bursts of up to `burst`.
+    ///
+    /// # Panics
+    ///
+    /// If `per_second` is not a positive, finite number.
+    pub fn new(per_second: f64, burst: u32) -> Self {
+        assert!(
+            per_second.is_finite() && per_second > 0.0,
+            "rate limit must be a positive number of requests per second, got {per_second}"
+        );
+        let capacity = f64::from(burst.max(1));
+        Self {
+            bucket: Arc::new(Mutex::new(Bucket {
+                rate: per_second,
+                capacity,
+                tokens: capacity,
+                refilled_at: Instant::now(),
+            })),
+        }
+    }
+
+    /// Block until a request may be sent.
+    pub fn acquire(&self) {
+        let wait = self.reserve();
+        if !wait.is_zero() {
+            std::thread::sleep(wait);
+        }
+    }
+
+    /// Take a token and return how long the caller must wait before using
+    /// it. Tokens may go negative, which queues callers fairly.
+    pub(crate) fn reserve(&self) -> Duration {
+        let mut bucket = self.bucket.lock().unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * bucket.rate).min(bucket.capacity);
+        bucket.refilled_at = now;
+        bucket.tokens -= 1.0;
+        if bucket.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            // A tiny rate can ask for longer than a Duration holds.
+            Duration::try_from_secs_f64(-bucket.tokens / bucket.rate).unwrap_or(Duration::MAX)
+        }
+    }
+}
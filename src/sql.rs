@@ -0,0 +1,51 @@
+//! Helpers shared by the SQL backends.
+
+use serde_json::{Map, Value};
+
+use crate::{Error, Result};
+
+/// Quote a possibly schema-qualified identifier: `public.todos` becomes
+/// `"public"."todos"`.
+pub(crate) fn quote_ident(name: &str) -> String {
+    name.split('.')
+        .map(|part| format!("\"{}\"", part.replace('"', "\"\"")))
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// A record must be a JSON object that contains the key column.
+pub(crate) fn record_columns<'a>(rec: &'a Value, key: &str) -> Result<&'a Map<String, Value>> {
+    let obj = rec
+        .as_object()
+        .ok_or_else(|| Error::InvalidInput("record must be a JSON object".into()))?;
+    if !obj.contains_key(key) {
+        return Err(Error::InvalidInput(format!(
+            "record is missing its key column `{key}`"
+        )));
+    }
+    Ok(obj)
+}
+
+/// `ON CONFLICT` clause that overwrites every non-key column.
+pub(crate) fn upsert_clause<'a>(key: &str, columns: impl Iterator<Item = &'a String>) -> String {
+    let updates: Vec<String> = columns
+        .filter(|c| c.as_str() != key)
+        .map(|c| format!("{0} = excluded.{0}", quote_ident(c)))
+        .collect();
+    if updates.is_empty() {
+        format!("ON CONFLICT ({}) DO NOTHING", quote_ident(key))
+    } else {
+        format!(
+            "ON CONFLICT ({}) DO UPDATE SET {}",
+            quote_ident(key),
+            updates.join(", ")
+        )
+    }
+}
+
+pub(crate) fn not_found(table: &str, key: &str, id: &str) -> Error {
+    Error::ObjectNotFound {
+        message: format!("no row in {table} with {key} = {id}"),
+        request_id: None,
+    }
+}
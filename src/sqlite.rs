@@ -0,0 +1,133 @@
+//! SQLite backend.
+//!
+//! [`SqliteClient`] stores records as rows of one table, addressed by a key
+//! column. Records are JSON objects keyed by column name: numbers, strings,
+//! booleans and nulls map onto SQLite's storage classes; arrays and objects
+//! are stored as JSON text.
+//!
+//! SQLite has no boolean type and stores `true` and `false` as 1 and 0.
+//! They read back as booleans from columns declared `BOOLEAN` (or `BOOL`),
+//! and as numbers from any other column.
+
+use std::path::Path;
+use std::sync::Mutex;
+
+use rusqlite::types::{ToSqlOutput, Value as SqlValue, ValueRef};
+use rusqlite::{params_from_iter, Connection, OptionalExtension};
+use serde_json::{Map, Number, Value};
+
+use crate::sql::{self, quote_ident};
+use crate::{Database, Result};
+
+/// SQLite client bound to one table.
+pub struct SqliteClient {
+    conn: Mutex<Connection>,
+    table: String,
+    key: String,
+}
+
+impl SqliteClient {
+    /// Open (or create) the database file at `path` and use `table`, keyed by `id`.
+    pub fn open(path: impl AsRef<Path>, table: impl Into<String>) -> Result<Self> {
+        Ok(Self::from_connection(Connection::open(path)?, table))
+    }
+
+    /// Wrap an existing connection, e.g. an in-memory one you created the
+    /// table on.
+    pub fn from_connection(conn: Connection, table: impl Into<String>) -> Self {
+        Self {
+            conn: Mutex::new(conn),
+            table: table.into(),
+            key: "id".to_string(),
+        }
+    }
+
+    /// Address rows by `column` instead of `id`.
+    pub fn with_key(mut self, column: impl Into<String>) -> Self {
+        self.key = column.into();
+        self
+    }
+}
+
+impl Database for SqliteClient {
+    type Record = Value;
+
+    fn get(&self, id: &str) -> Result<Self::Record> {
+        let query = format!(
+            "SELECT * FROM {} WHERE {} = ?1",
+            quote_ident(&self.table),
+            quote_ident(&self.key)
+        );
+        let conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        let mut stmt = conn.prepare(&query)?;
+        let columns: Vec<(String, bool)> = stmt
+            .columns()
+            .iter()
+            .map(|c| (c.name().to_string(), c.decl_type().is_some_and(is_boolean)))
+            .collect();
+        stmt.query_row([id], |row| {
+            let mut obj = Map::new();
+            for (i, (name, boolean)) in columns.iter().enumerate() {
+                let value = match row.get_ref(i)? {
+                    ValueRef::Integer(i) if *boolean => Value::Bool(i != 0),
+                    value => to_json(value),
+                };
+                obj.insert(name.clone(), value);
+            }
+            Ok(Value::Object(obj))
+        })
+        .optional()?
+        .ok_or_else(|| sql::not_found(&self.table, &self.key, id))
+    }
+
+    fn put(&self, rec: Self::Record) -> Result<()> {
+        let obj = sql::record_columns(&rec, &self.key)?;
+        let columns = obj
+            .keys()
+            .map(|c| quote_ident(c))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let placeholders = (1..=obj.len())
+            .map(|i| format!("?{i}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let statement = format!(
+            "INSERT INTO {} ({columns}) VALUES ({placeholders}) {}",
+            quote_ident(&self.table),
+            sql::upsert_clause(&self.key, obj.keys())
+        );
+        let conn = self.conn.lock().unwrap_or_else(|e| e.into_inner());
+        conn.execute(&statement, params_from_iter(obj.values().map(to_sql)))?;
+        Ok(())
+    }
+}
+
+/// Whether a declared column type is a boolean one, by SQLite's own
+/// reading a column of numeric affinity.
+fn is_boolean(decl_type: &str) -> bool {
+    let decl_type = decl_type.to_ascii_uppercase();
+    decl_type == "BOOL" || decl_type == "BOOLEAN"
+}
+
+fn to_json(value: ValueRef<'_>) -> Value {
+    match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(i) => Value::from(i),
+        ValueRef::Real(f) => Number::from_f64(f).map_or(Value::Null, Value::Number),
+        ValueRef::Text(t) => Value::String(String::from_utf8_lossy(t).into_owned()),
+        ValueRef::Blob(b) => Value::from(b.to_vec()),
+    }
+}
+
+fn to_sql(value: &Value) -> ToSqlOutput<'static> {
+    ToSqlOutput::Owned(match value {
+        Value::Null => SqlValue::Null,
+        Value::Bool(b) => SqlValue::Integer(i64::from(*b)),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => SqlValue::Integer(i),
+            None => SqlValue::Real(n.as_f64().unwrap_or_default()),
+        },
+        Value::String(s) => SqlValue::Text(s.clone()),
+        other => SqlValue::Text(other.to_string()),
+    })
+}
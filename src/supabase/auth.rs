@@ -0,0 +1,409 @@
+//! Supabase Auth (GoTrue): signing in, keeping the session fresh, and
+//! signing out.
+//!
+//! An [`AuthClient`] holds the current [`Session`] in a [`SessionStore`],
+//! in memory by default or on disk with a [`FileStore`]. Give it to
+//! [`SupabaseClient::with_auth`](super::SupabaseClient::with_auth) and
+//! every request carries the user's JWT instead of the project key, so
+//! row-level security policies see the signed-in user.
+//!
+//! ```no_run
+//! use swivel::supabase::{AuthClient, FileStore, SupabaseClient};
+//!
+//! let auth = AuthClient::from_env()?.with_store(FileStore::new("session.json"));
+//! if auth.session()?.is_none() {
+//!     auth.sign_in_with_password("ann@example.com", "hunter2")?;
+//! }
+//! let tasks = SupabaseClient::from_env("tasks")?.with_auth(auth);
+//! # Ok::<(), swivel::Error>(())
+//! ```
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use reqwest::Method;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use super::client;
+use super::error;
+use crate::http::{HttpRequest, HttpResponse, Transport};
+use crate::{Error, Result, RetryPolicy};
+
+/// A signed-in user's tokens, as Supabase Auth issues them.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    /// The JWT sent as `Authorization: Bearer ...`.
+    pub access_token: String,
+    /// Single use: every refresh returns a new one.
+    pub refresh_token: String,
+    #[serde(default = "bearer")]
+    pub token_type: String,
+    /// Lifetime of the access token in seconds, as issued.
+    #[serde(default)]
+    pub expires_in: u64,
+    /// When the access token expires, in seconds since the Unix epoch.
+    #[serde(default)]
+    pub expires_at: u64,
+    #[serde(default)]
+    pub user: Option<User>,
+}
+
+fn bearer() -> String {
+    "bearer".to_string()
+}
+
+impl Session {
+    /// Whether the access token expires within `margin` from now.
+    pub fn expires_within(&self, margin: Duration) -> bool {
+        self.expires_at <= now() + margin.as_secs()
+    }
+
+    /// Fill in `expires_at` when the server only sent `expires_in`.
+    fn stamped(mut self) -> Self {
+        if self.expires_at == 0 {
+            self.expires_at = now() + self.expires_in;
+        }
+        self
+    }
+}
+
+/// Tokens are left out, so sessions can be logged.
+impl fmt::Debug for Session {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Session")
+            .field("token_type", &self.token_type)
+            .field("expires_at", &self.expires_at)
+            .field("user", &self.user)
+            .finish_non_exhaustive()
+    }
+}
+
+/// The user a [`Session`] belongs to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct User {
+    pub id: String,
+    #[serde(default)]
+    pub email: Option<String>,
+    #[serde(default)]
+    pub phone: Option<String>,
+    /// The Postgres role requests run as, usually `authenticated`.
+    #[serde(default)]
+    pub role: Option<String>,
+    #[serde(default)]
+    pub user_metadata: Map<String, Value>,
+}
+
+/// Where an [`AuthClient`] keeps its session between requests, and between
+/// runs if the store is persistent.
+pub trait SessionStore: Send + Sync {
+    /// The saved session, if any.
+    fn load(&self) -> Result<Option<Session>>;
+
+    /// Replace the saved session.
+    fn save(&self, session: &Session) -> Result<()>;
+
+    /// Forget the saved session. Clearing an empty store is not an error.
+    fn clear(&self) -> Result<()>;
+}
+
+/// Keeps the session for the life of the process.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    session: Mutex<Option<Session>>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn slot(&self) -> std::sync::MutexGuard<'_, Option<Session>> {
+        self.session.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl SessionStore for MemoryStore {
+    fn load(&self) -> Result<Option<Session>> {
+        Ok(self.slot().clone())
+    }
+
+    fn save(&self, session: &Session) -> Result<()> {
+        *self.slot() = Some(session.clone());
+        Ok(())
+    }
+
+    fn clear(&self) -> Result<()> {
+        *self.slot() = None;
+        Ok(())
+    }
+}
+
+/// Keeps the session in a JSON file, so it survives restarts.
+///
+/// The file is replaced atomically and, on Unix, readable by its owner
+/// only: the refresh token in it signs the user in.
+#[derive(Debug, Clone)]
+pub struct FileStore {
+    path: PathBuf,
+}
+
+impl FileStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl SessionStore for FileStore {
+    fn load(&self) -> Result<Option<Session>> {
+        match fs::read_to_string(&self.path) {
+            Ok(text) => Ok(Some(serde_json::from_str(&text)?)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    fn save(&self, session: &Session) -> Result<()> {
+        if let Some(dir) = self.path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        let tmp = self.path.with_extension("tmp");
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let file = options.open(&tmp)?;
+        serde_json::to_writer_pretty(file, session)?;
+        fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+
+    fn clear(&self) -> Result<()> {
+        match fs::remove_file(&self.path) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Blocking client for a Supabase project's Auth API (`/auth/v1`).
+///
+/// Sign in with [`sign_in_with_password`](Self::sign_in_with_password) or a
+/// one-time code ([`send_otp`](Self::send_otp), then
+/// [`verify_otp`](Self::verify_otp)); the session is saved to the store and
+/// [`session`](Self::session) refreshes it once the access token is within
+/// a minute of expiring. Clones share the store, and only one of them
+/// refreshes at a time, since a refresh token can be spent only once.
+#[derive(Clone)]
+pub struct AuthClient {
+    transport: Transport,
+    url: String,
+    api_key: String,
+    store: Arc<dyn SessionStore>,
+    refresh_margin: Duration,
+    refreshing: Arc<Mutex<()>>,
+}
+
+impl AuthClient {
+    /// Create a client for the project at `url`, authenticated with its
+    /// anon key, keeping the session in memory.
+    pub fn new(url: impl Into<String>, api_key: impl Into<String>) -> Self {
+        let url: String = url.into();
+        Self {
+            transport: Transport::new(None),
+            url: url.trim_end_matches('/').to_string(),
+            api_key: api_key.into(),
+            store: Arc::new(MemoryStore::new()),
+            refresh_margin: Duration::from_secs(60),
+            refreshing: Arc::default(),
+        }
+    }
+
+    /// Read the project URL from `SUPABASE_URL` and the key from
+    /// `SUPABASE_KEY`.
+    pub fn from_env() -> Result<Self> {
+        Ok(Self::new(
+            client::env_var("SUPABASE_URL")?,
+            client::env_var("SUPABASE_KEY")?,
+        ))
+    }
+
+    /// Keep the session in `store` instead of in memory.
+    pub fn with_store(mut self, store: impl SessionStore + 'static) -> Self {
+        self.store = Arc::new(store);
+        self
+    }
+
+    /// Refresh the session when its access token expires within `margin`
+    /// (one minute by default).
+    pub fn with_refresh_margin(mut self, margin: Duration) -> Self {
+        self.refresh_margin = margin;
+        self
+    }
+
+    /// Replace the default retry policy.
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.transport.retry = retry;
+        self
+    }
+
+    /// Sign in with an email address and password.
+    pub fn sign_in_with_password(&self, email: &str, password: &str) -> Result<Session> {
+        let body = json!({ "email": email, "password": password });
+        self.grant("password", body)
+    }
+
+    /// Email a one-time code and magic link to an existing user. Sign in
+    /// with the code through [`verify_otp`](Self::verify_otp), or with the
+    /// link's `token_hash` through
+    /// [`verify_token_hash`](Self::verify_token_hash).
+    pub fn send_otp(&self, email: &str) -> Result<()> {
+        let req = self
+            .request(Method::POST, "otp")
+            .json(json!({ "email": email, "create_user": false }));
+        self.transport.execute(&req, parse_json::<Value>)?;
+        Ok(())
+    }
+
+    /// Sign in with the one-time code emailed by [`send_otp`](Self::send_otp).
+    pub fn verify_otp(&self, email: &str, code: &str) -> Result<Session> {
+        self.verify(json!({ "type": "email", "email": email, "token": code }))
+    }
+
+    /// Sign in with the `token_hash` of a magic link, for apps that handle
+    /// the link themselves.
+    pub fn verify_token_hash(&self, token_hash: &str) -> Result<Session> {
+        self.verify(json!({ "type": "email", "token_hash": token_hash }))
+    }
+
+    /// Use a session obtained elsewhere, e.g. from a browser sign-in.
+    pub fn set_session(&self, session: Session) -> Result<()> {
+        self.store.save(&session.stamped())
+    }
+
+    /// The current session, refreshed first if its access token is about to
+    /// expire; `None` when signed out.
+    pub fn session(&self) -> Result<Option<Session>> {
+        match self.store.load()? {
+            Some(session) if session.expires_within(self.refresh_margin) => self
+                .refresh_if(|current| current.expires_within(self.refresh_margin))
+                .map(Some),
+            session => Ok(session),
+        }
+    }
+
+    /// The signed-in user's access token, refreshed if needed.
+    pub fn access_token(&self) -> Result<Option<String>> {
+        Ok(self.session()?.map(|session| session.access_token))
+    }
+
+    /// Exchange the refresh token for a new session now, however long the
+    /// current one has left.
+    pub fn refresh(&self) -> Result<Session> {
+        self.refresh_if(|_| true)
+    }
+
+    /// Revoke the session on the server, everywhere the user is signed in,
+    /// and clear the store. A session the server no longer knows is
+    /// cleared all the same.
+    pub fn sign_out(&self) -> Result<()> {
+        let Some(session) = self.store.load()? else {
+            return Ok(());
+        };
+        let req = self
+            .request(Method::POST, "logout?scope=global")
+            .header("Authorization", format!("Bearer {}", session.access_token));
+        match self.transport.execute(&req, |resp| check(&resp)) {
+            Ok(()) | Err(Error::Unauthorized { .. } | Error::ObjectNotFound { .. }) => {
+                self.store.clear()
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Refresh under the lock if the stored session, re-read once the lock
+    /// is held, still `needs` it; a clone may have refreshed meanwhile.
+    fn refresh_if(&self, needs: impl Fn(&Session) -> bool) -> Result<Session> {
+        let _refreshing = self.refreshing.lock().unwrap_or_else(|e| e.into_inner());
+        let session = self.store.load()?.ok_or_else(|| Error::Unauthorized {
+            message: "not signed in to Supabase Auth".into(),
+            request_id: None,
+        })?;
+        if !needs(&session) {
+            return Ok(session);
+        }
+        let body = json!({ "refresh_token": session.refresh_token });
+        match self.grant("refresh_token", body) {
+            // The refresh token was revoked or already spent: sign in again.
+            Err(err @ Error::Unauthorized { .. }) => {
+                self.store.clear()?;
+                Err(err)
+            }
+            result => result,
+        }
+    }
+
+    fn grant(&self, grant_type: &str, body: Value) -> Result<Session> {
+        let req = self
+            .request(Method::POST, &format!("token?grant_type={grant_type}"))
+            .json(body);
+        self.save(self.transport.execute(&req, parse_json)?)
+    }
+
+    fn verify(&self, body: Value) -> Result<Session> {
+        let req = self.request(Method::POST, "verify").json(body);
+        self.save(self.transport.execute(&req, parse_json)?)
+    }
+
+    fn save(&self, session: Session) -> Result<Session> {
+        let session = session.stamped();
+        self.store.save(&session)?;
+        Ok(session)
+    }
+
+    fn request(&self, method: Method, path: &str) -> HttpRequest {
+        HttpRequest::new(method, format!("{}/auth/v1/{path}", self.url))
+            .header("apikey", &self.api_key)
+    }
+}
+
+impl fmt::Debug for AuthClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AuthClient")
+            .field("url", &self.url)
+            .field("refresh_margin", &self.refresh_margin)
+            .finish_non_exhaustive()
+    }
+}
+
+fn parse_json<T: DeserializeOwned>(resp: HttpResponse) -> Result<T> {
+    check(&resp)?;
+    Ok(serde_json::from_str(&resp.body)?)
+}
+
+/// Turn a failed response into a typed [`Error`].
+fn check(resp: &HttpResponse) -> Result<()> {
+    if resp.is_success() {
+        return Ok(());
+    }
+    Err(error::from_auth_response(
+        resp.status,
+        resp.retry_after(),
+        &resp.body,
+    ))
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
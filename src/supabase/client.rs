@@ -0,0 +1,307 @@
+use std::env;
+
+use reqwest::{Method, Url};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use super::auth::AuthClient;
+use super::error;
+use super::query::{self, Query};
+use super::Filter;
+use crate::http::{HttpRequest, HttpResponse, Transport};
+use crate::{Backend, Database, Error, RateLimiter, Record, Records, Result, RetryPolicy};
+
+/// Blocking client for a Supabase project's PostgREST API, bound to one
+/// table, or to none for only calling functions (see
+/// [`project`](Self::project)).
+///
+/// Rows are addressed by a key column, `id` unless
+/// [`with_key`](Self::with_key) says otherwise. Requests carry the project
+/// key both as `apikey` and as the bearer token, or the signed-in user's
+/// token after [`with_auth`](Self::with_auth), and are retried according
+/// to a [`RetryPolicy`]; there is no client-side rate limit by default.
+/// Cloning is cheap: clones share the connection pool.
+#[derive(Debug, Clone)]
+pub struct SupabaseClient {
+    transport: Transport,
+    url: String,
+    api_key: String,
+    table: Option<String>,
+    key: String,
+    auth: Option<AuthClient>,
+}
+
+impl SupabaseClient {
+    /// Create a client for `table` in the project at `url`
+    /// (`https://<ref>.supabase.co`), authenticated with an anon or
+    /// service-role key.
+    pub fn new(
+        url: impl Into<String>,
+        api_key: impl Into<String>,
+        table: impl Into<String>,
+    ) -> Self {
+        Self::project(url, api_key).with_table(table)
+    }
+
+    /// Create a client for the project at `url` that is not bound to a
+    /// table, for calling functions with [`rpc`](Self::rpc) and
+    /// [`call`](Self::call). Table reads and writes fail with
+    /// [`Error::Config`] until [`with_table`](Self::with_table) names one.
+    pub fn project(url: impl Into<String>, api_key: impl Into<String>) -> Self {
+        let url: String = url.into();
+        Self {
+            transport: Transport::new(None),
+            url: url.trim_end_matches('/').to_string(),
+            api_key: api_key.into(),
+            table: None,
+            key: "id".to_string(),
+            auth: None,
+        }
+    }
+
+    /// Read the project URL from `SUPABASE_URL` and the key from
+    /// `SUPABASE_KEY`.
+    pub fn from_env(table: impl Into<String>) -> Result<Self> {
+        Ok(Self::project_from_env()?.with_table(table))
+    }
+
+    /// [`project`](Self::project) with the URL and key read as by
+    /// [`from_env`](Self::from_env).
+    pub fn project_from_env() -> Result<Self> {
+        Ok(Self::project(
+            env_var("SUPABASE_URL")?,
+            env_var("SUPABASE_KEY")?,
+        ))
+    }
+
+    /// Read and write rows of `table`.
+    pub fn with_table(mut self, table: impl Into<String>) -> Self {
+        self.table = Some(table.into());
+        self
+    }
+
+    /// Address rows by `column` instead of `id`.
+    pub fn with_key(mut self, column: impl Into<String>) -> Self {
+        self.key = column.into();
+        self
+    }
+
+    /// Replace the default retry policy.
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.transport.retry = retry;
+        self
+    }
+
+    /// Throttle requests client-side; `None` (the default) sends them as
+    /// fast as they come.
+    pub fn with_rate_limiter(mut self, limiter: Option<RateLimiter>) -> Self {
+        self.transport.limiter = limiter;
+        self
+    }
+
+    /// Send requests as the user signed in to `auth`, so row-level security
+    /// applies to them; the session is refreshed as it nears expiry. Without
+    /// a session, requests fail with [`Error::Unauthorized`] before being
+    /// sent. The project key is still sent as `apikey`.
+    pub fn with_auth(mut self, auth: AuthClient) -> Self {
+        self.auth = Some(auth);
+        self
+    }
+
+    /// Read and write rows as `T`, keyed by its id column.
+    pub fn records<T: Record>(self) -> Records<Self, T> {
+        let key = T::key(Backend::Supabase);
+        Records::new(self.with_key(key), Backend::Supabase)
+    }
+
+    /// Start a query returning `columns` of the matching rows: `*`, a
+    /// column list, and embedded resources such as `*,author(name)`.
+    pub fn select(&self, columns: impl Into<String>) -> Query<'_> {
+        Query::new(self, columns.into())
+    }
+
+    /// Fetch the row whose key column equals `id`, as any deserializable
+    /// type. No matching row is [`Error::ObjectNotFound`].
+    pub fn fetch<T: DeserializeOwned>(&self, id: &str) -> Result<T> {
+        let (key, value) = Filter::column(&self.key).eq(id).to_param();
+        let url = format!(
+            "{}?{}={}",
+            self.table_url()?,
+            query::encode(&key),
+            query::encode(&value)
+        );
+        let req = self
+            .request(Method::GET, url)?
+            .header("Accept", "application/vnd.pgrst.object+json");
+        self.transport.execute(&req, parse_json)
+    }
+
+    /// Insert `row`, or update the row with the same key, and return the
+    /// row as stored (with defaults and triggers applied).
+    ///
+    /// `row` must serialize to a JSON object. A null key is left out so the
+    /// column's default, e.g. an identity, fills it in.
+    pub fn upsert<T: Serialize + DeserializeOwned>(&self, row: &T) -> Result<T> {
+        let mut row = match serde_json::to_value(row)? {
+            Value::Object(row) => row,
+            other => {
+                return Err(Error::InvalidInput(format!(
+                    "a row must be a JSON object, got {other}"
+                )))
+            }
+        };
+        if row.get(&self.key).is_some_and(Value::is_null) {
+            row.remove(&self.key);
+        }
+        // With its key, writing the same row twice leaves the same row, so
+        // retries are safe. Without one the database assigns a key, and a
+        // retry after a lost response would insert a second row.
+        let keyed = row.contains_key(&self.key);
+        let url = format!(
+            "{}?on_conflict={}",
+            self.table_url()?,
+            query::encode(&self.key)
+        );
+        let req = self
+            .request(Method::POST, url)?
+            .header(
+                "Prefer",
+                "resolution=merge-duplicates,return=representation",
+            )
+            .json(Value::Object(row))
+            .idempotent(keyed);
+        let mut stored: Vec<T> = self.transport.execute(&req, parse_json)?;
+        if stored.is_empty() {
+            return Err(Error::InvalidInput(format!(
+                "upsert into {} returned no row; check the table's select policy",
+                self.table.as_deref().unwrap_or_default()
+            )));
+        }
+        Ok(stored.swap_remove(0))
+    }
+
+    /// Call the Postgres function `function` with `args`, by `POST`, and
+    /// decode its result as `T`: the value of a scalar function, a `Vec`
+    /// of the rows of a set-returning one, `()` for a void one.
+    ///
+    /// `args` serializes to a JSON object of named arguments; `()` calls a
+    /// function without any. Functions do not depend on the client's table,
+    /// so a [`project`](Self::project) client can call them.
+    ///
+    /// ```no_run
+    /// # use swivel::supabase::SupabaseClient;
+    /// # use serde_json::json;
+    /// let db = SupabaseClient::project_from_env()?;
+    /// let total: i64 = db.rpc("add", json!({ "a": 1, "b": 2 }))?;
+    /// db.rpc::<_, ()>("archive_done_tasks", ())?;
+    /// # Ok::<(), swivel::Error>(())
+    /// ```
+    pub fn rpc<A: Serialize, T: DeserializeOwned>(&self, function: &str, args: A) -> Result<T> {
+        self.call(function, args).value()
+    }
+
+    /// [`rpc`](Self::rpc) by `GET`, for `IMMUTABLE` and `STABLE` functions;
+    /// the arguments go in the query string.
+    pub fn rpc_get<A: Serialize, T: DeserializeOwned>(&self, function: &str, args: A) -> Result<T> {
+        self.call(function, args).immutable().value()
+    }
+
+    /// Start a call to `function` that can filter, order and page the rows
+    /// it returns, like a [`select`](Self::select) on a table.
+    pub fn call<A: Serialize>(&self, function: &str, args: A) -> Query<'_> {
+        let args = match serde_json::to_value(args) {
+            Ok(Value::Object(args)) => Ok(args),
+            Ok(Value::Null) => Ok(Map::new()),
+            Ok(other) => Err(format!(
+                "arguments of {function} must be a JSON object of named arguments, got {other}"
+            )),
+            Err(err) => Err(format!("arguments of {function}: {err}")),
+        };
+        Query::function(self, function.to_string(), args)
+    }
+
+    /// `{url}/rest/v1/rpc/{function}`.
+    pub(crate) fn function_url(&self, function: &str) -> Result<Url> {
+        let mut url = self.rest_url()?;
+        url.path_segments_mut()
+            .map_err(|_| Error::Config(format!("invalid Supabase URL `{}`", self.url)))?
+            .extend(["rpc", function]);
+        Ok(url)
+    }
+
+    /// `{url}/rest/v1/{table}`.
+    pub(crate) fn table_url(&self) -> Result<Url> {
+        let table = self.table.as_deref().ok_or_else(|| {
+            Error::Config("the Supabase client is not bound to a table; see `with_table`".into())
+        })?;
+        let mut url = self.rest_url()?;
+        url.path_segments_mut()
+            .map_err(|_| Error::Config(format!("invalid Supabase URL `{}`", self.url)))?
+            .push(table);
+        Ok(url)
+    }
+
+    fn rest_url(&self) -> Result<Url> {
+        Url::parse(&format!("{}/rest/v1", self.url))
+            .map_err(|err| Error::Config(format!("invalid Supabase URL `{}`: {err}", self.url)))
+    }
+
+    pub(crate) fn request(&self, method: Method, url: impl Into<String>) -> Result<HttpRequest> {
+        authorized(method, url, &self.api_key, self.auth.as_ref())
+    }
+
+    pub(crate) fn transport(&self) -> &Transport {
+        &self.transport
+    }
+}
+
+impl Database for SupabaseClient {
+    type Record = Value;
+
+    fn get(&self, id: &str) -> Result<Value> {
+        self.fetch(id)
+    }
+
+    fn put(&self, rec: Value) -> Result<()> {
+        self.upsert(&rec).map(drop)
+    }
+}
+
+/// A request carrying the project key as `apikey`, and as the bearer token
+/// unless `auth` has a signed-in user's, so PostgREST and Storage run it as
+/// that user.
+pub(crate) fn authorized(
+    method: Method,
+    url: impl Into<String>,
+    api_key: &str,
+    auth: Option<&AuthClient>,
+) -> Result<HttpRequest> {
+    let token = match auth {
+        Some(auth) => auth.access_token()?.ok_or_else(|| Error::Unauthorized {
+            message: "not signed in to Supabase Auth".into(),
+            request_id: None,
+        })?,
+        None => api_key.to_string(),
+    };
+    Ok(HttpRequest::new(method, url)
+        .header("apikey", api_key)
+        .header("Authorization", format!("Bearer {token}")))
+}
+
+/// An environment variable, or [`Error::Config`] naming it.
+pub(crate) fn env_var(name: &str) -> Result<String> {
+    env::var(name).map_err(|_| Error::Config(format!("{name} is not set in the environment")))
+}
+
+/// Decode a successful response, or turn a failed one into a typed [`Error`].
+pub(crate) fn parse_json<T: DeserializeOwned>(resp: HttpResponse) -> Result<T> {
+    if !resp.is_success() {
+        return Err(error::from_response(
+            resp.status,
+            resp.retry_after(),
+            &resp.body,
+        ));
+    }
+    Ok(serde_json::from_str(&resp.body)?)
+}
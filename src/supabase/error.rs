@@ -0,0 +1,316 @@
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::Error;
+
+/// The JSON body PostgREST sends with a failed request. `code` is a
+/// Postgres SQLSTATE (`23505`) or one of PostgREST's own (`PGRST116`).
+///
+/// ```json
+/// { "code": "23505", "message": "duplicate key value violates unique constraint \"tasks_pkey\"",
+///   "details": "Key (id)=(42) already exists.", "hint": null }
+/// ```
+#[derive(Debug, Default, Deserialize)]
+struct ErrorBody {
+    #[serde(default)]
+    code: String,
+    #[serde(default)]
+    message: String,
+    #[serde(default)]
+    details: Option<String>,
+    #[serde(default)]
+    hint: Option<String>,
+}
+
+/// Turn a failed PostgREST response into a typed [`Error`].
+///
+/// The SQLSTATE or PostgREST code wins over the HTTP status, so a unique
+/// violation and a row-level security denial stay apart even though both
+/// can come back as 409 or 403.
+pub(crate) fn from_response(status: u16, retry_after: Option<Duration>, body: &str) -> Error {
+    let ErrorBody {
+        code,
+        message,
+        details,
+        hint,
+    } = serde_json::from_str(body).unwrap_or_default();
+    let constraint = constraint_of(&message);
+    let mut message = if message.is_empty() {
+        body.trim().to_string()
+    } else {
+        message
+    };
+    if let Some(details) = details.filter(|d| !d.is_empty()) {
+        message = format!("{message} ({details})");
+    }
+    if let Some(hint) = hint.filter(|h| !h.is_empty()) {
+        message = format!("{message}; hint: {hint}");
+    }
+    let request_id = None;
+
+    match (code.as_str(), status) {
+        ("23505", _) => Error::UniqueViolation {
+            constraint,
+            message,
+            request_id,
+        },
+        ("23503", _) => Error::ForeignKeyViolation {
+            constraint,
+            message,
+            request_id,
+        },
+        ("42501", _) => Error::PermissionDenied {
+            message,
+            request_id,
+        },
+        // A single object was asked for and no row matched.
+        ("PGRST116", _) | (_, 404) => Error::ObjectNotFound {
+            message,
+            request_id,
+        },
+        // A missing, malformed or expired JWT.
+        ("PGRST301" | "PGRST302" | "PGRST303", _) | (_, 401) => Error::Unauthorized {
+            message,
+            request_id,
+        },
+        (_, 403) => Error::RestrictedResource {
+            message,
+            request_id,
+        },
+        (_, 409) => Error::Conflict {
+            message,
+            request_id,
+        },
+        (_, 429) => Error::RateLimited {
+            retry_after,
+            request_id,
+        },
+        (_, 500..=599) => Error::Server {
+            status,
+            code: if code.is_empty() {
+                "server_error".into()
+            } else {
+                code
+            },
+            message,
+            request_id,
+        },
+        (_, 400) | (_, 406) | (_, 416) => Error::Validation {
+            code: if code.is_empty() {
+                "bad_request".into()
+            } else {
+                code
+            },
+            message,
+            request_id,
+        },
+        _ => Error::Http {
+            status,
+            message,
+            request_id,
+        },
+    }
+}
+
+/// The constraint a Postgres message names: `tasks_pkey` in `duplicate key
+/// value violates unique constraint "tasks_pkey"`.
+fn constraint_of(message: &str) -> Option<String> {
+    let rest = &message[message.find("constraint \"")? + "constraint \"".len()..];
+    Some(rest[..rest.find('"')?].to_string())
+}
+
+/// The JSON body Supabase Auth (GoTrue) sends with a failed request, in
+/// either of the shapes its versions use:
+///
+/// ```json
+/// { "code": 400, "error_code": "invalid_credentials", "msg": "Invalid login credentials" }
+/// { "error": "invalid_grant", "error_description": "Invalid Refresh Token: Already Used" }
+/// ```
+#[derive(Debug, Default, Deserialize)]
+struct AuthErrorBody {
+    #[serde(default)]
+    error_code: Option<String>,
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    msg: Option<String>,
+    #[serde(default)]
+    error_description: Option<String>,
+    #[serde(default)]
+    message: Option<String>,
+}
+
+/// Error codes meaning the credentials, code or token offered were not
+/// accepted, whatever status they come with.
+const REJECTED: &[&str] = &[
+    "invalid_grant",
+    "invalid_credentials",
+    "bad_jwt",
+    "no_authorization",
+    "session_not_found",
+    "session_expired",
+    "refresh_token_not_found",
+    "refresh_token_already_used",
+    "otp_expired",
+    "email_not_confirmed",
+    "user_banned",
+];
+
+/// Turn a failed Supabase Auth response into a typed [`Error`]. Rejected
+/// credentials and tokens are [`Error::Unauthorized`].
+pub(crate) fn from_auth_response(status: u16, retry_after: Option<Duration>, body: &str) -> Error {
+    let AuthErrorBody {
+        error_code,
+        error,
+        msg,
+        error_description,
+        message,
+    } = serde_json::from_str(body).unwrap_or_default();
+    let code = error_code.or(error).unwrap_or_default();
+    let message = msg
+        .or(error_description)
+        .or(message)
+        .filter(|m| !m.is_empty())
+        .unwrap_or_else(|| body.trim().to_string());
+    let request_id = None;
+
+    match (code.as_str(), status) {
+        (code, _) if REJECTED.contains(&code) => Error::Unauthorized {
+            message,
+            request_id,
+        },
+        (_, 401) => Error::Unauthorized {
+            message,
+            request_id,
+        },
+        (_, 403) => Error::RestrictedResource {
+            message,
+            request_id,
+        },
+        (_, 404) => Error::ObjectNotFound {
+            message,
+            request_id,
+        },
+        (_, 409) => Error::Conflict {
+            message,
+            request_id,
+        },
+        (_, 429) => Error::RateLimited {
+            retry_after,
+            request_id,
+        },
+        (_, 500..=599) => Error::Server {
+            status,
+            code: if code.is_empty() {
+                "server_error".into()
+            } else {
+                code
+            },
+            message,
+            request_id,
+        },
+        (_, 400) | (_, 422) => Error::Validation {
+            code: if code.is_empty() {
+                "bad_request".into()
+            } else {
+                code
+            },
+            message,
+            request_id,
+        },
+        _ => Error::Http {
+            status,
+            message,
+            request_id,
+        },
+    }
+}
+
+/// The JSON body Supabase Storage sends with a failed request. Its
+/// `statusCode` is the status meant, which older versions send with an
+/// HTTP 400 whatever went wrong:
+///
+/// ```json
+/// { "statusCode": "404", "error": "not_found", "message": "Object not found" }
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StorageErrorBody {
+    #[serde(default)]
+    status_code: Option<String>,
+    #[serde(default)]
+    error: String,
+    #[serde(default)]
+    message: String,
+}
+
+/// Turn a failed Supabase Storage response into a typed [`Error`]. A
+/// bucket policy refusing the user is [`Error::PermissionDenied`].
+pub(crate) fn from_storage_response(
+    status: u16,
+    retry_after: Option<Duration>,
+    body: &str,
+) -> Error {
+    let StorageErrorBody {
+        status_code,
+        error,
+        message,
+    } = serde_json::from_str(body).unwrap_or_default();
+    let status = status_code
+        .and_then(|code| code.parse().ok())
+        .unwrap_or(status);
+    let message = if message.is_empty() {
+        body.trim().to_string()
+    } else {
+        message
+    };
+    let request_id = None;
+
+    match status {
+        401 => Error::Unauthorized {
+            message,
+            request_id,
+        },
+        403 => Error::PermissionDenied {
+            message,
+            request_id,
+        },
+        404 => Error::ObjectNotFound {
+            message,
+            request_id,
+        },
+        409 => Error::Conflict {
+            message,
+            request_id,
+        },
+        429 => Error::RateLimited {
+            retry_after,
+            request_id,
+        },
+        500..=599 => Error::Server {
+            status,
+            code: if error.is_empty() {
+                "server_error".into()
+            } else {
+                error
+            },
+            message,
+            request_id,
+        },
+        400 | 413 | 415 => Error::Validation {
+            code: if error.is_empty() {
+                "bad_request".into()
+            } else {
+                error
+            },
+            message,
+            request_id,
+        },
+        _ => Error::Http {
+            status,
+            message,
+            request_id,
+        },
+    }
+}
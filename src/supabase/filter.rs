@@ -0,0 +1,373 @@
+//! Row filters and orderings in PostgREST's query string syntax.
+//!
+//! ```
+//! use swivel::supabase::Filter;
+//!
+//! let filter = Filter::column("status")
+//!     .in_(["open", "blocked"])
+//!     .or(Filter::column("priority").gte(3))
+//!     .and(!Filter::column("title").ilike("*draft*"));
+//! assert_eq!(
+//!     filter.to_param(),
+//!     ("and".to_string(), "(or(status.in.(open,blocked),priority.gte.3),title.not.ilike.*draft*)".to_string())
+//! );
+//! ```
+
+use std::fmt::Display;
+use std::ops::Not;
+
+/// A condition on the rows of a [`Query`](super::Query).
+///
+/// Start one with [`Filter::column`], combine them with
+/// [`and`](Filter::and), [`or`](Filter::or), [`Filter::all`] and
+/// [`Filter::any`], and negate any filter with `!`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Filter {
+    node: Node,
+    negated: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Condition {
+        column: String,
+        op: String,
+        operand: Operand,
+    },
+    And(Vec<Filter>),
+    Or(Vec<Filter>),
+}
+
+/// An operator's argument, as PostgREST reads it.
+#[derive(Debug, Clone, PartialEq)]
+enum Operand {
+    /// One value, quoted inside `and`/`or` groups when it holds characters
+    /// the group syntax reserves.
+    Scalar(String),
+    /// A list or keyword already in PostgREST syntax (`(a,b)`, `{a,b}`,
+    /// `null`), sent as is.
+    Literal(String),
+}
+
+impl Filter {
+    /// Start a condition on `name`. Embedded resources are addressed as
+    /// `author.name`, JSON fields as `data->>key`.
+    pub fn column(name: impl Into<String>) -> ColumnFilter {
+        ColumnFilter {
+            column: name.into(),
+            config: None,
+        }
+    }
+
+    /// Match rows that satisfy every filter.
+    pub fn all(filters: impl IntoIterator<Item = Filter>) -> Self {
+        Self::from_node(Node::And(filters.into_iter().collect()))
+    }
+
+    /// Match rows that satisfy at least one filter.
+    pub fn any(filters: impl IntoIterator<Item = Filter>) -> Self {
+        Self::from_node(Node::Or(filters.into_iter().collect()))
+    }
+
+    /// Both `self` and `other`. Chained calls stay one flat `and`.
+    pub fn and(self, other: Filter) -> Self {
+        match self {
+            Self {
+                node: Node::And(mut filters),
+                negated: false,
+            } => {
+                filters.push(other);
+                Self::from_node(Node::And(filters))
+            }
+            filter => Self::all([filter, other]),
+        }
+    }
+
+    /// Either `self` or `other`. Chained calls stay one flat `or`.
+    pub fn or(self, other: Filter) -> Self {
+        match self {
+            Self {
+                node: Node::Or(mut filters),
+                negated: false,
+            } => {
+                filters.push(other);
+                Self::from_node(Node::Or(filters))
+            }
+            filter => Self::any([filter, other]),
+        }
+    }
+
+    /// The filter as one query parameter, unencoded: `("status",
+    /// "eq.open")` for a condition, `("or", "(a.eq.1,b.eq.2)")` for a group.
+    pub fn to_param(&self) -> (String, String) {
+        let not = if self.negated { "not." } else { "" };
+        match &self.node {
+            Node::Condition {
+                column,
+                op,
+                operand,
+            } => {
+                let value = match operand {
+                    Operand::Scalar(value) | Operand::Literal(value) => value,
+                };
+                (column.clone(), format!("{not}{op}.{value}"))
+            }
+            Node::And(filters) => (format!("{not}and"), group(filters)),
+            Node::Or(filters) => (format!("{not}or"), group(filters)),
+        }
+    }
+
+    /// The filter as an element of an `and`/`or` group:
+    /// `status.not.eq.open`, `or(a.eq.1,b.eq.2)`.
+    fn to_term(&self) -> String {
+        let not = if self.negated { "not." } else { "" };
+        match &self.node {
+            Node::Condition {
+                column,
+                op,
+                operand,
+            } => {
+                let value = match operand {
+                    Operand::Scalar(value) => quote(value),
+                    Operand::Literal(value) => value.clone(),
+                };
+                format!("{column}.{not}{op}.{value}")
+            }
+            Node::And(filters) => format!("{not}and{}", group(filters)),
+            Node::Or(filters) => format!("{not}or{}", group(filters)),
+        }
+    }
+
+    fn from_node(node: Node) -> Self {
+        Self {
+            node,
+            negated: false,
+        }
+    }
+}
+
+impl Not for Filter {
+    type Output = Filter;
+
+    /// Rows the filter does not match (PostgREST's `not.`).
+    fn not(mut self) -> Filter {
+        self.negated = !self.negated;
+        self
+    }
+}
+
+fn group(filters: &[Filter]) -> String {
+    let terms: Vec<_> = filters.iter().map(Filter::to_term).collect();
+    format!("({})", terms.join(","))
+}
+
+/// Double-quote a value that holds whitespace or characters PostgREST's
+/// list and group syntax reserves, escaping quotes and backslashes inside
+/// it.
+pub(crate) fn quote(value: &str) -> String {
+    let reserved = value.is_empty()
+        || value.contains(char::is_whitespace)
+        || value.contains([',', '.', ':', '(', ')', '{', '}', '"', '\\']);
+    if !reserved {
+        return value.to_string();
+    }
+    let escaped = value.replace('\\', "\\\\").replace('"', "\\\"");
+    format!("\"{escaped}\"")
+}
+
+fn list(values: impl IntoIterator<Item = impl Display>, open: char, close: char) -> String {
+    let items: Vec<_> = values
+        .into_iter()
+        .map(|value| quote(&value.to_string()))
+        .collect();
+    format!("{open}{}{close}", items.join(","))
+}
+
+/// A condition on one column, waiting for its operator; see
+/// [`Filter::column`].
+#[derive(Debug, Clone)]
+pub struct ColumnFilter {
+    column: String,
+    config: Option<String>,
+}
+
+impl ColumnFilter {
+    fn scalar(self, op: &str, value: impl Display) -> Filter {
+        self.operand(op, Operand::Scalar(value.to_string()))
+    }
+
+    fn literal(self, op: &str, value: String) -> Filter {
+        self.operand(op, Operand::Literal(value))
+    }
+
+    fn operand(self, op: &str, operand: Operand) -> Filter {
+        Filter::from_node(Node::Condition {
+            column: self.column,
+            op: op.to_string(),
+            operand,
+        })
+    }
+
+    /// `= value`
+    pub fn eq(self, value: impl Display) -> Filter {
+        self.scalar("eq", value)
+    }
+
+    /// `<> value`
+    pub fn neq(self, value: impl Display) -> Filter {
+        self.scalar("neq", value)
+    }
+
+    pub fn gt(self, value: impl Display) -> Filter {
+        self.scalar("gt", value)
+    }
+
+    pub fn gte(self, value: impl Display) -> Filter {
+        self.scalar("gte", value)
+    }
+
+    pub fn lt(self, value: impl Display) -> Filter {
+        self.scalar("lt", value)
+    }
+
+    pub fn lte(self, value: impl Display) -> Filter {
+        self.scalar("lte", value)
+    }
+
+    /// `LIKE pattern`, with `*` standing for SQL's `%`.
+    pub fn like(self, pattern: impl Display) -> Filter {
+        self.scalar("like", pattern)
+    }
+
+    /// Case-insensitive [`like`](Self::like).
+    pub fn ilike(self, pattern: impl Display) -> Filter {
+        self.scalar("ilike", pattern)
+    }
+
+    /// `IS NULL`
+    pub fn is_null(self) -> Filter {
+        self.literal("is", "null".into())
+    }
+
+    /// `IS TRUE`
+    pub fn is_true(self) -> Filter {
+        self.literal("is", "true".into())
+    }
+
+    /// `IS FALSE`
+    pub fn is_false(self) -> Filter {
+        self.literal("is", "false".into())
+    }
+
+    /// `IS UNKNOWN`
+    pub fn is_unknown(self) -> Filter {
+        self.literal("is", "unknown".into())
+    }
+
+    /// `IN (values...)`
+    pub fn in_(self, values: impl IntoIterator<Item = impl Display>) -> Filter {
+        self.literal("in", list(values, '(', ')'))
+    }
+
+    /// The array (`@>`) holds every one of `values`.
+    pub fn contains(self, values: impl IntoIterator<Item = impl Display>) -> Filter {
+        self.literal("cs", list(values, '{', '}'))
+    }
+
+    /// Every element of the array (`<@`) is one of `values`.
+    pub fn contained_by(self, values: impl IntoIterator<Item = impl Display>) -> Filter {
+        self.literal("cd", list(values, '{', '}'))
+    }
+
+    /// The array shares an element with `values` (`&&`).
+    pub fn overlaps(self, values: impl IntoIterator<Item = impl Display>) -> Filter {
+        self.literal("ov", list(values, '{', '}'))
+    }
+
+    /// Use the text search configuration `config` (e.g. `english`) for the
+    /// search operators that follow.
+    pub fn config(mut self, config: impl Into<String>) -> Self {
+        self.config = Some(config.into());
+        self
+    }
+
+    /// Full-text search with `to_tsquery`.
+    pub fn fts(self, query: impl Display) -> Filter {
+        self.search("fts", query)
+    }
+
+    /// Full-text search with `plainto_tsquery`.
+    pub fn plfts(self, query: impl Display) -> Filter {
+        self.search("plfts", query)
+    }
+
+    /// Full-text search with `phraseto_tsquery`.
+    pub fn phfts(self, query: impl Display) -> Filter {
+        self.search("phfts", query)
+    }
+
+    /// Full-text search with `websearch_to_tsquery`.
+    pub fn wfts(self, query: impl Display) -> Filter {
+        self.search("wfts", query)
+    }
+
+    /// Any other PostgREST operator, e.g. `("sl", "[1,10)")` for a range
+    /// strictly left of another. The operand is sent as written.
+    pub fn op(self, op: impl Into<String>, operand: impl Into<String>) -> Filter {
+        self.literal(&op.into(), operand.into())
+    }
+
+    fn search(self, op: &str, query: impl Display) -> Filter {
+        let op = match &self.config {
+            Some(config) => format!("{op}({config})"),
+            None => op.to_string(),
+        };
+        self.scalar(&op, query)
+    }
+}
+
+/// One column to sort by, for [`Query::order`](super::Query::order).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Order {
+    column: String,
+    descending: bool,
+    nulls: Option<&'static str>,
+}
+
+impl Order {
+    pub fn asc(column: impl Into<String>) -> Self {
+        Self {
+            column: column.into(),
+            descending: false,
+            nulls: None,
+        }
+    }
+
+    pub fn desc(column: impl Into<String>) -> Self {
+        Self {
+            descending: true,
+            ..Self::asc(column)
+        }
+    }
+
+    /// Put nulls before other values.
+    pub fn nulls_first(mut self) -> Self {
+        self.nulls = Some("nullsfirst");
+        self
+    }
+
+    /// Put nulls after other values.
+    pub fn nulls_last(mut self) -> Self {
+        self.nulls = Some("nullslast");
+        self
+    }
+
+    /// `column.asc`, `column.desc.nullslast`, ...
+    pub(crate) fn to_term(&self) -> String {
+        let direction = if self.descending { "desc" } else { "asc" };
+        match self.nulls {
+            Some(nulls) => format!("{}.{direction}.{nulls}", self.column),
+            None => format!("{}.{direction}", self.column),
+        }
+    }
+}
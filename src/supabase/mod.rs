@@ -0,0 +1,23 @@
+//! Supabase backend.
+//!
+//! [`SupabaseClient`] talks to a project's PostgREST API (`/rest/v1`) and
+//! implements [`Database`](crate::Database) with the rows of one table as
+//! records. Rows are JSON objects keyed by column name, or any serde type
+//! through [`SupabaseClient::fetch`] and [`SupabaseClient::upsert`].
+//! [`SupabaseClient::select`] reads many rows at once with a [`Query`].
+//! [`StorageClient`] uploads and downloads the files in a Storage bucket.
+//! [`AuthClient`] signs users in through Supabase Auth, so requests to both
+//! run as them under row-level security.
+
+mod auth;
+mod client;
+mod error;
+mod filter;
+mod query;
+mod storage;
+
+pub use auth::{AuthClient, FileStore, MemoryStore, Session, SessionStore, User};
+pub use client::SupabaseClient;
+pub use filter::{ColumnFilter, Filter, Order};
+pub use query::{Count, Query, Rows};
+pub use storage::StorageClient;
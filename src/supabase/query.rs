@@ -0,0 +1,316 @@
+use reqwest::Method;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+use super::{client, filter};
+use super::{Filter, Order, SupabaseClient};
+use crate::http::HttpRequest;
+use crate::{Error, Result};
+
+/// How PostgREST should count the rows a [`Query`] matches, sent as
+/// `Prefer: count=...`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Count {
+    /// `COUNT(*)`: accurate, slow on large tables.
+    Exact,
+    /// The planner's estimate: fast, approximate.
+    Planned,
+    /// Exact up to the server's `db-max-rows`, planned beyond it.
+    Estimated,
+}
+
+impl Count {
+    fn as_str(self) -> &'static str {
+        match self {
+            Count::Exact => "exact",
+            Count::Planned => "planned",
+            Count::Estimated => "estimated",
+        }
+    }
+}
+
+/// One page of query results.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rows<T> {
+    pub rows: Vec<T>,
+    /// Every matching row, not just this page's; set when the query asked
+    /// for a [`Count`].
+    pub total: Option<u64>,
+}
+
+/// A read from the client's table, built with [`SupabaseClient::select`],
+/// or a call to a Postgres function, built with [`SupabaseClient::call`].
+///
+/// Filters given separately must all match; on a function they apply to
+/// the rows it returns. Nothing is sent until [`execute`](Self::execute) or
+/// [`value`](Self::value).
+///
+/// ```no_run
+/// # use swivel::supabase::{Count, Filter, Order, SupabaseClient};
+/// # use serde_json::Value;
+/// let posts = SupabaseClient::from_env("posts")?;
+/// let page = posts
+///     .select("id,title,author(name)")
+///     .filter(Filter::column("published").is_true())
+///     .order(Order::desc("published_at").nulls_last())
+///     .range(0, 24)
+///     .count(Count::Exact)
+///     .execute::<Value>()?;
+/// println!("{} of {:?}", page.rows.len(), page.total);
+/// # Ok::<(), swivel::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct Query<'a> {
+    client: &'a SupabaseClient,
+    target: Target,
+    columns: Option<String>,
+    filters: Vec<Filter>,
+    order: Vec<Order>,
+    limit: Option<u64>,
+    offset: Option<u64>,
+    range: Option<(u64, u64)>,
+    count: Option<Count>,
+}
+
+/// What a [`Query`] reads from.
+#[derive(Debug, Clone)]
+enum Target {
+    Table,
+    Function {
+        name: String,
+        /// The arguments, or why they could not be serialized.
+        args: std::result::Result<Map<String, Value>, String>,
+        get: bool,
+    },
+}
+
+impl<'a> Query<'a> {
+    pub(crate) fn new(client: &'a SupabaseClient, columns: String) -> Self {
+        Self::with_target(client, Target::Table, Some(columns))
+    }
+
+    pub(crate) fn function(
+        client: &'a SupabaseClient,
+        name: String,
+        args: std::result::Result<Map<String, Value>, String>,
+    ) -> Self {
+        let target = Target::Function {
+            name,
+            args,
+            get: false,
+        };
+        Self::with_target(client, target, None)
+    }
+
+    fn with_target(client: &'a SupabaseClient, target: Target, columns: Option<String>) -> Self {
+        Self {
+            client,
+            target,
+            columns,
+            filters: Vec::new(),
+            order: Vec::new(),
+            limit: None,
+            offset: None,
+            range: None,
+            count: None,
+        }
+    }
+
+    /// Return only `columns` of each row; see [`SupabaseClient::select`].
+    pub fn select(mut self, columns: impl Into<String>) -> Self {
+        self.columns = Some(columns.into());
+        self
+    }
+
+    /// Call the function with `GET`, its arguments in the query string, as
+    /// PostgREST allows for `IMMUTABLE` and `STABLE` functions. Such calls
+    /// are safe to retry. Table reads always use `GET`.
+    pub fn immutable(mut self) -> Self {
+        if let Target::Function { get, .. } = &mut self.target {
+            *get = true;
+        }
+        self
+    }
+
+    /// Only return rows matching `filter`, as well as any filter already
+    /// given.
+    pub fn filter(mut self, filter: Filter) -> Self {
+        self.filters.push(filter);
+        self
+    }
+
+    /// Sort by `order` after any ordering already given.
+    pub fn order(mut self, order: Order) -> Self {
+        self.order.push(order);
+        self
+    }
+
+    /// Return at most `limit` rows.
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Skip the first `offset` rows.
+    pub fn offset(mut self, offset: u64) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Return rows `from` to `to`, both included and counted from zero,
+    /// with a `Range` header instead of query parameters.
+    pub fn range(mut self, from: u64, to: u64) -> Self {
+        self.range = Some((from, to));
+        self
+    }
+
+    /// Count every matching row; see [`Rows::total`].
+    pub fn count(mut self, count: Count) -> Self {
+        self.count = Some(count);
+        self
+    }
+
+    /// The query string, URL-encoded: `select=*&status=eq.open&order=...`.
+    /// Arguments of a function called with `GET` come first.
+    pub fn query_string(&self) -> String {
+        let mut params = Vec::new();
+        if let Target::Function {
+            args: Ok(args),
+            get: true,
+            ..
+        } = &self.target
+        {
+            // A null argument is left out, so the function's default applies.
+            let args = args.iter().filter(|(_, value)| !value.is_null());
+            params.extend(args.map(|(name, value)| (name.clone(), arg(value))));
+        }
+        if let Some(columns) = &self.columns {
+            params.push(("select".to_string(), columns.clone()));
+        }
+        params.extend(self.filters.iter().map(Filter::to_param));
+        if !self.order.is_empty() {
+            let terms: Vec<_> = self.order.iter().map(Order::to_term).collect();
+            params.push(("order".into(), terms.join(",")));
+        }
+        if let Some(limit) = self.limit {
+            params.push(("limit".into(), limit.to_string()));
+        }
+        if let Some(offset) = self.offset {
+            params.push(("offset".into(), offset.to_string()));
+        }
+        let pairs: Vec<_> = params
+            .iter()
+            .map(|(key, value)| format!("{}={}", encode(key), encode(value)))
+            .collect();
+        pairs.join("&")
+    }
+
+    /// Send the query and decode the rows as `T`.
+    pub fn execute<T: DeserializeOwned>(&self) -> Result<Rows<T>> {
+        let req = self.request()?;
+        self.client.transport().execute(&req, |resp| {
+            let total = resp.header("content-range").and_then(total_of);
+            let rows = client::parse_json(resp)?;
+            Ok(Rows { rows, total })
+        })
+    }
+
+    /// Send the query and decode the whole response as `T`, whatever its
+    /// shape: one value for a scalar function, a `Vec` for a set, `()` for
+    /// a void function.
+    pub fn value<T: DeserializeOwned>(&self) -> Result<T> {
+        let req = self.request()?;
+        self.client.transport().execute(&req, |mut resp| {
+            // Void functions answer with no content at all.
+            if resp.is_success() && resp.body.trim().is_empty() {
+                resp.body = "null".into();
+            }
+            client::parse_json(resp)
+        })
+    }
+
+    fn request(&self) -> Result<HttpRequest> {
+        if let Some((from, to)) = self.range {
+            if from > to {
+                return Err(Error::InvalidInput(format!(
+                    "range {from}-{to} ends before it starts"
+                )));
+            }
+        }
+        let mut req = match &self.target {
+            Target::Table => {
+                let url = format!("{}?{}", self.client.table_url()?, self.query_string());
+                self.client.request(Method::GET, url)?
+            }
+            Target::Function { name, args, get } => {
+                let args = args.clone().map_err(Error::InvalidInput)?;
+                let mut url = self.client.function_url(name)?.to_string();
+                let query = self.query_string();
+                if !query.is_empty() {
+                    url = format!("{url}?{query}");
+                }
+                if *get {
+                    self.client.request(Method::GET, url)?
+                } else {
+                    self.client
+                        .request(Method::POST, url)?
+                        .json(Value::Object(args))
+                }
+            }
+        };
+        if let Some((from, to)) = self.range {
+            req = req
+                .header("Range-Unit", "items")
+                .header("Range", format!("{from}-{to}"));
+        }
+        if let Some(count) = self.count {
+            req = req.header("Prefer", format!("count={}", count.as_str()));
+        }
+        Ok(req)
+    }
+}
+
+/// A function argument as a query string value: strings as they are,
+/// arrays in Postgres' `{a,b}` form, anything else as JSON.
+fn arg(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Array(items) => {
+            let items: Vec<_> = items.iter().map(|item| filter::quote(&arg(item))).collect();
+            format!("{{{}}}", items.join(","))
+        }
+        other => other.to_string(),
+    }
+}
+
+/// The total in a `Content-Range` header: `0-24/3573`, or `*/0` when
+/// nothing matched. `*` in place of the total means it was not counted.
+fn total_of(content_range: &str) -> Option<u64> {
+    content_range.rsplit_once('/')?.1.trim().parse().ok()
+}
+
+/// Percent-encode a query string key or value. PostgREST's own syntax
+/// characters are left readable; everything else outside the unreserved set
+/// is escaped, `&`, `=`, `+`, `%` and `#` included.
+pub(crate) fn encode(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z'
+            | b'a'..=b'z'
+            | b'0'..=b'9'
+            | b'-'
+            | b'_'
+            | b'.'
+            | b'~'
+            | b'('
+            | b')'
+            | b','
+            | b':'
+            | b'*'
+            | b'!' => out.push(byte as char),
+            _ => out.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    out
+}
@@ -0,0 +1,130 @@
+use reqwest::{Method, Url};
+use serde_json::json;
+
+use super::auth::AuthClient;
+use super::client::{self, authorized};
+use super::error;
+use crate::http::{HttpRequest, HttpResponse, Transport};
+use crate::{Error, Result, RetryPolicy};
+
+/// Blocking client for one bucket of a Supabase project's Storage API
+/// (`/storage/v1`).
+///
+/// Objects are addressed by their path in the bucket, such as
+/// `avatars/ann.png`. Like [`SupabaseClient`](super::SupabaseClient),
+/// requests carry the project key, or the signed-in user's token after
+/// [`with_auth`](Self::with_auth) so the bucket's policies apply to them.
+/// Cloning is cheap: clones share the connection pool.
+#[derive(Debug, Clone)]
+pub struct StorageClient {
+    transport: Transport,
+    url: String,
+    api_key: String,
+    bucket: String,
+    auth: Option<AuthClient>,
+}
+
+impl StorageClient {
+    /// Create a client for `bucket` in the project at `url`
+    /// (`https://<ref>.supabase.co`), authenticated with an anon or
+    /// service-role key.
+    pub fn new(
+        url: impl Into<String>,
+        api_key: impl Into<String>,
+        bucket: impl Into<String>,
+    ) -> Self {
+        let url: String = url.into();
+        Self {
+            transport: Transport::new(None),
+            url: url.trim_end_matches('/').to_string(),
+            api_key: api_key.into(),
+            bucket: bucket.into(),
+            auth: None,
+        }
+    }
+
+    /// Read the project URL from `SUPABASE_URL` and the key from
+    /// `SUPABASE_KEY`.
+    pub fn from_env(bucket: impl Into<String>) -> Result<Self> {
+        Ok(Self::new(
+            client::env_var("SUPABASE_URL")?,
+            client::env_var("SUPABASE_KEY")?,
+            bucket,
+        ))
+    }
+
+    /// Replace the default retry policy.
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.transport.retry = retry;
+        self
+    }
+
+    /// Send requests as the user signed in to `auth`; see
+    /// [`SupabaseClient::with_auth`](super::SupabaseClient::with_auth).
+    pub fn with_auth(mut self, auth: AuthClient) -> Self {
+        self.auth = Some(auth);
+        self
+    }
+
+    /// Store `data` at `path`, replacing any object already there.
+    pub fn upload(&self, path: &str, data: impl Into<Vec<u8>>, content_type: &str) -> Result<()> {
+        // Uploading the same bytes twice leaves the same object, so retries
+        // are safe.
+        let req = self
+            .request(Method::POST, self.object_url(path)?)?
+            .header("x-upsert", "true")
+            .bytes(content_type, data.into())
+            .idempotent(true);
+        self.transport.execute(&req, check)
+    }
+
+    /// The object at `path`. A missing object is [`Error::ObjectNotFound`].
+    pub fn download(&self, path: &str) -> Result<Vec<u8>> {
+        let req = self.request(Method::GET, self.object_url(path)?)?;
+        self.transport.execute_bytes(&req, |resp| {
+            error::from_storage_response(resp.status, resp.retry_after(), &resp.body)
+        })
+    }
+
+    /// Delete the objects at `paths`; paths with no object are skipped.
+    pub fn remove(&self, paths: &[&str]) -> Result<()> {
+        let req = self
+            .request(Method::DELETE, self.url(["object", &self.bucket])?)?
+            .json(json!({ "prefixes": paths }));
+        self.transport.execute(&req, check)
+    }
+
+    /// `{url}/storage/v1/object/{bucket}/{path}`.
+    fn object_url(&self, path: &str) -> Result<Url> {
+        let mut url = self.url(["object", &self.bucket])?;
+        url.path_segments_mut()
+            .map_err(|_| Error::Config(format!("invalid Supabase URL `{}`", self.url)))?
+            .extend(path.split('/').filter(|segment| !segment.is_empty()));
+        Ok(url)
+    }
+
+    fn url<'a>(&self, segments: impl IntoIterator<Item = &'a str>) -> Result<Url> {
+        let mut url = Url::parse(&format!("{}/storage/v1", self.url))
+            .map_err(|err| Error::Config(format!("invalid Supabase URL `{}`: {err}", self.url)))?;
+        url.path_segments_mut()
+            .map_err(|_| Error::Config(format!("invalid Supabase URL `{}`", self.url)))?
+            .extend(segments);
+        Ok(url)
+    }
+
+    fn request(&self, method: Method, url: Url) -> Result<HttpRequest> {
+        authorized(method, url, &self.api_key, self.auth.as_ref())
+    }
+}
+
+/// Turn a failed response into a typed [`Error`].
+fn check(resp: HttpResponse) -> Result<()> {
+    if resp.is_success() {
+        return Ok(());
+    }
+    Err(error::from_storage_response(
+        resp.status,
+        resp.retry_after(),
+        &resp.body,
+    ))
+}
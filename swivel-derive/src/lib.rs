@@ -0,0 +1,266 @@
+//! `#[derive(Record)]` for [swivel](https://docs.rs/swivel).
+//!
+//! Use it through swivel's `derive` feature, which re-exports the macro next
+//! to the `Record` trait it implements:
+//!
+//! ```ignore
+//! use swivel::Record;
+//!
+//! #[derive(Record)]
+//! struct Task {
+//!     #[swivel(id)]
+//!     id: String,
+//!     #[swivel(notion = "Name")]
+//!     title: String,
+//!     #[swivel(notion = "Due Date", postgres = "due_date", supabase = "due_date")]
+//!     due: Option<String>,
+//!     #[swivel(skip)]
+//!     dirty: bool,
+//! }
+//! ```
+//!
+//! Field attributes:
+//!
+//! - `id`: the field that addresses the record; exactly one is required.
+//! - `notion = "..."`, `supabase = "..."`, `postgres = "..."`: the field's
+//!   name on that backend. Without one, the Rust field name is used.
+//! - `skip`: not stored; filled with `Default::default()` when read.
+//!
+//! Field types need `serde::Serialize` and `serde::Deserialize`.
+
+use std::collections::HashMap;
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, Ident, LitStr};
+
+/// Backend keys accepted in `#[swivel(...)]`, and the `swivel::Backend`
+/// variant each names.
+const BACKENDS: &[(&str, &str)] = &[
+    ("notion", "Notion"),
+    ("supabase", "Supabase"),
+    ("postgres", "Postgres"),
+];
+
+#[proc_macro_derive(Record, attributes(swivel))]
+pub fn derive_record(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// One field and what its attributes say about it.
+struct Field<'a> {
+    ident: &'a Ident,
+    ty: &'a syn::Type,
+    id: bool,
+    skip: bool,
+    /// Backend key and name, for backends that rename the field.
+    names: Vec<(&'static str, LitStr)>,
+}
+
+impl Field<'_> {
+    /// The field's name on `backend`.
+    fn name(&self, backend: &str) -> String {
+        self.names
+            .iter()
+            .find(|(key, _)| *key == backend)
+            .map_or_else(|| self.ident.to_string(), |(_, name)| name.value())
+    }
+
+    /// An expression for the field's name on the `backend` in scope.
+    fn name_expr(&self) -> TokenStream2 {
+        let default = self.ident.to_string();
+        if self.names.is_empty() {
+            return quote!(#default);
+        }
+        let arms = self.names.iter().map(|(key, name)| {
+            let variant = variant(key);
+            quote!(::swivel::Backend::#variant => #name,)
+        });
+        quote! {
+            match backend {
+                #(#arms)*
+                _ => #default,
+            }
+        }
+    }
+}
+
+fn variant(key: &str) -> Ident {
+    let (_, variant) = BACKENDS.iter().find(|(k, _)| *k == key).expect("known key");
+    Ident::new(variant, Span::call_site())
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(Error::new(input.ident.span(), NAMED_FIELDS_ONLY)),
+        },
+        _ => return Err(Error::new(input.ident.span(), NAMED_FIELDS_ONLY)),
+    };
+
+    let mut parsed = Vec::new();
+    for field in fields {
+        let ident = field.ident.as_ref().expect("named field");
+        parsed.push(parse_field(ident, field)?);
+    }
+    check(input, &parsed)?;
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let id = parsed.iter().find(|f| f.id).expect("checked");
+    let id_ident = id.ident;
+    let key = id.name_expr();
+
+    let stored: Vec<_> = parsed.iter().filter(|f| !f.skip).collect();
+    // Conversions carry the field type's span, so a type that does not
+    // serialize is reported on the field rather than on the derive.
+    let inserts = stored.iter().map(|f| {
+        let ident = f.ident;
+        let name = f.name_expr();
+        let value =
+            quote_spanned!(f.ty.span()=> ::swivel::__private::to_field(&self.#ident, #name)?);
+        quote! {
+            fields.insert(::std::string::ToString::to_string(#name), #value);
+        }
+    });
+    let reads = parsed.iter().map(|f| {
+        let ident = f.ident;
+        if f.skip {
+            return quote_spanned!(f.ty.span()=> #ident: ::std::default::Default::default(),);
+        }
+        let name = f.name_expr();
+        quote_spanned!(f.ty.span()=> #ident: ::swivel::__private::from_field(&mut fields, #name)?,)
+    });
+
+    Ok(quote! {
+        impl #impl_generics ::swivel::Record for #name #ty_generics #where_clause {
+            fn key(backend: ::swivel::Backend) -> &'static str {
+                #key
+            }
+
+            fn id(&self) -> ::std::option::Option<::std::string::String> {
+                ::swivel::__private::id_string(&self.#id_ident)
+            }
+
+            fn to_fields(
+                &self,
+                backend: ::swivel::Backend,
+            ) -> ::swivel::Result<::swivel::__private::Map> {
+                let _ = backend;
+                let mut fields = ::swivel::__private::Map::new();
+                #(#inserts)*
+                ::std::result::Result::Ok(fields)
+            }
+
+            fn from_fields(
+                backend: ::swivel::Backend,
+                mut fields: ::swivel::__private::Map,
+            ) -> ::swivel::Result<Self> {
+                let _ = (backend, &mut fields);
+                ::std::result::Result::Ok(Self {
+                    #(#reads)*
+                })
+            }
+        }
+    })
+}
+
+const NAMED_FIELDS_ONLY: &str = "`#[derive(Record)]` only supports structs with named fields";
+
+fn parse_field<'a>(ident: &'a Ident, field: &'a syn::Field) -> syn::Result<Field<'a>> {
+    let mut parsed = Field {
+        ident,
+        ty: &field.ty,
+        id: false,
+        skip: false,
+        names: Vec::new(),
+    };
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("swivel")) {
+        attr.parse_nested_meta(|meta| {
+            let path = &meta.path;
+            if path.is_ident("id") {
+                parsed.id = true;
+                return Ok(());
+            }
+            if path.is_ident("skip") {
+                parsed.skip = true;
+                return Ok(());
+            }
+            let key = BACKENDS
+                .iter()
+                .map(|(key, _)| *key)
+                .find(|key| path.is_ident(key));
+            let Some(key) = key else {
+                let found = quote!(#path).to_string().replace(' ', "");
+                return Err(meta.error(format!(
+                    "unknown swivel attribute `{found}`; expected `id`, `skip`, \
+                     `notion`, `supabase` or `postgres`"
+                )));
+            };
+            let name: LitStr = meta.value()?.parse()?;
+            if name.value().is_empty() {
+                return Err(Error::new(name.span(), "a field name cannot be empty"));
+            }
+            if parsed.names.iter().any(|(k, _)| *k == key) {
+                return Err(meta.error(format!("`{key}` is given twice")));
+            }
+            parsed.names.push((key, name));
+            Ok(())
+        })?;
+    }
+    if parsed.skip && parsed.id {
+        return Err(Error::new(
+            ident.span(),
+            "the `#[swivel(id)]` field cannot be skipped",
+        ));
+    }
+    if parsed.skip && !parsed.names.is_empty() {
+        return Err(Error::new(
+            parsed.names[0].1.span(),
+            "a `#[swivel(skip)]` field is not stored, so it has no backend names",
+        ));
+    }
+    Ok(parsed)
+}
+
+/// Struct-wide rules: exactly one id, and no two stored fields sharing a
+/// name on any backend.
+fn check(input: &DeriveInput, fields: &[Field]) -> syn::Result<()> {
+    let mut ids = fields.iter().filter(|f| f.id);
+    if ids.next().is_none() {
+        return Err(Error::new(
+            input.ident.span(),
+            "`#[derive(Record)]` needs one field marked `#[swivel(id)]`",
+        ));
+    }
+    if let Some(extra) = ids.next() {
+        return Err(Error::new(
+            extra.ident.span(),
+            "only one field can be `#[swivel(id)]`",
+        ));
+    }
+    for (backend, _) in BACKENDS {
+        let mut seen: HashMap<String, &Ident> = HashMap::new();
+        for field in fields.iter().filter(|f| !f.skip) {
+            let name = field.name(backend);
+            if let Some(first) = seen.insert(name.clone(), field.ident) {
+                let span = field
+                    .names
+                    .iter()
+                    .find(|(k, _)| k == backend)
+                    .map_or(field.ident.span(), |(_, lit)| lit.span());
+                return Err(Error::new(
+                    span,
+                    format!("`{name}` is already the {backend} name of `{first}`"),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
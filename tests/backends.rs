@@ -0,0 +1,50 @@
+use std::process::Command;
+
+#[test]
+fn backends_lists_the_compiled_features() {
+    let expected: Vec<&str> = [
+        ("notion", cfg!(feature = "notion")),
+        ("supabase", cfg!(feature = "supabase")),
+        ("postgres", cfg!(feature = "postgres")),
+        ("sqlite", cfg!(feature = "sqlite")),
+    ]
+    .into_iter()
+    .filter_map(|(name, enabled)| enabled.then_some(name))
+    .collect();
+    assert_eq!(swivel::BACKENDS, expected.as_slice());
+}
+
+fn swivel(args: &[&str]) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_swivel"))
+        .args(args)
+        .env_clear()
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn cli_exits_with_2_for_backends_not_compiled_in() {
+    for name in ["notion", "supabase", "postgres", "sqlite"] {
+        if swivel::BACKENDS.contains(&name) {
+            continue;
+        }
+        let out = swivel(&[name, "get", "table", "1"]);
+        assert_eq!(out.status.code(), Some(2), "{name}");
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        assert!(stderr.contains("not compiled into this build"), "{stderr}");
+    }
+
+    let out = swivel(&["mongodb", "get", "1"]);
+    assert_eq!(out.status.code(), Some(2));
+    let usage = String::from_utf8_lossy(&out.stderr);
+    for command in ["notion search", "notion schema", "codegen", "supabase rpc"] {
+        assert!(
+            usage.contains(command),
+            "{command} is missing from\n{usage}"
+        );
+    }
+    let out = swivel(&["backends"]);
+    assert_eq!(out.status.code(), Some(0));
+    let listed = String::from_utf8_lossy(&out.stdout);
+    assert_eq!(listed.lines().collect::<Vec<_>>(), swivel::BACKENDS);
+}
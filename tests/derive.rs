@@ -0,0 +1,242 @@
+#![cfg(feature = "derive")]
+
+#[cfg(feature = "notion")]
+mod common;
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde_json::{json, Map, Value};
+use swivel::{Backend, Database, Record, Records};
+
+#[derive(Debug, Clone, PartialEq, Record)]
+struct Task {
+    #[swivel(id)]
+    id: String,
+    #[swivel(notion = "Name")]
+    title: String,
+    #[swivel(notion = "Due Date", postgres = "due_date", supabase = "due_on")]
+    due: Option<String>,
+    #[swivel(notion = "Tags")]
+    tags: Vec<String>,
+    #[swivel(notion = "Done")]
+    done: bool,
+    #[swivel(skip)]
+    dirty: bool,
+}
+
+fn task() -> Task {
+    Task {
+        id: "42".into(),
+        title: "Ship it".into(),
+        due: Some("2025-10-01".into()),
+        tags: vec!["rust".into()],
+        done: false,
+        dirty: true,
+    }
+}
+
+#[test]
+fn names_fields_per_backend() {
+    let task = task();
+    assert_eq!(Task::key(Backend::Postgres), "id");
+    assert_eq!(task.id(), Some("42".into()));
+
+    let postgres = task.to_fields(Backend::Postgres).unwrap();
+    assert_eq!(
+        Value::Object(postgres),
+        json!({
+            "id": "42",
+            "title": "Ship it",
+            "due_date": "2025-10-01",
+            "tags": ["rust"],
+            "done": false
+        })
+    );
+    let notion = task.to_fields(Backend::Notion).unwrap();
+    let keys: Vec<_> = notion.keys().map(String::as_str).collect();
+    assert_eq!(keys, ["Done", "Due Date", "Name", "Tags", "id"]);
+    let supabase = task.to_fields(Backend::Supabase).unwrap();
+    assert_eq!(supabase["due_on"], "2025-10-01");
+}
+
+#[test]
+fn reads_fields_back_with_defaults_for_skipped_ones() {
+    let fields = task().to_fields(Backend::Notion).unwrap();
+    let read = Task::from_fields(Backend::Notion, fields).unwrap();
+    assert_eq!(
+        read,
+        Task {
+            dirty: false,
+            ..task()
+        }
+    );
+
+    // Missing optional fields read as `None`; missing required ones fail
+    // with the backend's name for them.
+    let mut fields = Map::new();
+    fields.insert("id".into(), json!("7"));
+    fields.insert("title".into(), json!("Draft"));
+    fields.insert("tags".into(), json!([]));
+    fields.insert("done".into(), json!(true));
+    let read = Task::from_fields(Backend::Postgres, fields.clone()).unwrap();
+    assert_eq!(read.due, None);
+    fields.remove("title");
+    let err = Task::from_fields(Backend::Postgres, fields).unwrap_err();
+    assert!(err.to_string().contains("field `title`"), "{err}");
+}
+
+#[test]
+fn an_empty_id_means_not_stored_yet() {
+    let task = Task {
+        id: String::new(),
+        ..task()
+    };
+    assert_eq!(task.id(), None);
+
+    #[derive(Record)]
+    struct Row {
+        #[swivel(id)]
+        id: i64,
+    }
+    assert_eq!(Row { id: 7 }.id(), Some("7".into()));
+}
+
+/// Rows in memory, keyed by `id`, standing in for a SQL backend.
+#[derive(Default)]
+struct MemoryTable(Mutex<HashMap<String, Value>>);
+
+impl Database for MemoryTable {
+    type Record = Value;
+
+    fn get(&self, id: &str) -> swivel::Result<Value> {
+        let rows = self.0.lock().unwrap();
+        rows.get(id).cloned().ok_or(swivel::Error::ObjectNotFound {
+            message: id.to_string(),
+            request_id: None,
+        })
+    }
+
+    fn put(&self, rec: Value) -> swivel::Result<()> {
+        let id = rec["id"].as_str().unwrap().to_string();
+        self.0.lock().unwrap().insert(id, rec);
+        Ok(())
+    }
+}
+
+#[test]
+fn records_type_a_row_backend() {
+    let tasks: Records<MemoryTable, Task> = Records::new(MemoryTable::default(), Backend::Postgres);
+    tasks.put(task()).unwrap();
+    assert_eq!(tasks.inner().get("42").unwrap()["due_date"], "2025-10-01");
+    assert_eq!(
+        tasks.get("42").unwrap(),
+        Task {
+            dirty: false,
+            ..task()
+        }
+    );
+}
+
+#[cfg(feature = "notion")]
+mod notion {
+    use super::common::{page_json, MockServer, Reply};
+    use super::*;
+    use swivel::notion::NotionClient;
+
+    const DATA_SOURCE_ID: &str = "b5ad9a34-4e2f-4f8e-9c3c-1b0e6d4f7a21";
+    const PAGE_ID: &str = "275a1865-b187-807a-adea-ebaf36fb49b0";
+
+    fn server() -> MockServer {
+        MockServer::start(|req| {
+            if req.path.starts_with("/data_sources/") {
+                let mut schema: Value =
+                    serde_json::from_str(include_str!("fixtures/data_source.json")).unwrap();
+                schema["properties"]["Due Date"] =
+                    json!({ "id": "dd", "name": "Due Date", "type": "date", "date": {} });
+                return Reply::json(200, schema);
+            }
+            let mut page = page_json(PAGE_ID);
+            page["properties"] = json!({
+                "Name": { "id": "title", "type": "title", "title": [
+                    { "type": "text", "text": { "content": "Ship it" }, "plain_text": "Ship it" }
+                ] },
+                "Due Date": { "id": "dd", "type": "date", "date": { "start": "2025-10-01" } },
+                "Tags": { "id": "tag", "type": "multi_select", "multi_select": [{ "name": "rust" }] },
+                "Done": { "id": "dn", "type": "checkbox", "checkbox": false },
+                "Days left": { "id": "fx", "type": "formula", "formula": { "type": "number", "number": 10 } }
+            });
+            Reply::json(200, page)
+        })
+    }
+
+    fn client(server: &MockServer) -> NotionClient {
+        NotionClient::new("secret")
+            .with_base_url(server.url())
+            .with_rate_limiter(None)
+    }
+
+    #[test]
+    fn reads_pages_as_records() {
+        let server = server();
+        let notion = client(&server);
+        let task: Task = notion.records(DATA_SOURCE_ID).get(PAGE_ID).unwrap();
+        assert_eq!(
+            task,
+            Task {
+                id: PAGE_ID.into(),
+                dirty: false,
+                ..super::task()
+            }
+        );
+    }
+
+    #[test]
+    fn writes_records_as_typed_properties() {
+        let server = server();
+        let notion = client(&server);
+        let tasks = notion.records::<Task>(DATA_SOURCE_ID);
+        tasks
+            .put(Task {
+                id: String::new(),
+                ..task()
+            })
+            .unwrap();
+        tasks
+            .put(Task {
+                id: PAGE_ID.into(),
+                done: true,
+                ..task()
+            })
+            .unwrap();
+
+        let requests = server.requests();
+        let methods: Vec<_> = requests.iter().map(|r| r.method.as_str()).collect();
+        // The schema is fetched once.
+        assert_eq!(methods, ["GET", "POST", "PATCH"]);
+        let created = requests[1].json();
+        assert_eq!(
+            created["parent"],
+            json!({ "type": "data_source_id", "data_source_id": DATA_SOURCE_ID })
+        );
+        assert_eq!(
+            created["properties"]["Due Date"],
+            json!({ "type": "date", "date": { "start": "2025-10-01", "end": null, "time_zone": null } })
+        );
+        assert_eq!(
+            created["properties"]["Tags"],
+            json!({ "type": "multi_select", "multi_select": [{ "name": "rust" }] })
+        );
+        assert!(created["properties"].get("id").is_none());
+        assert_eq!(requests[2].path, format!("/pages/{PAGE_ID}"));
+        assert_eq!(
+            requests[2].json()["properties"]["Done"],
+            json!({ "type": "checkbox", "checkbox": true })
+        );
+    }
+}
+
+#[test]
+fn helpful_compile_errors() {
+    trybuild::TestCases::new().compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,397 @@
+// @generated by `swivel codegen notion` from the "Launch tasks" data source,
+// b5ad9a34-4e2f-4f8e-9c3c-1b0e6d4f7a21. Do not edit; regenerate when the schema changes.
+#![cfg_attr(rustfmt, rustfmt::skip)]
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde_json::{Map, Number, Value};
+use swivel::notion::{
+    DateValue, FileObject, FormulaValue, Page, Property, PropertyValue, RelationRef, Rollup,
+    SelectOption, UniqueId, User, Verification, rich_text,
+};
+use swivel::{Error, Result};
+
+/// A page of the "Launch tasks" data source.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Task {
+    /// "Name": title.
+    pub name: String,
+    /// "Attachments": files.
+    pub attachments: Vec<FileObject>,
+    /// "Blocked by": relation to `b5ad9a34-4e2f-4f8e-9c3c-1b0e6d4f7a21`.
+    pub blocked_by: Vec<String>,
+    /// "Budget": number.
+    pub budget: Option<f64>,
+    /// "Contact": email.
+    pub contact: Option<String>,
+    /// "Created": created time, read-only.
+    pub created: String,
+    /// "Creator": created by, read-only.
+    pub creator: User,
+    /// "Days left": formula, read-only.
+    pub days_left: FormulaValue,
+    /// "Done": checkbox.
+    pub done: bool,
+    /// "Due": date.
+    pub due: Option<DateValue>,
+    /// "Edited": last edited time, read-only.
+    pub edited: String,
+    /// "Editor": last edited by, read-only.
+    pub editor: User,
+    /// "Estimate": number.
+    pub estimate: Option<f64>,
+    /// "Link": url.
+    pub link: Option<String>,
+    /// "Notes": rich text.
+    pub notes: String,
+    /// "Owner": people.
+    pub owner: Vec<User>,
+    /// "Phone": phone number.
+    pub phone: Option<String>,
+    /// "Place": place, read-only.
+    pub place: PropertyValue,
+    /// "Priority": select.
+    pub priority: Option<Priority>,
+    /// "Related names": rollup, read-only.
+    pub related_names: Rollup,
+    /// "Status": status.
+    pub status: Option<Status>,
+    /// "Tags": multi select.
+    pub tags: Vec<Tags>,
+    /// "Ticket": unique id, read-only.
+    pub ticket: UniqueId,
+    /// "Total": rollup, read-only.
+    pub total: Rollup,
+    /// "Verified": verification, read-only.
+    pub verified: Option<Verification>,
+}
+
+impl Task {
+    /// The data source these pages belong to.
+    pub const DATA_SOURCE_ID: &'static str = "b5ad9a34-4e2f-4f8e-9c3c-1b0e6d4f7a21";
+
+    /// Read the properties of `page`.
+    pub fn from_page(page: &Page) -> Result<Self> {
+        Self::from_properties(&page.properties)
+    }
+
+    /// Read a page's `properties` object, as Notion sends it.
+    pub fn from_json(properties: &Value) -> Result<Self> {
+        let properties: BTreeMap<String, Property> =
+            serde_json::from_value(properties.clone())?;
+        Self::from_properties(&properties)
+    }
+
+    pub fn from_properties(properties: &BTreeMap<String, Property>) -> Result<Self> {
+        Ok(Self {
+            name: match value(properties, "Name")? {
+                PropertyValue::Title(v) => rich_text::plain_text(v),
+                other => return Err(mismatch("Name", "title", other)),
+            },
+            attachments: match value(properties, "Attachments")? {
+                PropertyValue::Files(v) => v.clone(),
+                other => return Err(mismatch("Attachments", "files", other)),
+            },
+            blocked_by: match value(properties, "Blocked by")? {
+                PropertyValue::Relation(v) => v.iter().map(|r| r.id.clone()).collect(),
+                other => return Err(mismatch("Blocked by", "relation", other)),
+            },
+            budget: match value(properties, "Budget")? {
+                PropertyValue::Number(v) => v.as_ref().and_then(Number::as_f64),
+                other => return Err(mismatch("Budget", "number", other)),
+            },
+            contact: match value(properties, "Contact")? {
+                PropertyValue::Email(v) => v.clone(),
+                other => return Err(mismatch("Contact", "email", other)),
+            },
+            created: match value(properties, "Created")? {
+                PropertyValue::CreatedTime(v) => v.clone(),
+                other => return Err(mismatch("Created", "created_time", other)),
+            },
+            creator: match value(properties, "Creator")? {
+                PropertyValue::CreatedBy(v) => v.clone(),
+                other => return Err(mismatch("Creator", "created_by", other)),
+            },
+            days_left: match value(properties, "Days left")? {
+                PropertyValue::Formula(v) => v.clone(),
+                other => return Err(mismatch("Days left", "formula", other)),
+            },
+            done: match value(properties, "Done")? {
+                PropertyValue::Checkbox(v) => *v,
+                other => return Err(mismatch("Done", "checkbox", other)),
+            },
+            due: match value(properties, "Due")? {
+                PropertyValue::Date(v) => v.clone(),
+                other => return Err(mismatch("Due", "date", other)),
+            },
+            edited: match value(properties, "Edited")? {
+                PropertyValue::LastEditedTime(v) => v.clone(),
+                other => return Err(mismatch("Edited", "last_edited_time", other)),
+            },
+            editor: match value(properties, "Editor")? {
+                PropertyValue::LastEditedBy(v) => v.clone(),
+                other => return Err(mismatch("Editor", "last_edited_by", other)),
+            },
+            estimate: match value(properties, "Estimate")? {
+                PropertyValue::Number(v) => v.as_ref().and_then(Number::as_f64),
+                other => return Err(mismatch("Estimate", "number", other)),
+            },
+            link: match value(properties, "Link")? {
+                PropertyValue::Url(v) => v.clone(),
+                other => return Err(mismatch("Link", "url", other)),
+            },
+            notes: match value(properties, "Notes")? {
+                PropertyValue::RichText(v) => rich_text::plain_text(v),
+                other => return Err(mismatch("Notes", "rich_text", other)),
+            },
+            owner: match value(properties, "Owner")? {
+                PropertyValue::People(v) => v.clone(),
+                other => return Err(mismatch("Owner", "people", other)),
+            },
+            phone: match value(properties, "Phone")? {
+                PropertyValue::PhoneNumber(v) => v.clone(),
+                other => return Err(mismatch("Phone", "phone_number", other)),
+            },
+            place: value(properties, "Place")?.clone(),
+            priority: match value(properties, "Priority")? {
+                PropertyValue::Select(v) => v.as_ref().map(|o| Priority::from_name(&o.name)),
+                other => return Err(mismatch("Priority", "select", other)),
+            },
+            related_names: match value(properties, "Related names")? {
+                PropertyValue::Rollup(v) => v.clone(),
+                other => return Err(mismatch("Related names", "rollup", other)),
+            },
+            status: match value(properties, "Status")? {
+                PropertyValue::Status(v) => v.as_ref().map(|o| Status::from_name(&o.name)),
+                other => return Err(mismatch("Status", "status", other)),
+            },
+            tags: match value(properties, "Tags")? {
+                PropertyValue::MultiSelect(v) => v.iter().map(|o| Tags::from_name(&o.name)).collect(),
+                other => return Err(mismatch("Tags", "multi_select", other)),
+            },
+            ticket: match value(properties, "Ticket")? {
+                PropertyValue::UniqueId(v) => v.clone(),
+                other => return Err(mismatch("Ticket", "unique_id", other)),
+            },
+            total: match value(properties, "Total")? {
+                PropertyValue::Rollup(v) => v.clone(),
+                other => return Err(mismatch("Total", "rollup", other)),
+            },
+            verified: match value(properties, "Verified")? {
+                PropertyValue::Verification(v) => v.clone(),
+                other => return Err(mismatch("Verified", "verification", other)),
+            },
+        })
+    }
+
+    /// The writable properties, for creating or updating a page.
+    pub fn to_properties(&self) -> BTreeMap<String, PropertyValue> {
+        let mut properties = BTreeMap::new();
+        properties.insert(
+            "Name".to_string(),
+            PropertyValue::Title(rich_text::from_plain_text(&self.name)),
+        );
+        properties.insert(
+            "Attachments".to_string(),
+            PropertyValue::Files(self.attachments.clone()),
+        );
+        properties.insert(
+            "Blocked by".to_string(),
+            PropertyValue::Relation(
+                self.blocked_by.iter().map(|id| RelationRef { id: id.clone() }).collect(),
+            ),
+        );
+        properties.insert(
+            "Budget".to_string(),
+            PropertyValue::Number(self.budget.and_then(Number::from_f64)),
+        );
+        properties.insert(
+            "Contact".to_string(),
+            PropertyValue::Email(self.contact.clone()),
+        );
+        properties.insert(
+            "Done".to_string(),
+            PropertyValue::Checkbox(self.done),
+        );
+        properties.insert(
+            "Due".to_string(),
+            PropertyValue::Date(self.due.clone()),
+        );
+        properties.insert(
+            "Estimate".to_string(),
+            PropertyValue::Number(self.estimate.and_then(Number::from_f64)),
+        );
+        properties.insert(
+            "Link".to_string(),
+            PropertyValue::Url(self.link.clone()),
+        );
+        properties.insert(
+            "Notes".to_string(),
+            PropertyValue::RichText(rich_text::from_plain_text(&self.notes)),
+        );
+        properties.insert(
+            "Owner".to_string(),
+            PropertyValue::People(self.owner.clone()),
+        );
+        properties.insert(
+            "Phone".to_string(),
+            PropertyValue::PhoneNumber(self.phone.clone()),
+        );
+        properties.insert(
+            "Priority".to_string(),
+            PropertyValue::Select(self.priority.as_ref().map(|o| SelectOption::named(o.name()))),
+        );
+        properties.insert(
+            "Status".to_string(),
+            PropertyValue::Status(self.status.as_ref().map(|o| SelectOption::named(o.name()))),
+        );
+        properties.insert(
+            "Tags".to_string(),
+            PropertyValue::MultiSelect(
+                self.tags.iter().map(|o| SelectOption::named(o.name())).collect(),
+            ),
+        );
+        // Files Notion hosts cannot be written back, only uploaded anew.
+        properties.retain(|_, value| !value.is_read_only());
+        properties
+    }
+
+    /// The writable properties as a `properties` object for the Notion API.
+    pub fn to_json(&self) -> Value {
+        let properties: Map<String, Value> = self
+            .to_properties()
+            .into_iter()
+            .map(|(name, value)| (name, serde_json::json!(Property::new(value))))
+            .collect();
+        Value::Object(properties)
+    }
+}
+
+/// The options of the "Priority" select property.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Priority {
+    High,
+    Medium,
+    Low,
+    /// An option added after this code was generated.
+    Unknown(String),
+}
+
+impl Priority {
+    /// The option's name in Notion.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::High => "High",
+            Self::Medium => "Medium",
+            Self::Low => "Low",
+            Self::Unknown(name) => name,
+        }
+    }
+
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "High" => Self::High,
+            "Medium" => Self::Medium,
+            "Low" => Self::Low,
+            other => Self::Unknown(other.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for Priority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The options of the "Status" status property.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Status {
+    NotStarted,
+    InProgress,
+    Done,
+    /// An option added after this code was generated.
+    Unknown(String),
+}
+
+impl Status {
+    /// The option's name in Notion.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::NotStarted => "Not started",
+            Self::InProgress => "In progress",
+            Self::Done => "Done",
+            Self::Unknown(name) => name,
+        }
+    }
+
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "Not started" => Self::NotStarted,
+            "In progress" => Self::InProgress,
+            "Done" => Self::Done,
+            other => Self::Unknown(other.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The options of the "Tags" multi-select property.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Tags {
+    Backend,
+    Rust,
+    FrontEnd,
+    V2025Q4,
+    /// An option added after this code was generated.
+    Unknown(String),
+}
+
+impl Tags {
+    /// The option's name in Notion.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Backend => "backend",
+            Self::Rust => "rust",
+            Self::FrontEnd => "front-end",
+            Self::V2025Q4 => "2025 Q4",
+            Self::Unknown(name) => name,
+        }
+    }
+
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "backend" => Self::Backend,
+            "rust" => Self::Rust,
+            "front-end" => Self::FrontEnd,
+            "2025 Q4" => Self::V2025Q4,
+            other => Self::Unknown(other.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for Tags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+fn value<'a>(properties: &'a BTreeMap<String, Property>, name: &str) -> Result<&'a PropertyValue> {
+    properties
+        .get(name)
+        .map(|p| &p.value)
+        .ok_or_else(|| Error::InvalidInput(format!("page has no property named `{name}`")))
+}
+
+fn mismatch(name: &str, expected: &str, found: &PropertyValue) -> Error {
+    Error::InvalidInput(format!(
+        "property `{name}` is a {} property, expected {expected}",
+        found.kind()
+    ))
+}
@@ -0,0 +1,95 @@
+#![cfg(all(feature = "notion", feature = "async"))]
+
+mod common;
+
+use std::time::Duration;
+
+use common::{MockServer, Reply};
+use serde_json::json;
+use swivel::notion::AsyncNotionClient;
+use swivel::{AsyncDatabase, Error, RetryPolicy};
+
+const PAGE_ID: &str = "0d2c4d47-5f3b-4a8e-9c1d-2b6e8f7a9c10";
+
+fn client(server: &MockServer) -> AsyncNotionClient {
+    AsyncNotionClient::new("secret")
+        .with_base_url(server.url())
+        .with_rate_limiter(None)
+        .with_retry_policy(RetryPolicy::default().with_base_delay(Duration::from_millis(1)))
+}
+
+#[tokio::test]
+async fn gets_a_page_inside_a_runtime() {
+    let server = MockServer::sequence(vec![Reply::json(200, common::page_json(PAGE_ID))]);
+    let page = client(&server).get(PAGE_ID).await.unwrap();
+    assert_eq!(page.id, PAGE_ID);
+
+    let req = &server.requests()[0];
+    assert_eq!(req.path, format!("/pages/{PAGE_ID}"));
+    assert_eq!(req.header("authorization"), Some("Bearer secret"));
+    assert_eq!(req.header("notion-version"), Some("2025-09-03"));
+}
+
+#[tokio::test]
+async fn shares_retry_and_error_parsing_with_blocking_client() {
+    let server = MockServer::sequence(vec![
+        Reply::json(
+            429,
+            json!({ "object": "error", "code": "rate_limited", "message": "" }),
+        )
+        .header("Retry-After", "0"),
+        Reply::json(
+            401,
+            json!({ "object": "error", "code": "unauthorized", "message": "API token is invalid.", "request_id": "r1" }),
+        ),
+    ]);
+    let err = client(&server).get_page(PAGE_ID).await.unwrap_err();
+    assert!(matches!(err, Error::Unauthorized { .. }));
+    assert_eq!(err.request_id(), Some("r1"));
+    assert_eq!(server.hits(), 2);
+}
+
+#[tokio::test]
+async fn put_sends_only_changed_properties() {
+    let server = MockServer::start(|_| Reply::json(200, common::page_json(PAGE_ID)));
+    let notion = client(&server);
+    let mut page = notion.get_page(PAGE_ID).await.unwrap();
+    page.in_trash = true;
+    notion.put(page).await.unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 3);
+    assert_eq!(requests[2].method, "PATCH");
+    assert_eq!(requests[2].json(), json!({ "in_trash": true }));
+}
+
+#[tokio::test]
+async fn database_ids_stand_for_their_data_source() {
+    const DATA_SOURCE_ID: &str = "b5ad9a34-4e2f-4f8e-9c3c-1b0e6d4f7a21";
+    let database_id = PAGE_ID;
+    let server = MockServer::sequence(vec![
+        Reply::json(
+            404,
+            json!({ "object": "error", "code": "object_not_found", "message": "" }),
+        ),
+        Reply::json(
+            200,
+            json!({ "object": "database", "id": database_id, "data_sources": [{ "id": DATA_SOURCE_ID, "name": "Tasks" }] }),
+        ),
+        Reply::json(
+            200,
+            json!({ "object": "data_source", "id": DATA_SOURCE_ID }),
+        ),
+    ]);
+    let schema = client(&server).get_data_source(database_id).await.unwrap();
+    assert_eq!(schema.id, DATA_SOURCE_ID);
+    let paths: Vec<_> = server.requests().into_iter().map(|r| r.path).collect();
+    assert_eq!(
+        paths,
+        [
+            format!("/data_sources/{database_id}"),
+            format!("/databases/{database_id}"),
+            format!("/data_sources/{DATA_SOURCE_ID}"),
+        ]
+    );
+}
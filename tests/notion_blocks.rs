@@ -0,0 +1,189 @@
+#![cfg(feature = "notion")]
+
+mod common;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use common::{MockServer, Reply};
+use serde_json::{json, Value};
+use swivel::notion::{BlockContent, BlockTree, ContentOptions, NotionClient};
+use swivel::Error;
+
+const PAGE_ID: &str = "0d2c4d47-5f3b-4a8e-9c1d-2b6e8f7a9c10";
+
+fn block(id: &str, kind: &str, has_children: bool) -> Value {
+    let payload = match kind {
+        "child_page" => json!({ "title": id }),
+        "synced_block" => json!({ "synced_from": null }),
+        _ => json!({
+            "rich_text": [{ "type": "text", "text": { "content": id }, "plain_text": id }],
+            "color": "default"
+        }),
+    };
+    json!({ "object": "block", "id": id, "has_children": has_children, "type": kind, kind: payload })
+}
+
+fn list(results: Vec<Value>, next_cursor: Option<&str>) -> Reply {
+    Reply::json(
+        200,
+        json!({
+            "object": "list",
+            "results": results,
+            "next_cursor": next_cursor,
+            "has_more": next_cursor.is_some()
+        }),
+    )
+}
+
+/// page
+/// ├── p1
+/// ├── t1 (toggle)
+/// │   └── t1a
+/// │       └── t1a1
+/// ├── cp (child page)
+/// │   └── cp1
+/// └── sy (synced block)
+///     └── sy1
+///
+/// The page's own children come back in two pages of results.
+fn server() -> MockServer {
+    MockServer::start(|req| match req.path.replace(PAGE_ID, "page").as_str() {
+        "/blocks/page/children?page_size=100" => list(
+            vec![block("p1", "paragraph", false), block("t1", "toggle", true)],
+            Some("c2"),
+        ),
+        "/blocks/page/children?page_size=100&start_cursor=c2" => list(
+            vec![
+                block("cp", "child_page", true),
+                block("sy", "synced_block", true),
+            ],
+            None,
+        ),
+        "/blocks/t1/children?page_size=100" => {
+            list(vec![block("t1a", "bulleted_list_item", true)], None)
+        }
+        "/blocks/t1a/children?page_size=100" => list(vec![block("t1a1", "paragraph", false)], None),
+        "/blocks/cp/children?page_size=100" => list(vec![block("cp1", "paragraph", false)], None),
+        "/blocks/sy/children?page_size=100" => list(vec![block("sy1", "paragraph", false)], None),
+        other => panic!("unexpected request {other}"),
+    })
+}
+
+fn client(server: &MockServer) -> NotionClient {
+    NotionClient::new("secret")
+        .with_base_url(server.url())
+        .with_rate_limiter(None)
+}
+
+fn outline(tree: &BlockTree) -> Vec<String> {
+    tree.iter()
+        .map(|(depth, block)| format!("{}{}", "  ".repeat(depth), block.id))
+        .collect()
+}
+
+fn fetched(server: &MockServer) -> Vec<String> {
+    let mut paths: Vec<String> = server.requests().into_iter().map(|r| r.path).collect();
+    paths.sort();
+    paths
+}
+
+#[test]
+fn walks_the_whole_page_by_default() {
+    let server = server();
+    let tree = client(&server).page_content(PAGE_ID).unwrap();
+    assert_eq!(
+        outline(&tree),
+        ["p1", "t1", "  t1a", "    t1a1", "cp", "sy", "  sy1"]
+    );
+    assert_eq!(tree.len(), 7);
+    assert!(matches!(
+        tree.blocks[1].block.content,
+        BlockContent::Toggle(_)
+    ));
+    assert_eq!(tree.blocks[1].children[0].block.plain_text(), "t1a");
+
+    // The child page is a stop point by default.
+    assert!(tree.blocks[2].is_truncated());
+    assert!(!fetched(&server)
+        .iter()
+        .any(|p| p.starts_with("/blocks/cp/")));
+}
+
+#[test]
+fn honours_max_depth() {
+    let server = server();
+    let options = ContentOptions::default().with_max_depth(2);
+    let tree = client(&server)
+        .page_content_with(PAGE_ID, &options)
+        .unwrap();
+    assert_eq!(outline(&tree), ["p1", "t1", "  t1a", "cp", "sy", "  sy1"]);
+    assert!(tree.blocks[1].children[0].is_truncated());
+    assert!(!fetched(&server)
+        .iter()
+        .any(|p| p.starts_with("/blocks/t1a/")));
+}
+
+#[test]
+fn stop_points_are_configurable() {
+    let server = server();
+    let options = ContentOptions::default()
+        .with_child_pages(true)
+        .with_synced_blocks(false);
+    let tree = client(&server)
+        .page_content_with(PAGE_ID, &options)
+        .unwrap();
+    assert_eq!(
+        outline(&tree),
+        ["p1", "t1", "  t1a", "    t1a1", "cp", "  cp1", "sy"]
+    );
+    assert!(tree.blocks[3].is_truncated());
+}
+
+#[test]
+fn bounds_concurrent_requests() {
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let peak = Arc::new(AtomicUsize::new(0));
+    let server = {
+        let (in_flight, peak) = (Arc::clone(&in_flight), Arc::clone(&peak));
+        MockServer::start(move |req| {
+            let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(now, Ordering::SeqCst);
+            thread::sleep(Duration::from_millis(50));
+            in_flight.fetch_sub(1, Ordering::SeqCst);
+            if req.path.starts_with(&format!("/blocks/{PAGE_ID}/")) {
+                let toggles = (0..6)
+                    .map(|i| block(&format!("t{i}"), "toggle", true))
+                    .collect();
+                list(toggles, None)
+            } else {
+                list(vec![block("leaf", "paragraph", false)], None)
+            }
+        })
+    };
+    let options = ContentOptions::default().with_concurrency(2);
+    let tree = client(&server)
+        .page_content_with(PAGE_ID, &options)
+        .unwrap();
+    assert_eq!(tree.len(), 12);
+    assert_eq!(server.hits(), 7);
+    assert_eq!(peak.load(Ordering::SeqCst), 2);
+}
+
+#[test]
+fn fails_with_the_first_error() {
+    let server = MockServer::start(|req| {
+        if req.path.starts_with(&format!("/blocks/{PAGE_ID}/")) {
+            list(vec![block("t1", "toggle", true)], None)
+        } else {
+            Reply::json(
+                404,
+                json!({ "object": "error", "status": 404, "code": "object_not_found", "message": "gone" }),
+            )
+        }
+    });
+    let err = client(&server).page_content(PAGE_ID).unwrap_err();
+    assert!(matches!(err, Error::ObjectNotFound { .. }), "{err:?}");
+}
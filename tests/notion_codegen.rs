@@ -0,0 +1,165 @@
+#![cfg(feature = "notion")]
+
+// The checked-in output for `fixtures/data_source.json`, compiled here so a
+// generator change that breaks the generated code fails the build.
+#[allow(dead_code)]
+#[path = "fixtures/data_source.rs"]
+mod task;
+
+use serde_json::{json, Value};
+use swivel::notion::{generate_rust, CodegenOptions, DataSource, Page, PropertyValue};
+use swivel::Error;
+use task::{Priority, Status, Tags, Task};
+
+fn schema() -> DataSource {
+    serde_json::from_str(include_str!("fixtures/data_source.json")).expect("schema fixture")
+}
+
+fn page() -> Page {
+    serde_json::from_str(include_str!("fixtures/page.json")).expect("page fixture")
+}
+
+fn options() -> CodegenOptions {
+    CodegenOptions::default().with_struct_name("Task")
+}
+
+#[test]
+fn output_matches_the_checked_in_module() {
+    let code = generate_rust(&schema(), &options());
+    assert_eq!(
+        code,
+        include_str!("fixtures/data_source.rs"),
+        "generator output changed; regenerate tests/fixtures/data_source.rs"
+    );
+    assert_eq!(generate_rust(&schema(), &options()), code);
+}
+
+#[test]
+fn generated_struct_reads_pages() {
+    let task = Task::from_page(&page()).unwrap();
+    assert_eq!(task.name, "Launch plan");
+    assert_eq!(task.estimate, Some(3.0));
+    assert_eq!(task.priority, Some(Priority::High));
+    assert_eq!(task.status, Some(Status::InProgress));
+    assert_eq!(
+        task.tags,
+        [Tags::Backend, Tags::Rust],
+        "options read as variants"
+    );
+    assert_eq!(task.blocked_by, ["3b1f0c2d-1111-4222-8333-944455556666"]);
+    assert_eq!(task.ticket.to_string(), "TASK-42");
+    assert!(!task.done);
+    assert_eq!(task.contact, None);
+    assert_eq!(Task::DATA_SOURCE_ID, schema().id);
+}
+
+#[test]
+fn generated_struct_writes_only_writable_properties() {
+    let mut task = Task::from_page(&page()).unwrap();
+    task.status = Some(Status::Done);
+    task.tags.push(Tags::Unknown("infra".into()));
+
+    let json = task.to_json();
+    assert_eq!(
+        json["Status"],
+        json!({ "type": "status", "status": { "name": "Done" } })
+    );
+    assert_eq!(
+        json["Tags"]["multi_select"],
+        json!([{ "name": "backend" }, { "name": "rust" }, { "name": "infra" }])
+    );
+    assert_eq!(json["Name"]["title"][0]["text"]["content"], "Launch plan");
+    for computed in ["Days left", "Total", "Ticket", "Created", "Editor", "Place"] {
+        assert!(json.get(computed).is_none(), "{computed}");
+    }
+    // Verification is set in the app, and Notion-hosted files cannot be sent
+    // back; attachments that are all external links can.
+    assert!(json.get("Verified").is_none());
+    assert!(
+        json.get("Attachments").is_none(),
+        "the fixture's file is hosted"
+    );
+    let mut linked = task.clone();
+    linked.attachments = vec![serde_json::from_value(json!({
+        "name": "spec.pdf",
+        "type": "external",
+        "external": { "url": "https://example.com/spec.pdf" }
+    }))
+    .unwrap()];
+    assert_eq!(
+        linked.to_json()["Attachments"]["files"][0]["external"]["url"],
+        "https://example.com/spec.pdf"
+    );
+
+    // What is written reads back the same.
+    let mut properties = page()
+        .properties
+        .into_iter()
+        .map(|(name, p)| (name, serde_json::to_value(p).unwrap()))
+        .collect::<serde_json::Map<String, Value>>();
+    properties.extend(json.as_object().unwrap().clone());
+    assert_eq!(Task::from_json(&Value::Object(properties)).unwrap(), task);
+}
+
+#[test]
+fn generated_struct_reports_schema_drift() {
+    let mut page = page();
+    page.properties.remove("Priority");
+    match Task::from_page(&page) {
+        Err(Error::InvalidInput(message)) => assert!(message.contains("`Priority`")),
+        other => panic!("{other:?}"),
+    }
+
+    let mut page = self::page();
+    page.properties.get_mut("Done").unwrap().value = PropertyValue::Number(None);
+    match Task::from_page(&page) {
+        Err(Error::InvalidInput(message)) => {
+            assert!(message.contains("`Done` is a number property"))
+        }
+        other => panic!("{other:?}"),
+    }
+}
+
+#[test]
+fn names_become_valid_identifiers() {
+    let schema: DataSource = serde_json::from_value(json!({
+        "id": "d1",
+        "title": [{ "type": "text", "text": { "content": "page" }, "plain_text": "page" }],
+        "properties": {
+            "type": { "id": "a", "name": "type", "type": "title", "title": {} },
+            "1st pick": { "id": "b", "name": "1st pick", "type": "checkbox", "checkbox": {} },
+            "Due date": { "id": "c", "name": "Due date", "type": "date", "date": {} },
+            "due-date": { "id": "d", "name": "due-date", "type": "date", "date": {} },
+            "self": { "id": "e", "name": "self", "type": "url", "url": {} },
+            "Page": {
+                "id": "f",
+                "name": "Page",
+                "type": "select",
+                "select": { "options": [
+                    { "name": "Unknown" },
+                    { "name": "🚀" },
+                    { "name": "P0" },
+                    { "name": "p0" }
+                ] }
+            }
+        }
+    }))
+    .unwrap();
+    let code = generate_rust(&schema, &CodegenOptions::default());
+    for expected in [
+        "pub struct PagePage {",
+        "    pub r#type: String,",
+        "    pub property_1st_pick: bool,",
+        "    pub due_date: Option<DateValue>,",
+        "    pub due_date2: Option<DateValue>,",
+        "    pub self_: Option<String>,",
+        "    pub page: Option<PageOption>,",
+        "pub enum PageOption {",
+        "            \"Unknown\" => Self::Unknown2,",
+        "            \"🚀\" => Self::Option2,",
+        "            \"P0\" => Self::P0,",
+        "            \"p0\" => Self::P02,",
+    ] {
+        assert!(code.contains(expected), "missing {expected:?} in\n{code}");
+    }
+}
@@ -0,0 +1,222 @@
+#![cfg(feature = "notion")]
+
+mod common;
+
+use common::{page_json as page, MockServer, Recorded, Reply};
+use serde_json::{json, Value};
+use swivel::notion::{
+    NewPage, NotionClient, Parent, SearchObject, SearchResult, LEGACY_NOTION_VERSION,
+    NOTION_VERSION,
+};
+use swivel::Error;
+
+const DATABASE_ID: &str = "0d2c4d47-5f3b-4a8e-9c1d-2b6e8f7a9c10";
+const DATA_SOURCE_ID: &str = "b5ad9a34-4e2f-4f8e-9c3c-1b0e6d4f7a21";
+
+fn not_found() -> Reply {
+    Reply::json(
+        404,
+        json!({
+            "object": "error",
+            "status": 404,
+            "code": "object_not_found",
+            "message": "Could not find data source."
+        }),
+    )
+}
+
+fn schema() -> Value {
+    serde_json::from_str(include_str!("fixtures/data_source.json")).unwrap()
+}
+
+fn database(data_sources: Value) -> Value {
+    json!({
+        "object": "database",
+        "id": DATABASE_ID,
+        "title": [{ "type": "text", "text": { "content": "Launch" }, "plain_text": "Launch" }],
+        "data_sources": data_sources
+    })
+}
+
+fn results(pages: Vec<Value>) -> Value {
+    json!({ "object": "list", "results": pages, "next_cursor": null, "has_more": false })
+}
+
+/// A workspace on 2025-09-03 or later: one database with one data source.
+fn current(req: &Recorded) -> Reply {
+    let path = req
+        .path
+        .replace(DATABASE_ID, "db")
+        .replace(DATA_SOURCE_ID, "ds");
+    match path.as_str() {
+        "/databases/db" => Reply::json(
+            200,
+            database(json!([{ "id": DATA_SOURCE_ID, "name": "Tasks" }])),
+        ),
+        "/data_sources/ds" => Reply::json(200, schema()),
+        "/data_sources/ds/query" => Reply::json(200, results(vec![page("p1")])),
+        _ => not_found(),
+    }
+}
+
+fn client(server: &MockServer) -> NotionClient {
+    NotionClient::new("secret")
+        .with_base_url(server.url())
+        .with_rate_limiter(None)
+}
+
+fn paths(server: &MockServer) -> Vec<String> {
+    server
+        .requests()
+        .iter()
+        .map(|r| {
+            r.path
+                .replace(DATABASE_ID, "db")
+                .replace(DATA_SOURCE_ID, "ds")
+        })
+        .collect()
+}
+
+#[test]
+fn database_ids_stand_for_their_data_source() {
+    let server = MockServer::start(current);
+    let notion = client(&server);
+
+    let pages: Vec<_> = notion
+        .query_data_source(DATABASE_ID)
+        .into_iter()
+        .map(|p| p.unwrap().id)
+        .collect();
+    assert_eq!(pages, ["p1"]);
+    let schema = notion.get_data_source(DATABASE_ID).unwrap();
+    assert_eq!(schema.id, DATA_SOURCE_ID);
+
+    // The database is looked up once; after that its data source is used
+    // directly, by clones too.
+    notion
+        .clone()
+        .query_data_source(DATABASE_ID)
+        .pages()
+        .count();
+    assert_eq!(
+        paths(&server),
+        [
+            "/data_sources/db/query",
+            "/databases/db",
+            "/data_sources/ds/query",
+            "/data_sources/ds",
+            "/data_sources/ds/query",
+        ]
+    );
+    assert!(server
+        .requests()
+        .iter()
+        .all(|r| r.header("notion-version") == Some(NOTION_VERSION)));
+}
+
+#[test]
+fn data_source_ids_need_no_lookup() {
+    let server = MockServer::start(current);
+    let notion = client(&server);
+    notion.get_data_source(DATA_SOURCE_ID).unwrap();
+    assert_eq!(paths(&server), ["/data_sources/ds"]);
+
+    // An id that is neither keeps the original error.
+    let err = notion
+        .get_data_source("9f0e1d2c-3b4a-4596-8877-665544332211")
+        .unwrap_err();
+    assert!(matches!(err, Error::ObjectNotFound { .. }), "{err}");
+}
+
+#[test]
+fn databases_list_their_data_sources() {
+    let server = MockServer::start(|req| {
+        if req.path.starts_with("/databases/") {
+            return Reply::json(
+                200,
+                database(json!([
+                    { "id": DATA_SOURCE_ID, "name": "Tasks" },
+                    { "id": "c6f1a0b2-7d3e-4f59-a8b1-0e2d3c4b5a69", "name": "Bugs" }
+                ])),
+            );
+        }
+        not_found()
+    });
+    let notion = client(&server);
+    let database = notion.get_database(DATABASE_ID).unwrap();
+    assert_eq!(database.title(), "Launch");
+    let names: Vec<_> = notion
+        .data_sources(DATABASE_ID)
+        .unwrap()
+        .into_iter()
+        .map(|ds| ds.name)
+        .collect();
+    assert_eq!(names, ["Tasks", "Bugs"]);
+
+    // With two data sources, a database id does not say which one.
+    match notion.get_data_source(DATABASE_ID) {
+        Err(Error::InvalidInput(message)) => {
+            assert!(message.contains("has 2 data sources"), "{message}");
+            assert!(message.contains(&format!("{DATA_SOURCE_ID} (Tasks)")));
+        }
+        other => panic!("{other:?}"),
+    }
+}
+
+#[test]
+fn legacy_versions_use_database_endpoints() {
+    let server = MockServer::start(|req| {
+        let mut legacy = schema();
+        legacy["object"] = json!("database");
+        legacy["id"] = json!(DATABASE_ID);
+        match req.path.replace(DATABASE_ID, "db").as_str() {
+            "/databases/db" => Reply::json(200, legacy),
+            "/databases/db/query" => Reply::json(200, results(vec![page("p1")])),
+            "/pages" => Reply::json(200, page("p2")),
+            "/search" => Reply::json(200, results(vec![legacy])),
+            _ => not_found(),
+        }
+    });
+    let notion = client(&server).with_notion_version(LEGACY_NOTION_VERSION);
+
+    assert_eq!(notion.query_data_source(DATABASE_ID).pages().count(), 1);
+    let schema = notion.get_data_source(DATABASE_ID).unwrap();
+    assert_eq!(schema.title(), "Launch tasks");
+    let sources = notion.data_sources(DATABASE_ID).unwrap();
+    assert_eq!(sources.len(), 1);
+    assert_eq!(sources[0].id, DATABASE_ID);
+
+    let parent = Parent::DataSource {
+        data_source_id: DATABASE_ID.into(),
+        database_id: None,
+    };
+    notion.create_page(&NewPage::new(parent)).unwrap();
+
+    let hits: Vec<_> = notion
+        .search("")
+        .object(SearchObject::DataSource)
+        .into_iter()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert!(matches!(&hits[..], [SearchResult::DataSource(ds)] if ds.id == DATABASE_ID));
+
+    let requests = server.requests();
+    assert!(requests
+        .iter()
+        .all(|r| r.header("notion-version") == Some(LEGACY_NOTION_VERSION)));
+    assert_eq!(
+        paths(&server),
+        [
+            "/databases/db/query",
+            "/databases/db",
+            "/databases/db",
+            "/pages",
+            "/search"
+        ]
+    );
+    assert_eq!(
+        requests[3].json()["parent"],
+        json!({ "type": "database_id", "database_id": DATABASE_ID })
+    );
+    assert_eq!(requests[4].json()["filter"]["value"], "database");
+}
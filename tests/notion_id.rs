@@ -0,0 +1,95 @@
+#![cfg(feature = "notion")]
+
+mod common;
+
+use common::{MockServer, Reply};
+use swivel::notion::{NotionClient, NotionId};
+use swivel::Error;
+
+const DASHED: &str = "275a1865-b187-807a-adea-ebaf36fb49b0";
+
+fn parse(input: &str) -> String {
+    NotionId::parse(input)
+        .unwrap_or_else(|err| panic!("{input}: {err}"))
+        .to_string()
+}
+
+#[test]
+fn normalizes_uuids() {
+    assert_eq!(parse(DASHED), DASHED);
+    assert_eq!(parse("275a1865b187807aadeaebaf36fb49b0"), DASHED);
+    assert_eq!(parse("275A1865-B187-807A-ADEA-EBAF36FB49B0"), DASHED);
+    assert_eq!(parse("  275a1865b187807aadeaebaf36fb49b0\n"), DASHED);
+    assert_eq!(
+        NotionId::parse(DASHED).unwrap().simple(),
+        "275a1865b187807aadeaebaf36fb49b0"
+    );
+}
+
+#[test]
+fn extracts_ids_from_urls() {
+    for url in [
+        "https://www.notion.so/acme/Launch-plan-275a1865b187807aadeaebaf36fb49b0",
+        "https://www.notion.so/275a1865b187807aadeaebaf36fb49b0",
+        "notion.so/acme/Launch-plan-275a1865b187807aadeaebaf36fb49b0#block",
+        "https://acme.notion.site/Launch-plan-275a1865b187807aadeaebaf36fb49b0",
+        "https://www.notion.so/acme/Launch-plan-275a1865-b187-807a-adea-ebaf36fb49b0/",
+        // A database view: the path names the database.
+        "https://www.notion.so/acme/275a1865b187807aadeaebaf36fb49b0?v=0d2c4d475f3b4a8e9c1d2b6e8f7a9c10",
+        // A page open in a side peek wins over the database behind it.
+        "https://www.notion.so/acme/0d2c4d475f3b4a8e9c1d2b6e8f7a9c10?v=1&p=275a1865b187807aadeaebaf36fb49b0&pm=s",
+        "collection://275a1865-b187-807a-adea-ebaf36fb49b0",
+    ] {
+        assert_eq!(parse(url), DASHED, "{url}");
+    }
+}
+
+#[test]
+fn rejects_anything_else() {
+    for input in [
+        "",
+        "abc",
+        "275a1865b187807aadeaebaf36fb49b",
+        "275a1865b187807aadeaebaf36fb49bz",
+        "275a1865-b187807a-adea-ebaf36fb49b0a",
+        "https://example.com/275a1865b187807aadeaebaf36fb49b0",
+        "https://www.notion.so/acme/Launch-plan",
+        "https://www.notion.so/acme/Launch-plan-ßßßßßßßßßßßßßßßßßß",
+    ] {
+        match NotionId::parse(input) {
+            Err(Error::InvalidInput(message)) => assert!(message.contains("not a Notion id")),
+            other => panic!("{input}: {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn serializes_as_the_dashed_form() {
+    let id: NotionId = serde_json::from_str("\"275a1865b187807aadeaebaf36fb49b0\"").unwrap();
+    assert_eq!(serde_json::to_string(&id).unwrap(), format!("\"{DASHED}\""));
+    assert!(serde_json::from_str::<NotionId>("\"abc\"").is_err());
+}
+
+#[test]
+fn client_methods_accept_urls_and_reject_bad_ids_locally() {
+    let server = MockServer::start(|req| Reply::json(200, common::page_json(&req.path[7..])));
+    let notion = NotionClient::new("secret")
+        .with_base_url(server.url())
+        .with_rate_limiter(None);
+
+    let page = notion
+        .get_page("https://www.notion.so/acme/Launch-plan-275a1865b187807aadeaebaf36fb49b0")
+        .unwrap();
+    assert_eq!(page.id, DASHED);
+    assert_eq!(server.requests()[0].path, format!("/pages/{DASHED}"));
+
+    assert!(matches!(
+        notion.get_page("Launch plan"),
+        Err(Error::InvalidInput(_))
+    ));
+    assert!(matches!(
+        notion.archive_page("abc"),
+        Err(Error::InvalidInput(_))
+    ));
+    assert_eq!(server.hits(), 1);
+}
@@ -0,0 +1,373 @@
+#![cfg(feature = "notion")]
+
+mod common;
+
+use common::{page_json, MockServer, Recorded, Reply};
+use serde_json::{json, Value};
+use swivel::notion::{parse_markdown, BlockNode, NotionClient, Parent};
+
+const DATA_SOURCE_ID: &str = "b5ad9a34-4e2f-4f8e-9c3c-1b0e6d4f7a21";
+
+fn kinds(blocks: &[BlockNode]) -> Vec<&str> {
+    blocks.iter().map(|n| n.block.content.kind()).collect()
+}
+
+fn runs(node: &BlockNode) -> Value {
+    json!(node.block.content.rich_text().unwrap())
+}
+
+/// Creates pages as `new-page` and answers appends with one block per
+/// child, with ids `<parent>.<index>`.
+fn server() -> MockServer {
+    MockServer::start(|req| match (req.method.as_str(), req.path.as_str()) {
+        ("GET", path) if path == format!("/data_sources/{DATA_SOURCE_ID}") => Reply::json(
+            200,
+            json!({
+                "object": "data_source",
+                "id": DATA_SOURCE_ID,
+                "title": [],
+                "properties": {
+                    "Name": { "id": "title", "name": "Name", "type": "title", "title": {} },
+                    "Tags": { "id": "t", "name": "Tags", "type": "multi_select", "multi_select": {} },
+                    "Budget": { "id": "b", "name": "Budget", "type": "number", "number": {} },
+                    "Due": { "id": "d", "name": "Due", "type": "date", "date": {} },
+                    "Created": { "id": "c", "name": "Created", "type": "created_time", "created_time": {} }
+                }
+            }),
+        ),
+        ("POST", "/pages") => Reply::json(200, page_json("new-page")),
+        ("PATCH", path) => {
+            let parent = path
+                .strip_prefix("/blocks/")
+                .and_then(|p| p.strip_suffix("/children"))
+                .unwrap();
+            let results: Vec<Value> = req.json()["children"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .enumerate()
+                .map(|(i, child)| {
+                    let mut child = child.clone();
+                    child["id"] = json!(format!("{parent}.{i}"));
+                    child
+                })
+                .collect();
+            Reply::json(
+                200,
+                json!({ "object": "list", "results": results, "next_cursor": null, "has_more": false }),
+            )
+        }
+        other => panic!("unexpected request {other:?}"),
+    })
+}
+
+fn client(server: &MockServer) -> NotionClient {
+    NotionClient::new("secret")
+        .with_base_url(server.url())
+        .with_rate_limiter(None)
+}
+
+fn appends(server: &MockServer) -> Vec<Recorded> {
+    server
+        .requests()
+        .into_iter()
+        .filter(|r| r.method == "PATCH")
+        .collect()
+}
+
+#[test]
+fn parses_inline_styles_and_links() {
+    let doc = parse_markdown(
+        "Ship **it _now_** ~~later~~ <u>really</u> `cargo` [docs](https://docs.rs) $x^2$\n",
+    );
+    let styled = |content: &str, annotations: Value, link: Option<&str>| {
+        let mut run = json!({
+            "type": "text",
+            "text": { "content": content, "link": link.map(|url| json!({ "url": url })) },
+            "annotations": {
+                "bold": false, "italic": false, "strikethrough": false,
+                "underline": false, "code": false, "color": "default"
+            },
+            "plain_text": content,
+            "href": link
+        });
+        for (k, v) in annotations.as_object().unwrap() {
+            run["annotations"][k] = v.clone();
+        }
+        run
+    };
+    let plain = |content: &str| styled(content, json!({}), None);
+    assert_eq!(kinds(&doc.blocks), ["paragraph"]);
+    assert_eq!(
+        runs(&doc.blocks[0]),
+        json!([
+            plain("Ship "),
+            styled("it ", json!({ "bold": true }), None),
+            styled("now", json!({ "bold": true, "italic": true }), None),
+            plain(" "),
+            styled("later", json!({ "strikethrough": true }), None),
+            plain(" "),
+            styled("really", json!({ "underline": true }), None),
+            plain(" "),
+            styled("cargo", json!({ "code": true }), None),
+            plain(" "),
+            styled("docs", json!({}), Some("https://docs.rs")),
+            plain(" "),
+            {
+                "type": "equation",
+                "equation": { "expression": "x^2" },
+                "annotations": {
+                    "bold": false, "italic": false, "strikethrough": false,
+                    "underline": false, "code": false, "color": "default"
+                },
+                "plain_text": "x^2",
+                "href": null
+            }
+        ])
+    );
+}
+
+#[test]
+fn keeps_relative_and_anchor_links_as_text() {
+    let doc = parse_markdown(
+        "See [the guide](./guide.md), [setup](#setup), [mail](mailto:ann@example.com) \
+         and [site](http://example.com).\n",
+    );
+    let runs = doc.blocks[0].block.content.rich_text().unwrap();
+    let links: Vec<_> = runs
+        .iter()
+        .map(|run| (run.plain_text.as_str(), run.href.as_deref()))
+        .collect();
+    assert_eq!(
+        links,
+        [
+            ("See the guide, setup, ", None),
+            ("mail", Some("mailto:ann@example.com")),
+            (" and ", None),
+            ("site", Some("http://example.com")),
+            (".", None),
+        ]
+    );
+    assert_eq!(json!(runs)[0]["text"]["link"], Value::Null);
+}
+
+#[test]
+fn nests_lists_and_tasks() {
+    let doc =
+        parse_markdown("- one\n  - one.a\n    1. deep\n- [x] done\n\n> quoted\n>\n> - inside\n");
+    assert_eq!(kinds(&doc.blocks), ["bulleted_list_item", "to_do", "quote"]);
+    let one = &doc.blocks[0];
+    assert_eq!(one.block.plain_text(), "one");
+    assert_eq!(one.children[0].block.plain_text(), "one.a");
+    assert_eq!(kinds(&one.children[0].children), ["numbered_list_item"]);
+    assert_eq!(json!(doc.blocks[1].block.content)["to_do"]["checked"], true);
+    let quote = &doc.blocks[2];
+    assert_eq!(quote.block.plain_text(), "quoted");
+    assert_eq!(kinds(&quote.children), ["bulleted_list_item"]);
+}
+
+#[test]
+fn reads_exported_markdown_back() {
+    let source = include_str!("fixtures/page.md").replace("{server}", "https://files.example.com");
+    let doc = parse_markdown(&source);
+    assert_eq!(doc.title.as_deref(), Some("Launch plan"));
+    assert_eq!(doc.properties["Budget"], json!(12.5));
+    assert_eq!(doc.properties["Tags"], json!(["backend", "rust"]));
+    assert!(!doc.properties.contains_key("id"));
+    assert_eq!(
+        kinds(&doc.blocks),
+        [
+            "heading_1",
+            "paragraph",
+            "bulleted_list_item",
+            "bulleted_list_item",
+            "numbered_list_item",
+            "numbered_list_item",
+            "to_do",
+            "to_do",
+            "toggle",
+            "code",
+            "quote",
+            "quote",
+            "divider",
+            "equation",
+            "table",
+            "image",
+            "paragraph",
+            "paragraph",
+        ]
+    );
+    assert_eq!(doc.blocks[8].children[0].block.plain_text(), "Hidden");
+    assert_eq!(
+        json!(doc.blocks[9].block.content)["code"]["language"],
+        "rust"
+    );
+    assert_eq!(
+        json!(doc.blocks[13].block.content)["equation"]["expression"],
+        "a^2+b^2=c^2"
+    );
+    let rows = &doc.blocks[14].children;
+    assert_eq!(rows.len(), 2);
+    assert_eq!(
+        json!(rows[1].block.content)["table_row"]["cells"][0][0]["plain_text"],
+        "a|b"
+    );
+}
+
+#[test]
+fn maps_front_matter_onto_the_schema() {
+    let server = server();
+    let doc = parse_markdown(
+        "---\ntitle: Launch\nTags: [backend, rust]\nproperties:\n  Budget: 12.5\n  Due: \"2025-10-01\"\n  Created: \"2025-09-20T18:03:00.000Z\"\n---\n\nHello\n",
+    );
+    let parent = Parent::DataSource {
+        data_source_id: DATA_SOURCE_ID.into(),
+        database_id: None,
+    };
+    let page = client(&server).import_markdown(parent, &doc).unwrap();
+    assert_eq!(page.id, "new-page");
+
+    let create = server
+        .requests()
+        .into_iter()
+        .find(|r| r.method == "POST")
+        .unwrap()
+        .json();
+    assert_eq!(create["parent"]["data_source_id"], DATA_SOURCE_ID);
+    let props = &create["properties"];
+    assert_eq!(props["Name"]["title"][0]["text"]["content"], "Launch");
+    assert_eq!(
+        props["Tags"]["multi_select"],
+        json!([{ "name": "backend" }, { "name": "rust" }])
+    );
+    assert_eq!(props["Budget"]["number"], 12.5);
+    assert_eq!(props["Due"]["date"]["start"], "2025-10-01");
+    // Computed properties are not sent.
+    assert!(props.get("Created").is_none());
+
+    let appended = appends(&server);
+    assert_eq!(appended.len(), 1);
+    assert_eq!(appended[0].path, "/blocks/new-page/children");
+
+    let unknown = parse_markdown("---\nOwner: Ada\n---\n");
+    let parent = Parent::DataSource {
+        data_source_id: DATA_SOURCE_ID.into(),
+        database_id: None,
+    };
+    let err = client(&server)
+        .import_markdown(parent, &unknown)
+        .unwrap_err();
+    assert!(matches!(err, swivel::Error::InvalidInput(_)), "{err:?}");
+}
+
+#[test]
+fn imports_under_a_data_source_already_fetched() {
+    let server = server();
+    let notion = client(&server);
+    let data_source = notion.get_data_source(DATA_SOURCE_ID).unwrap();
+    let doc = parse_markdown("---\ntitle: Launch\n---\n\nHello\n");
+    notion.import_markdown_into(&data_source, &doc).unwrap();
+
+    let methods: Vec<_> = server.requests().into_iter().map(|r| r.method).collect();
+    assert_eq!(
+        methods,
+        ["GET", "POST", "PATCH"],
+        "the schema is fetched once"
+    );
+}
+
+#[test]
+fn splits_long_text_runs() {
+    let doc = parse_markdown(&format!("{}**tail**\n", "a".repeat(4500)));
+    let runs = runs(&doc.blocks[0]);
+    let lengths: Vec<usize> = runs
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["text"]["content"].as_str().unwrap().len())
+        .collect();
+    assert_eq!(lengths, [2000, 2000, 500, 4]);
+}
+
+#[test]
+fn splits_long_code_blocks_summaries_and_captions() {
+    let long = "x".repeat(4500);
+    let doc = parse_markdown(&format!(
+        "```rust\n{long}\n```\n\n<details><summary>{long}</summary>\n\nbody\n\n</details>\n\n\
+         ![{long}](https://example.com/a.png)\n\n![{long}](local.png)\n"
+    ));
+    assert_eq!(kinds(&doc.blocks), ["code", "toggle", "image", "paragraph"]);
+    let lengths = |runs: &Value| -> Vec<usize> {
+        runs.as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["text"]["content"].as_str().unwrap().chars().count())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(lengths(&runs(&doc.blocks[0])), [2000, 2000, 500]);
+    assert_eq!(lengths(&runs(&doc.blocks[1])), [2000, 2000, 500]);
+    let image = json!(doc.blocks[2].block.content);
+    assert_eq!(lengths(&image["image"]["caption"]), [2000, 2000, 500]);
+    assert_eq!(lengths(&runs(&doc.blocks[3])), [2000, 2000, 512]);
+}
+
+#[test]
+fn appends_long_pages_in_batches() {
+    let server = server();
+    let source: String = (0..250).map(|i| format!("Paragraph {i}\n\n")).collect();
+    let doc = parse_markdown(&source);
+    let parent = Parent::Page {
+        page_id: "root".into(),
+    };
+    client(&server).import_markdown(parent, &doc).unwrap();
+
+    let sizes: Vec<usize> = appends(&server)
+        .iter()
+        .map(|r| r.json()["children"].as_array().unwrap().len())
+        .collect();
+    assert_eq!(sizes, [100, 100, 50]);
+    let last = &appends(&server)[2].json()["children"][49];
+    assert_eq!(
+        last["paragraph"]["rich_text"][0]["text"]["content"],
+        "Paragraph 249"
+    );
+}
+
+#[test]
+fn appends_deep_nesting_under_created_blocks() {
+    let server = server();
+    let doc = parse_markdown("- a\n  - b\n    - c\n      - d\n        - e\n- f\n");
+    let parent = Parent::Page {
+        page_id: "root".into(),
+    };
+    client(&server).import_markdown(parent, &doc).unwrap();
+
+    let appended = appends(&server);
+    let paths: Vec<&str> = appended.iter().map(|r| r.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        [
+            "/blocks/new-page/children",
+            "/blocks/new-page.0/children",
+            "/blocks/new-page.0.0/children"
+        ]
+    );
+    // Only top-level blocks come back with ids, so a block goes in a request
+    // only with its whole subtree; deeper content follows under its parent.
+    let first = appended[0].json();
+    assert_eq!(first["children"].as_array().unwrap().len(), 2);
+    assert!(first["children"][0]["bulleted_list_item"]
+        .get("children")
+        .is_none());
+    let second = appended[1].json();
+    assert_eq!(second["children"].as_array().unwrap().len(), 1);
+    assert!(second["children"][0]["bulleted_list_item"]
+        .get("children")
+        .is_none());
+    let c = &appended[2].json()["children"][0]["bulleted_list_item"];
+    assert_eq!(c["rich_text"][0]["text"]["content"], "c");
+    let d = &c["children"][0]["bulleted_list_item"];
+    assert_eq!(d["rich_text"][0]["text"]["content"], "d");
+    let e = &d["children"][0]["bulleted_list_item"];
+    assert_eq!(e["rich_text"][0]["text"]["content"], "e");
+}
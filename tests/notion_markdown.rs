@@ -0,0 +1,224 @@
+#![cfg(feature = "notion")]
+
+mod common;
+
+use std::collections::HashMap;
+use std::fs;
+
+use common::{MockServer, Reply};
+use serde_json::{json, Value};
+use swivel::notion::{rich_text, MarkdownOptions, NotionClient, RichText};
+
+fn text(content: &str) -> Value {
+    styled(content, json!({}))
+}
+
+fn styled(content: &str, annotations: Value) -> Value {
+    json!({
+        "type": "text",
+        "text": { "content": content, "link": null },
+        "annotations": annotations,
+        "plain_text": content,
+        "href": null
+    })
+}
+
+fn block(id: &str, kind: &str, payload: Value) -> Value {
+    json!({ "object": "block", "id": id, "has_children": false, "type": kind, kind: payload })
+}
+
+fn rich(id: &str, kind: &str, runs: Vec<Value>) -> Value {
+    block(id, kind, json!({ "rich_text": runs, "color": "default" }))
+}
+
+/// Children of each block id; blocks listed here get `has_children: true`.
+fn page_blocks(server_url: &str) -> HashMap<&'static str, Vec<Value>> {
+    let mut children = HashMap::new();
+    children.insert(
+        "page",
+        vec![
+            rich("h1", "heading_1", vec![text("Overview")]),
+            rich(
+                "p1",
+                "paragraph",
+                vec![
+                    text("Ship it "),
+                    styled("today", json!({ "bold": true })),
+                    text(", see "),
+                    json!({
+                        "type": "mention",
+                        "mention": { "type": "page", "page": { "id": "abc" } },
+                        "plain_text": "Roadmap",
+                        "href": "https://www.notion.so/abc"
+                    }),
+                    text(" and use "),
+                    styled("cargo *", json!({ "code": true })),
+                    text("."),
+                ],
+            ),
+            rich("b1", "bulleted_list_item", vec![text("First")]),
+            rich("b2", "bulleted_list_item", vec![text("Second")]),
+            rich("n1", "numbered_list_item", vec![text("One")]),
+            rich("n2", "numbered_list_item", vec![text("Two")]),
+            block(
+                "td1",
+                "to_do",
+                json!({ "rich_text": [text("Write docs")], "checked": true }),
+            ),
+            block(
+                "td2",
+                "to_do",
+                json!({ "rich_text": [text("Review")], "checked": false }),
+            ),
+            rich("tg", "toggle", vec![text("More")]),
+            block(
+                "code",
+                "code",
+                json!({ "rich_text": [text("fn main() {}")], "language": "rust", "caption": [] }),
+            ),
+            rich("q", "quote", vec![text("Simplicity is prerequisite.")]),
+            block(
+                "co",
+                "callout",
+                json!({ "rich_text": [text("Heads up")], "icon": { "type": "emoji", "emoji": "💡" } }),
+            ),
+            block("dv", "divider", json!({})),
+            block("eq", "equation", json!({ "expression": "a^2+b^2=c^2" })),
+            block(
+                "tb",
+                "table",
+                json!({ "table_width": 2, "has_column_header": true, "has_row_header": false }),
+            ),
+            block(
+                "img-1",
+                "image",
+                json!({
+                    "caption": [text("Diagram")],
+                    "type": "file",
+                    "file": { "url": format!("{server_url}/files/diagram.png?sig=1"), "expiry_time": null }
+                }),
+            ),
+            block(
+                "f1",
+                "pdf",
+                json!({
+                    "caption": [],
+                    "type": "external",
+                    "external": { "url": "https://example.com/spec.pdf" }
+                }),
+            ),
+            block("cp", "child_page", json!({ "title": "Appendix" })),
+            block("x", "breadcrumb", json!({})),
+        ],
+    );
+    children.insert(
+        "b1",
+        vec![rich("b1a", "bulleted_list_item", vec![text("Nested")])],
+    );
+    children.insert("tg", vec![rich("tg1", "paragraph", vec![text("Hidden")])]);
+    children.insert(
+        "tb",
+        vec![
+            block(
+                "r1",
+                "table_row",
+                json!({ "cells": [[text("Name")], [text("Score")]] }),
+            ),
+            block(
+                "r2",
+                "table_row",
+                json!({ "cells": [[text("a|b")], [text("1")]] }),
+            ),
+        ],
+    );
+    children
+}
+
+fn server() -> MockServer {
+    let page: Value =
+        serde_json::from_str(include_str!("fixtures/page.json")).expect("page fixture");
+    MockServer::start(move |req| {
+        let path = req.path.split('?').next().unwrap();
+        if path == "/files/diagram.png" {
+            return Reply {
+                status: 200,
+                headers: vec![],
+                body: "PNG".into(),
+            };
+        }
+        if path.starts_with("/pages/") {
+            return Reply::json(200, page.clone());
+        }
+        let host = req.header("host").unwrap();
+        let children = page_blocks(&format!("http://{host}"));
+        let id = path
+            .strip_prefix("/blocks/")
+            .and_then(|p| p.strip_suffix("/children"))
+            .map(|id| if id == page["id"] { "page" } else { id })
+            .expect("a children request");
+        let results: Vec<Value> = children[id]
+            .iter()
+            .map(|b| {
+                let mut b = b.clone();
+                b["has_children"] = json!(children.contains_key(b["id"].as_str().unwrap()));
+                b
+            })
+            .collect();
+        Reply::json(
+            200,
+            json!({ "object": "list", "results": results, "next_cursor": null, "has_more": false }),
+        )
+    })
+}
+
+fn client(server: &MockServer) -> NotionClient {
+    NotionClient::new("secret")
+        .with_base_url(server.url())
+        .with_rate_limiter(None)
+}
+
+const PAGE_ID: &str = "275a1865-b187-807a-adea-ebaf36fb49b0";
+
+#[test]
+fn exports_a_page_as_gfm() {
+    let server = server();
+    let markdown = client(&server)
+        .export_markdown(PAGE_ID, &MarkdownOptions::default())
+        .unwrap();
+    let expected = include_str!("fixtures/page.md").replace("{server}", server.url());
+    assert_eq!(markdown, expected);
+}
+
+#[test]
+fn downloads_hosted_files_when_asked() {
+    let server = server();
+    let dir = std::env::temp_dir().join(format!("swivel-export-{}", std::process::id()));
+    let options = MarkdownOptions::default()
+        .with_front_matter(false)
+        .with_assets_dir(&dir);
+    let markdown = client(&server).export_markdown(PAGE_ID, &options).unwrap();
+
+    let local = dir.join("img1.png");
+    assert_eq!(fs::read_to_string(&local).unwrap(), "PNG");
+    let link = format!("![Diagram]({})", local.to_string_lossy().replace('\\', "/"));
+    assert!(markdown.contains(&link), "{markdown}");
+    assert!(markdown.starts_with("# Launch [**plan**](https://example.com)\n"));
+    // External files are left as links.
+    assert!(markdown.contains("(https://example.com/spec.pdf)"));
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn merges_adjacent_runs_and_escapes_syntax() {
+    let runs: Vec<RichText> = serde_json::from_value(json!([
+        styled("bold ", json!({ "bold": true })),
+        styled("text", json!({ "bold": true })),
+        text(" with *stars* and_underscores"),
+        styled("struck", json!({ "strikethrough": true, "italic": true })),
+    ]))
+    .unwrap();
+    assert_eq!(
+        rich_text::to_markdown(&runs),
+        "**bold text** with \\*stars\\* and\\_underscores*~~struck~~*"
+    );
+}
@@ -0,0 +1,123 @@
+#![cfg(feature = "notion")]
+
+use serde_json::{json, Value};
+use swivel::notion::{
+    FileSource, FormulaValue, Icon, Page, Parent, PropertyValue, RichText, RollupValue,
+};
+
+fn fixture() -> Value {
+    serde_json::from_str(include_str!("fixtures/page.json")).unwrap()
+}
+
+fn page() -> Page {
+    serde_json::from_value(fixture()).unwrap()
+}
+
+#[test]
+fn reads_page_metadata() {
+    let page = page();
+    assert_eq!(page.id, "275a1865-b187-807a-adea-ebaf36fb49b0");
+    assert_eq!(page.title(), "Launch plan");
+    assert!(!page.in_trash);
+    assert_eq!(page.icon, Some(Icon::Emoji("🚀".into())));
+    assert_eq!(
+        page.cover.as_ref().and_then(|c| c.url()),
+        Some("https://example.com/cover.png")
+    );
+    assert_eq!(
+        page.parent,
+        Parent::DataSource {
+            data_source_id: "b5ad9a34-4e2f-4f8e-9c3c-1b0e6d4f7a21".into(),
+            database_id: Some("1a7c5d28-6a2b-4d0c-8f1e-2e6a9b3c4d5e".into()),
+        }
+    );
+}
+
+#[test]
+fn typed_accessors() {
+    let page = page();
+    let prop = |name| page.prop(name).unwrap();
+
+    assert_eq!(prop("Status").as_status().unwrap().name, "In progress");
+    assert_eq!(prop("Priority").as_select().unwrap().name, "High");
+    assert_eq!(prop("Tags").as_multi_select().unwrap().len(), 2);
+    assert_eq!(prop("Estimate").as_number(), Some(3.0));
+    assert_eq!(prop("Budget").as_number(), Some(12.5));
+    assert_eq!(prop("Due").as_date().unwrap().start, "2025-10-01");
+    assert_eq!(
+        prop("Owner").as_people().unwrap()[0].email(),
+        Some("ada@example.com")
+    );
+    assert!(matches!(
+        prop("Attachments").as_files().unwrap()[0].source,
+        FileSource::File(_)
+    ));
+    assert_eq!(prop("Done").as_checkbox(), Some(false));
+    assert_eq!(prop("Link").as_url(), Some("https://example.com/launch"));
+    assert_eq!(prop("Contact").as_email(), None);
+    assert_eq!(prop("Phone").as_phone_number(), Some("+1 555 0100"));
+    assert!(matches!(
+        prop("Days left").as_formula(),
+        Some(FormulaValue::Number(Some(_)))
+    ));
+    assert_eq!(prop("Blocked by").as_relation().unwrap().len(), 1);
+    assert_eq!(
+        prop("Total").as_rollup().unwrap().function.as_deref(),
+        Some("sum")
+    );
+    assert!(matches!(
+        &prop("Related names").as_rollup().unwrap().value,
+        RollupValue::Array(items) if items[0].as_checkbox() == Some(true)
+    ));
+    assert_eq!(
+        prop("Ticket").as_unique_id().unwrap().to_string(),
+        "TASK-42"
+    );
+    assert_eq!(
+        prop("Verified").as_verification().unwrap().state,
+        "unverified"
+    );
+    assert_eq!(
+        prop("Created").as_timestamp(),
+        Some("2025-09-20T18:03:00.000Z")
+    );
+    assert!(prop("Editor").as_user().is_some());
+    assert_eq!(prop("Notes").plain_text().as_deref(), Some("e^{i\\pi}+1=0"));
+
+    // Accessors for the wrong type return None rather than panicking.
+    assert_eq!(prop("Status").as_number(), None);
+    assert!(page.prop("Missing").is_none());
+}
+
+#[test]
+fn keeps_unknown_property_types_raw() {
+    let page = page();
+    match page.prop("Place").unwrap() {
+        PropertyValue::Unknown { kind, value } => {
+            assert_eq!(kind, "place");
+            assert_eq!(value["name"], "Berlin");
+        }
+        other => panic!("expected unknown, got {other:?}"),
+    }
+}
+
+#[test]
+fn round_trips_losslessly() {
+    let back = serde_json::to_value(page()).unwrap();
+    assert_eq!(back, fixture());
+}
+
+#[test]
+fn rich_text_annotations_and_links() {
+    let page = page();
+    let title: &[RichText] = page.prop("Name").unwrap().as_title().unwrap();
+    assert!(title[1].annotations.bold);
+    assert_eq!(title[1].annotations.color, "red");
+    assert_eq!(title[1].href.as_deref(), Some("https://example.com"));
+}
+
+#[test]
+fn rejects_objects_without_a_type_tag() {
+    let err = serde_json::from_value::<PropertyValue>(json!({ "number": 1 })).unwrap_err();
+    assert!(err.to_string().contains("type"));
+}
@@ -0,0 +1,170 @@
+#![cfg(feature = "notion")]
+
+mod common;
+
+use common::{page_json, MockServer, Recorded, Reply};
+use serde_json::{json, Value};
+use swivel::notion::{
+    NotionClient, Page, PageOptions, PropertyValue, RollupValue, MAX_INLINE_ITEMS,
+};
+
+const PAGE_ID: &str = "275a1865-b187-807a-adea-ebaf36fb49b0";
+
+fn client(server: &MockServer) -> NotionClient {
+    NotionClient::new("secret")
+        .with_base_url(server.url())
+        .with_rate_limiter(None)
+}
+
+fn related(n: usize) -> Vec<Value> {
+    (0..n)
+        .map(|i| json!({ "id": format!("rel-{i}") }))
+        .collect()
+}
+
+/// A page whose relation Notion cut off at 25 pages, with a rollup over it.
+fn truncated_page() -> Value {
+    let mut page = page_json(PAGE_ID);
+    page["properties"] = json!({
+        "Name": { "id": "title", "type": "title", "title": [
+            { "type": "text", "text": { "content": "Launch" }, "plain_text": "Launch" }
+        ] },
+        "Blocked by": { "id": "rel", "type": "relation", "relation": related(MAX_INLINE_ITEMS), "has_more": true },
+        "Blockers": { "id": "rol", "type": "rollup", "rollup": { "type": "number", "number": 25, "function": "count" } },
+        "Estimate": { "id": "num", "type": "number", "number": 3 }
+    });
+    page
+}
+
+/// `items` served `per_page` at a time, as the property item endpoint does.
+fn list_reply(req: &Recorded, kind: &str, items: &[Value], per_page: usize) -> Reply {
+    let start = req
+        .path
+        .split("start_cursor=")
+        .nth(1)
+        .map_or(0, |c| c.parse::<usize>().unwrap());
+    let end = (start + per_page).min(items.len());
+    let has_more = end < items.len();
+    let results: Vec<Value> = items[start..end]
+        .iter()
+        .map(|item| json!({ "object": "property_item", "id": "x", "type": kind, kind: item }))
+        .collect();
+    let property_item = match kind {
+        "rollup" => {
+            json!({ "id": "rol", "type": "rollup", "rollup": { "type": "number", "number": 30, "function": "count" } })
+        }
+        _ => json!({ "id": "x", "type": kind, kind: {} }),
+    };
+    Reply::json(
+        200,
+        json!({
+            "object": "list",
+            "results": results,
+            "next_cursor": has_more.then(|| end.to_string()),
+            "has_more": has_more,
+            "type": "property_item",
+            "property_item": property_item
+        }),
+    )
+}
+
+fn server() -> MockServer {
+    MockServer::start(|req| {
+        let path = req.path.replace(PAGE_ID, "page");
+        let path = path.split('?').next().unwrap();
+        match path {
+            "/pages/page" => Reply::json(200, truncated_page()),
+            "/pages/page/properties/rel" => list_reply(req, "relation", &related(30), 20),
+            "/pages/page/properties/rol" => list_reply(req, "rollup", &[], 100),
+            "/pages/page/properties/num" => Reply::json(
+                200,
+                json!({ "object": "property_item", "id": "num", "type": "number", "number": 3 }),
+            ),
+            other => panic!("unexpected request {other}"),
+        }
+    })
+}
+
+#[test]
+fn detects_truncated_properties() {
+    let page: Page = serde_json::from_value(truncated_page()).unwrap();
+    assert!(page.properties["Blocked by"].is_truncated());
+    assert!(!page.properties["Blockers"].is_truncated());
+    // The rollup is listed too: Notion computed it from the first 25.
+    assert_eq!(page.truncated_properties(), ["Blocked by", "Blockers"]);
+
+    let mut runs: Page = serde_json::from_value(truncated_page()).unwrap();
+    runs.properties.remove("Blocked by");
+    assert!(runs.truncated_properties().is_empty());
+    let text = json!({ "type": "text", "text": { "content": "x" }, "plain_text": "x" });
+    runs.properties.get_mut("Name").unwrap().value =
+        serde_json::from_value(json!({ "type": "title", "title": vec![text; 25] })).unwrap();
+    assert_eq!(runs.truncated_properties(), ["Name"]);
+}
+
+#[test]
+fn get_property_follows_cursors() {
+    let server = server();
+    let notion = client(&server);
+    let value = notion.get_property(PAGE_ID, "rel").unwrap();
+    let ids: Vec<_> = value
+        .as_relation()
+        .unwrap()
+        .iter()
+        .map(|r| r.id.as_str())
+        .collect();
+    assert_eq!(ids.len(), 30);
+    assert_eq!(ids[29], "rel-29");
+
+    let paths: Vec<_> = server.requests().into_iter().map(|r| r.path).collect();
+    assert_eq!(
+        paths,
+        [
+            format!("/pages/{PAGE_ID}/properties/rel?page_size=100"),
+            format!("/pages/{PAGE_ID}/properties/rel?page_size=100&start_cursor=20"),
+        ]
+    );
+
+    // Simple properties come back as one item.
+    let estimate = notion.get_property(PAGE_ID, "num").unwrap();
+    assert_eq!(estimate.as_number(), Some(3.0));
+    // Rollups take the value Notion computed over every item.
+    let rollup = notion.get_property(PAGE_ID, "rol").unwrap();
+    match rollup {
+        PropertyValue::Rollup(rollup) => {
+            assert_eq!(rollup.value, RollupValue::Number(Some(30.into())));
+            assert_eq!(rollup.function.as_deref(), Some("count"));
+        }
+        other => panic!("{other:?}"),
+    }
+}
+
+#[test]
+fn fetch_complete_merges_full_values_into_the_page() {
+    let server = server();
+    let notion = client(&server);
+    let page = notion.get_page(PAGE_ID).unwrap();
+    assert_eq!(
+        page.prop("Blocked by")
+            .unwrap()
+            .as_relation()
+            .unwrap()
+            .len(),
+        25
+    );
+    assert_eq!(server.hits(), 1);
+
+    let options = PageOptions::default().with_fetch_complete(true);
+    let page = notion.get_page_with(PAGE_ID, &options).unwrap();
+    let relation = &page.properties["Blocked by"];
+    assert_eq!(relation.value.as_relation().unwrap().len(), 30);
+    assert!(relation.extra.get("has_more").is_none());
+    assert_eq!(
+        page.prop("Blockers").unwrap().as_rollup().unwrap().value,
+        RollupValue::Number(Some(30.into()))
+    );
+    // One page request, two relation pages, one rollup page; the number is
+    // left alone.
+    assert_eq!(server.hits(), 1 + 4);
+    assert!(page.truncated_properties().is_empty());
+}
@@ -0,0 +1,219 @@
+#![cfg(feature = "notion")]
+
+mod common;
+
+use common::{page_json as page, MockServer, Reply};
+use serde_json::{json, Value};
+use swivel::notion::{Direction, Filter, NotionClient, Sort};
+use swivel::Error;
+
+const DATA_SOURCE_ID: &str = "b5ad9a34-4e2f-4f8e-9c3c-1b0e6d4f7a21";
+
+/// Three pages of results: ids 0..5 split as [0, 1], [2, 3], [4].
+fn paginated() -> MockServer {
+    MockServer::start(|req| {
+        let body = req.json();
+        let start = body["start_cursor"]
+            .as_str()
+            .map_or(0, |c| c.parse::<usize>().unwrap());
+        let end = (start + 2).min(5);
+        let results: Vec<Value> = (start..end).map(|i| page(&i.to_string())).collect();
+        let has_more = end < 5;
+        Reply::json(
+            200,
+            json!({
+                "object": "list",
+                "results": results,
+                "next_cursor": has_more.then(|| end.to_string()),
+                "has_more": has_more
+            }),
+        )
+    })
+}
+
+fn client(server: &MockServer) -> NotionClient {
+    NotionClient::new("secret")
+        .with_base_url(server.url())
+        .with_rate_limiter(None)
+}
+
+#[test]
+fn follows_cursors_until_has_more_is_false() {
+    let server = paginated();
+    let notion = client(&server);
+    let ids: Vec<String> = notion
+        .query_data_source(DATA_SOURCE_ID)
+        .page_size(2)
+        .into_iter()
+        .map(|p| p.unwrap().id)
+        .collect();
+    assert_eq!(ids, ["0", "1", "2", "3", "4"]);
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 3);
+    assert!(requests
+        .iter()
+        .all(|r| r.method == "POST" && r.path == format!("/data_sources/{DATA_SOURCE_ID}/query")));
+    assert_eq!(requests[0].json(), json!({ "page_size": 2 }));
+    assert_eq!(
+        requests[2].json(),
+        json!({ "page_size": 2, "start_cursor": "4" })
+    );
+}
+
+#[test]
+fn fetches_lazily() {
+    let server = paginated();
+    let notion = client(&server);
+    let mut pages = notion
+        .query_data_source(DATA_SOURCE_ID)
+        .page_size(2)
+        .pages();
+    assert_eq!(server.hits(), 0);
+    pages.next().unwrap().unwrap();
+    pages.next().unwrap().unwrap();
+    assert_eq!(server.hits(), 1);
+    pages.next().unwrap().unwrap();
+    assert_eq!(server.hits(), 2);
+}
+
+#[test]
+fn sends_filter_and_sorts() {
+    let server = paginated();
+    let notion = client(&server);
+    let filter = json!({ "property": "Done", "checkbox": { "equals": true } });
+    notion
+        .query_data_source(DATA_SOURCE_ID)
+        .filter(filter.clone())
+        .sort(Sort::ascending("Due"))
+        .sort(Sort::created_time(Direction::Descending))
+        .pages()
+        .next();
+    let body = server.requests()[0].json();
+    assert_eq!(body["filter"], filter);
+    assert_eq!(
+        body["sorts"],
+        json!([
+            { "property": "Due", "direction": "ascending" },
+            { "timestamp": "created_time", "direction": "descending" }
+        ])
+    );
+}
+
+#[test]
+fn typed_filters_compile_to_notion_json() {
+    let filter = Filter::prop("Status")
+        .status()
+        .equals("Done")
+        .and(Filter::prop("Estimate").number().greater_than(2))
+        .and(
+            Filter::prop("Tags")
+                .multi_select()
+                .contains("ops")
+                .or(Filter::prop("Days left").formula().number().less_than(1.5)),
+        )
+        .and(Filter::last_edited_time().past_week());
+    assert_eq!(
+        filter.to_value(),
+        json!({ "and": [
+            { "property": "Status", "status": { "equals": "Done" } },
+            { "property": "Estimate", "number": { "greater_than": 2 } },
+            { "or": [
+                { "property": "Tags", "multi_select": { "contains": "ops" } },
+                { "property": "Days left", "formula": { "number": { "less_than": 1.5 } } }
+            ] },
+            { "timestamp": "last_edited_time", "last_edited_time": { "past_week": {} } }
+        ] })
+    );
+}
+
+#[test]
+fn rejects_filters_nested_too_deep() {
+    let server = paginated();
+    let notion = client(&server);
+    let leaf = || Filter::prop("Done").checkbox().equals(true);
+    let filter = Filter::any([Filter::all([Filter::any([leaf(), leaf()]), leaf()]), leaf()]);
+    let mut pages = notion
+        .query_data_source(DATA_SOURCE_ID)
+        .filter(filter)
+        .pages();
+    assert!(matches!(pages.next(), Some(Err(Error::InvalidInput(_)))));
+    assert_eq!(server.hits(), 0);
+}
+
+fn schema_server() -> MockServer {
+    MockServer::start(|req| {
+        assert_eq!(
+            (req.method.as_str(), req.path.as_str()),
+            ("GET", format!("/data_sources/{DATA_SOURCE_ID}").as_str())
+        );
+        Reply::json(
+            200,
+            json!({
+                "object": "data_source",
+                "id": DATA_SOURCE_ID,
+                "title": [{ "type": "text", "text": { "content": "Tasks" }, "plain_text": "Tasks" }],
+                "properties": {
+                    "Status": { "id": "a", "name": "Status", "type": "select", "select": { "options": [] } },
+                    "Created": { "id": "b", "name": "Created", "type": "created_time", "created_time": {} }
+                }
+            }),
+        )
+    })
+}
+
+#[test]
+fn checks_filters_against_the_schema() {
+    let server = schema_server();
+    let notion = client(&server);
+    let schema = notion.get_data_source(DATA_SOURCE_ID).unwrap();
+    assert_eq!(schema.title(), "Tasks");
+
+    let ok = notion.query_data_source(DATA_SOURCE_ID).filter(
+        Filter::prop("Status")
+            .select()
+            .equals("Done")
+            .and(Filter::prop("Created").date().past_month()),
+    );
+    assert!(ok.check(&schema).is_ok());
+
+    let wrong_type = notion
+        .query_data_source(DATA_SOURCE_ID)
+        .filter(Filter::prop("Status").number().greater_than(3));
+    let err = wrong_type.check(&schema).unwrap_err();
+    assert!(matches!(err, Error::InvalidInput(_)));
+    assert!(err.to_string().contains("`select`"), "{err}");
+
+    let missing = notion
+        .query_data_source(DATA_SOURCE_ID)
+        .sort(Sort::descending("Priority"));
+    assert!(matches!(
+        missing.check(&schema),
+        Err(Error::InvalidInput(_))
+    ));
+}
+
+#[test]
+fn checked_fetches_the_schema_before_querying() {
+    let server = schema_server();
+    let notion = client(&server);
+    let result = notion
+        .query_data_source(DATA_SOURCE_ID)
+        .filter(Filter::prop("Status").status().equals("Done"))
+        .checked();
+    assert!(matches!(result, Err(Error::InvalidInput(_))));
+    assert_eq!(server.hits(), 1);
+}
+
+#[test]
+fn rejects_page_size_out_of_range() {
+    let server = paginated();
+    let notion = client(&server);
+    let mut pages = notion
+        .query_data_source(DATA_SOURCE_ID)
+        .page_size(101)
+        .pages();
+    assert!(matches!(pages.next(), Some(Err(Error::InvalidInput(_)))));
+    assert!(pages.next().is_none());
+    assert_eq!(server.hits(), 0);
+}
@@ -0,0 +1,150 @@
+#![cfg(feature = "notion")]
+
+mod common;
+
+use std::time::{Duration, Instant};
+
+use common::{MockServer, Reply};
+use serde_json::json;
+use swivel::notion::NotionClient;
+use swivel::{Error, RateLimiter, RetryPolicy};
+
+const PAGE_ID: &str = "0d2c4d47-5f3b-4a8e-9c1d-2b6e8f7a9c10";
+
+fn page() -> Reply {
+    Reply::json(200, common::page_json(PAGE_ID))
+}
+
+fn rate_limited() -> Reply {
+    Reply::json(
+        429,
+        json!({
+            "object": "error",
+            "status": 429,
+            "code": "rate_limited",
+            "message": "You have been rate limited.",
+            "request_id": "req-429"
+        }),
+    )
+    .header("Retry-After", "0")
+}
+
+fn client(server: &MockServer) -> NotionClient {
+    NotionClient::new("secret")
+        .with_base_url(server.url())
+        .with_rate_limiter(None)
+        .with_retry_policy(
+            RetryPolicy::default()
+                .with_base_delay(Duration::from_millis(1))
+                .with_jitter(false),
+        )
+}
+
+#[test]
+fn retries_429_until_success() {
+    let server = MockServer::sequence(vec![rate_limited(), rate_limited(), page()]);
+    let page = client(&server).get_page(PAGE_ID).unwrap();
+    assert_eq!(page.id, PAGE_ID);
+    assert_eq!(server.hits(), 3);
+}
+
+#[test]
+fn gives_up_after_max_attempts() {
+    let server = MockServer::sequence(vec![rate_limited()]);
+    let err = client(&server)
+        .with_retry_policy(RetryPolicy::default().with_max_attempts(2))
+        .get_page(PAGE_ID)
+        .unwrap_err();
+    assert!(matches!(err, Error::RateLimited { retry_after: Some(d), .. } if d.is_zero()));
+    assert_eq!(err.request_id(), Some("req-429"));
+    assert_eq!(server.hits(), 2);
+}
+
+#[test]
+fn retries_bad_gateway() {
+    let server = MockServer::sequence(vec![
+        Reply::json(
+            502,
+            json!({ "object": "error", "code": "bad_gateway", "message": "" }),
+        ),
+        page(),
+    ]);
+    client(&server).get_page(PAGE_ID).unwrap();
+    assert_eq!(server.hits(), 2);
+}
+
+#[test]
+fn does_not_retry_client_errors() {
+    let server = MockServer::sequence(vec![Reply::json(
+        404,
+        json!({
+            "object": "error",
+            "status": 404,
+            "code": "object_not_found",
+            "message": "Could not find page.",
+            "request_id": "req-404"
+        }),
+    )]);
+    let err = client(&server).get_page(PAGE_ID).unwrap_err();
+    assert!(matches!(err, Error::ObjectNotFound { .. }));
+    assert_eq!(server.hits(), 1);
+}
+
+#[test]
+fn retry_policy_none_sends_once() {
+    let server = MockServer::sequence(vec![rate_limited(), page()]);
+    let err = client(&server)
+        .with_retry_policy(RetryPolicy::none())
+        .get_page(PAGE_ID)
+        .unwrap_err();
+    assert!(matches!(err, Error::RateLimited { .. }));
+    assert_eq!(server.hits(), 1);
+}
+
+#[test]
+fn only_idempotent_requests_are_retried() {
+    let policy = RetryPolicy::default();
+    let err = Error::RateLimited {
+        retry_after: None,
+        request_id: None,
+    };
+    assert!(policy.should_retry(1, &err, true));
+    assert!(!policy.should_retry(1, &err, false));
+    assert!(!policy.should_retry(policy.max_attempts(), &err, true));
+}
+
+#[test]
+fn retry_after_overrides_backoff() {
+    let policy = RetryPolicy::default().with_jitter(false);
+    let err = Error::RateLimited {
+        retry_after: Some(Duration::from_secs(7)),
+        request_id: None,
+    };
+    assert_eq!(policy.delay(1, &err), Duration::from_secs(7));
+    let policy = policy.with_retry_after(false);
+    assert_eq!(policy.delay(1, &err), Duration::from_millis(500));
+    assert_eq!(policy.delay(3, &err), Duration::from_secs(2));
+}
+
+#[test]
+fn rate_limiter_is_shared_between_clones() {
+    let server = MockServer::sequence(vec![page()]);
+    let notion = client(&server).with_rate_limiter(Some(RateLimiter::new(20.0, 1)));
+    let clone = notion.clone();
+
+    let start = Instant::now();
+    for _ in 0..3 {
+        notion.get_page(PAGE_ID).unwrap();
+        clone.get_page(PAGE_ID).unwrap();
+    }
+    // Six requests with a burst of one at 20/s need at least 5 * 50ms.
+    assert!(start.elapsed() >= Duration::from_millis(240));
+}
+
+#[test]
+fn rate_limiter_rejects_rates_it_cannot_honor() {
+    for rate in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+        let built = std::panic::catch_unwind(|| RateLimiter::new(rate, 1));
+        assert!(built.is_err(), "{rate} was accepted");
+    }
+}
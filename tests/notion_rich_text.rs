@@ -0,0 +1,227 @@
+#![cfg(feature = "notion")]
+
+use serde_json::{json, Value};
+use swivel::notion::rich_text::{
+    self, Annotations, LinkPreview, ObjectRef, RichTextContent, TemplateMention, MAX_TEXT_LENGTH,
+};
+use swivel::notion::{DateValue, Mention, RichText};
+
+const PAGE_ID: &str = "275a1865-b187-807a-adea-ebaf36fb49b0";
+
+fn mention(payload: Value) -> RichText {
+    serde_json::from_value(json!({
+        "type": "mention",
+        "mention": payload,
+        "annotations": {},
+        "plain_text": "shown",
+        "href": null
+    }))
+    .unwrap()
+}
+
+fn mentioned(run: &RichText) -> &Mention {
+    match &run.content {
+        RichTextContent::Mention(mention) => mention,
+        other => panic!("{other:?}"),
+    }
+}
+
+#[test]
+fn mentions_are_typed_and_round_trip() {
+    let cases = [
+        json!({ "type": "user", "user": { "object": "user", "id": "u1" } }),
+        json!({ "type": "page", "page": { "id": PAGE_ID } }),
+        json!({ "type": "database", "database": { "id": "db" } }),
+        json!({ "type": "date", "date": { "start": "2025-01-01", "end": null, "time_zone": null } }),
+        json!({ "type": "link_preview", "link_preview": { "url": "https://github.com" } }),
+        json!({ "type": "template_mention", "template_mention": {
+            "type": "template_mention_date", "template_mention_date": "today"
+        } }),
+        json!({ "type": "custom_emoji", "custom_emoji": { "id": "e1" } }),
+    ];
+    let kinds: Vec<_> = cases
+        .iter()
+        .map(|payload| {
+            let run = mention(payload.clone());
+            assert_eq!(serde_json::to_value(&run).unwrap()["mention"], *payload);
+            mentioned(&run).kind().to_string()
+        })
+        .collect();
+    assert_eq!(
+        kinds,
+        [
+            "user",
+            "page",
+            "database",
+            "date",
+            "link_preview",
+            "template_mention",
+            "custom_emoji"
+        ]
+    );
+    let run = mention(cases[1].clone());
+    assert_eq!(
+        mentioned(&run),
+        &Mention::Page(ObjectRef { id: PAGE_ID.into() })
+    );
+    let run = mention(cases[5].clone());
+    assert_eq!(
+        mentioned(&run),
+        &Mention::TemplateMention(TemplateMention::Date("today".into()))
+    );
+}
+
+#[test]
+fn builder_writes_what_notion_expects() {
+    let runs = RichText::new()
+        .bold("Note:")
+        .text(" see ")
+        .page_mention(PAGE_ID)
+        .text(", ")
+        .link("the docs", "https://developers.notion.com")
+        .text(" and ")
+        .equation("e^{i\\pi} + 1 = 0")
+        .date_mention(DateValue::new("2025-03-01"))
+        .build();
+    let json = serde_json::to_value(&runs).unwrap();
+    assert_eq!(json[0]["annotations"]["bold"], true);
+    assert_eq!(json[0]["text"]["content"], "Note:");
+    assert_eq!(
+        json[2]["mention"],
+        json!({ "type": "page", "page": { "id": PAGE_ID } })
+    );
+    assert_eq!(
+        json[4]["text"]["link"]["url"],
+        "https://developers.notion.com"
+    );
+    assert_eq!(json[6]["equation"]["expression"], "e^{i\\pi} + 1 = 0");
+    assert_eq!(json[7]["mention"]["date"]["start"], "2025-03-01");
+
+    assert_eq!(
+        rich_text::plain_text(&runs),
+        format!("Note: see {PAGE_ID}, the docs and e^{{i\\pi}} + 1 = 02025-03-01")
+    );
+
+    let others = RichText::new()
+        .user_mention("u1")
+        .link_preview("https://github.com")
+        .template_mention(TemplateMention::User("me".into()))
+        .mention(Mention::DataSource(ObjectRef { id: "ds".into() }))
+        .build();
+    let kinds: Vec<_> = others.iter().map(|r| mentioned(r).kind()).collect();
+    assert_eq!(
+        kinds,
+        ["user", "link_preview", "template_mention", "data_source"]
+    );
+    assert_eq!(
+        mentioned(&others[2]),
+        &Mention::TemplateMention(TemplateMention::User("me".into()))
+    );
+    assert_eq!(
+        mentioned(&others[1]),
+        &Mention::LinkPreview(LinkPreview {
+            url: "https://github.com".into()
+        })
+    );
+}
+
+#[test]
+fn builder_splits_long_text() {
+    let long = "a".repeat(MAX_TEXT_LENGTH * 2 + 10);
+    let runs: Vec<RichText> = RichText::new().italic(long.clone()).code("x").into();
+    let lengths: Vec<_> = runs.iter().map(|r| r.plain_text.len()).collect();
+    assert_eq!(lengths, [MAX_TEXT_LENGTH, MAX_TEXT_LENGTH, 10, 1]);
+    assert!(runs[..3].iter().all(|r| r.annotations.italic));
+    assert_eq!(rich_text::plain_text(&runs[..3]), long);
+}
+
+#[test]
+fn long_text_is_measured_in_utf16_units() {
+    // 1500 emoji are 1500 characters but 3000 UTF-16 code units.
+    let emoji = "🦀".repeat(1500);
+    let runs = rich_text::from_plain_text(&emoji);
+    let units: Vec<_> = runs
+        .iter()
+        .map(|r| r.plain_text.encode_utf16().count())
+        .collect();
+    assert_eq!(units, [MAX_TEXT_LENGTH, 1000]);
+    assert_eq!(rich_text::plain_text(&runs), emoji);
+
+    // A character that would straddle the limit starts the next run.
+    let text = format!("{}🦀", "a".repeat(MAX_TEXT_LENGTH - 1));
+    let runs = rich_text::from_plain_text(&text);
+    let lengths: Vec<_> = runs.iter().map(|r| r.plain_text.len()).collect();
+    assert_eq!(lengths, [MAX_TEXT_LENGTH - 1, 4]);
+}
+
+#[test]
+fn renders_html_and_markdown() {
+    let red = Annotations {
+        color: "red".into(),
+        ..Annotations::default()
+    };
+    let runs = RichText::new()
+        .bold("Note:")
+        .text(" a < b & ")
+        .styled("warm", red)
+        .text("\n")
+        .underline("under")
+        .strikethrough("gone")
+        .code("x")
+        .link("docs", "https://example.com/?a=1&b=2")
+        .equation("x^2")
+        .page_mention(PAGE_ID)
+        .build();
+    assert_eq!(
+        rich_text::to_html(&runs),
+        "<strong>Note:</strong> a &lt; b &amp; <span class=\"notion-red\">warm</span><br>\
+         <u>under</u><s>gone</s><code>x</code>\
+         <a href=\"https://example.com/?a=1&amp;b=2\">docs</a>\
+         <span class=\"equation\">x^2</span>\
+         <a href=\"https://www.notion.so/275a1865b187807aadeaebaf36fb49b0\">275a1865-b187-807a-adea-ebaf36fb49b0</a>"
+    );
+    assert_eq!(
+        rich_text::to_markdown(&runs[..3]),
+        "**Note:** a \\< b & warm"
+    );
+    // Mentions read from Notion show the title Notion gave them.
+    let read = mention(json!({ "type": "user", "user": { "object": "user", "id": "u1" } }));
+    assert_eq!(rich_text::to_html(&[read]), "shown");
+}
+
+#[test]
+fn links_survive_markdown_and_script_urls_stay_out_of_html() {
+    let runs = RichText::new()
+        .link(
+            "wiki",
+            "https://en.wikipedia.org/wiki/Rust_(programming_language)",
+        )
+        .text(" ")
+        .link("spec", "https://example.com/a b<c>")
+        .text(" ")
+        .link("plain", "https://example.com/x")
+        .build();
+    assert_eq!(
+        rich_text::to_markdown(&runs),
+        "[wiki](<https://en.wikipedia.org/wiki/Rust_(programming_language)>) \
+         [spec](<https://example.com/a b%3Cc%3E>) [plain](https://example.com/x)"
+    );
+
+    for url in [
+        "javascript:alert(1)",
+        "JavaScript:alert(1)",
+        " java\tscript:alert(1)",
+        "data:text/html,<script>alert(1)</script>",
+        "vbscript:msgbox",
+    ] {
+        let runs = RichText::new().link("click", url).build();
+        assert_eq!(rich_text::to_html(&runs), "click", "{url}");
+    }
+    for url in ["mailto:ann@example.com", "/docs?a=b:c", "#top", "tel:+1555"] {
+        let runs = RichText::new().link("ok", url).build();
+        assert_eq!(
+            rich_text::to_html(&runs),
+            format!("<a href=\"{url}\">ok</a>"),
+        );
+    }
+}
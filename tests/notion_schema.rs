@@ -0,0 +1,287 @@
+#![cfg(feature = "notion")]
+
+mod common;
+
+use common::{MockServer, Reply};
+use serde_json::{json, Value};
+use swivel::notion::{
+    DataSource, NotionClient, SchemaChange, SchemaPlan, SchemaSpec, SchemaTarget,
+    LEGACY_NOTION_VERSION,
+};
+use swivel::Error;
+
+const DATA_SOURCE_ID: &str = "b5ad9a34-4e2f-4f8e-9c3c-1b0e6d4f7a21";
+const PARENT_ID: &str = "275a1865-b187-807a-adea-ebaf36fb49b0";
+const DATABASE_ID: &str = "0d2c4d47-5f3b-4a8e-9c1d-2b6e8f7a9c10";
+
+/// The fixture's layout, evolved: a renamed title, a new property, a
+/// retyped one, a new and a renamed select option and a new formula.
+const SPEC: &str = r#"
+title = "Launch tasks"
+parent = "https://www.notion.so/acme/Projects-275a1865b187807aadeaebaf36fb49b0"
+
+[properties.Task]
+type = "title"
+
+[properties.Priority]
+type = "select"
+options = ["High", { name = "Urgent", color = "red" }, { name = "Minor", renamed_from = "Low" }]
+
+[properties.Notes]
+type = "number"
+
+[properties.Review]
+type = "date"
+
+[properties."Days left"]
+type = "formula"
+expression = "dateBetween(prop(\"Due\"), now(), \"weeks\")"
+
+[properties."Blocked by"]
+type = "relation"
+data_source = "b5ad9a344e2f4f8e9c3c1b0e6d4f7a21"
+
+[properties.Points]
+type = "number"
+format = "number"
+renamed_from = "Estimate"
+"#;
+
+fn spec() -> SchemaSpec {
+    SchemaSpec::from_toml(SPEC).unwrap()
+}
+
+fn schema() -> DataSource {
+    serde_json::from_str(include_str!("fixtures/data_source.json")).unwrap()
+}
+
+fn client(server: &MockServer) -> NotionClient {
+    NotionClient::new("secret")
+        .with_base_url(server.url())
+        .with_rate_limiter(None)
+}
+
+#[test]
+fn reads_toml_and_json_and_rejects_bad_specs() {
+    let spec = spec();
+    assert_eq!(
+        spec.properties["Priority"].options[1].color.as_deref(),
+        Some("red")
+    );
+    let json = serde_json::to_string(&spec).unwrap();
+    assert_eq!(SchemaSpec::from_json(&json).unwrap(), spec);
+
+    for (source, expected) in [
+        ("title = \"T\"", "exactly one `title` property"),
+        (
+            "title = \"T\"\n[properties.A]\ntype = \"title\"\n[properties.B]\ntype = \"number\"\noptions = [\"x\"]",
+            "property `B`: `options` does not apply to a `number` property",
+        ),
+        (
+            "title = \"T\"\n[properties.A]\ntype = \"title\"\n[properties.B]\ntype = \"relation\"",
+            "a relation needs `data_source`",
+        ),
+        (
+            "title = \"T\"\n[properties.A]\ntype = \"title\"\n[properties.B]\ntype = \"rollup\"",
+            "type `rollup` cannot be set up",
+        ),
+        ("title = \"T\"\ncolour = \"red\"", "unknown field `colour`"),
+        (
+            "title = \"T\"\n[properties.A]\ntype = \"title\"\n[properties.B]\ntype = \"select\"\noptions = [{ name = \"x\", renamed_form = \"y\" }]",
+            "unknown field `renamed_form`",
+        ),
+    ] {
+        match SchemaSpec::from_toml(source) {
+            Err(Error::InvalidInput(message)) => {
+                assert!(message.contains(expected), "{message}")
+            }
+            other => panic!("{source}: {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn plans_the_difference_with_the_live_schema() {
+    let plan = SchemaPlan::new(&spec(), Some(&schema())).unwrap();
+    assert_eq!(
+        plan.target,
+        SchemaTarget::Update {
+            data_source_id: DATA_SOURCE_ID.into()
+        }
+    );
+    assert_eq!(
+        plan.to_string(),
+        "update data source \"Launch tasks\" (b5ad9a34-4e2f-4f8e-9c3c-1b0e6d4f7a21)\n  \
+         ~ Days left: expression\n  \
+         ~ Notes: rich_text -> number\n  \
+         ~ Estimate: rename to Points\n  \
+         ~ Priority: rename option Low to Minor\n  \
+         + Priority: options Urgent\n  \
+         + Review: date\n  \
+         ~ Name: rename to Task\n"
+    );
+
+    // Applied, the same spec has nothing left to do.
+    let mut applied = schema();
+    for change in &plan.changes {
+        if let SchemaChange::RenameProperty { from, to } = change {
+            let mut property = applied.properties.remove(from).unwrap();
+            property.name = to.clone();
+            applied.properties.insert(to.clone(), property);
+        }
+    }
+    let mut spec = spec();
+    spec.properties.remove("Priority");
+    spec.properties.remove("Days left");
+    spec.properties.remove("Review");
+    spec.properties.get_mut("Notes").unwrap().kind = "rich_text".into();
+    let plan = SchemaPlan::new(&spec, Some(&applied)).unwrap();
+    assert!(plan.is_empty(), "{plan}");
+    assert_eq!(
+        plan.to_string(),
+        "data source \"Launch tasks\" is up to date\n"
+    );
+}
+
+#[test]
+fn updates_an_existing_data_source() {
+    let server = MockServer::start(|req| {
+        let mut schema: Value =
+            serde_json::from_str(include_str!("fixtures/data_source.json")).unwrap();
+        if req.method == "PATCH" {
+            schema["properties"]["Review"] =
+                json!({ "id": "rev", "name": "Review", "type": "date", "date": {} });
+        }
+        Reply::json(200, schema)
+    });
+    let notion = client(&server);
+    let mut spec = spec();
+    spec.id = Some(DATA_SOURCE_ID.into());
+    let plan = notion.plan_schema(&spec).unwrap();
+    let updated = notion.apply_schema(&plan).unwrap();
+    assert!(updated.properties.contains_key("Review"));
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[1].method, "PATCH");
+    assert_eq!(requests[1].path, format!("/data_sources/{DATA_SOURCE_ID}"));
+    let properties = &requests[1].json()["properties"];
+    // Renames and changes address each property by its current name.
+    assert_eq!(properties["Name"], json!({ "name": "Task" }));
+    assert_eq!(
+        properties["Estimate"],
+        json!({ "name": "Points" }),
+        "the format already matches"
+    );
+    assert_eq!(
+        properties["Notes"],
+        json!({ "number": { "format": "number" } })
+    );
+    assert_eq!(properties["Review"], json!({ "date": {} }));
+    // The options Notion has are sent back, or they would be deleted.
+    let options: Vec<_> = properties["Priority"]["select"]["options"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|o| o["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(options, ["High", "Medium", "Minor", "Urgent"]);
+    assert_eq!(properties["Priority"]["select"]["options"][0]["id"], "p1");
+    // A renamed option keeps its id, so pages that use it follow along.
+    assert_eq!(
+        properties["Priority"]["select"]["options"][2],
+        json!({ "id": "p3", "name": "Minor", "color": "gray" })
+    );
+    assert_eq!(
+        properties["Priority"]["select"]["options"][3],
+        json!({ "name": "Urgent", "color": "red" })
+    );
+    assert!(properties.get("Blocked by").is_none());
+}
+
+fn creating_server() -> MockServer {
+    MockServer::start(|req| match (req.method.as_str(), req.path.as_str()) {
+        ("POST", "/search") => Reply::json(
+            200,
+            json!({ "object": "list", "results": [], "next_cursor": null, "has_more": false }),
+        ),
+        ("POST", "/databases") => Reply::json(
+            200,
+            json!({
+                "object": "database",
+                "id": DATABASE_ID,
+                "data_sources": [{ "id": DATA_SOURCE_ID, "name": "Launch tasks" }]
+            }),
+        ),
+        _ => Reply::json(
+            200,
+            serde_json::from_str(include_str!("fixtures/data_source.json")).unwrap(),
+        ),
+    })
+}
+
+#[test]
+fn creates_a_missing_database_under_the_parent() {
+    let server = creating_server();
+    let notion = client(&server);
+    let plan = notion.plan_schema(&spec()).unwrap();
+    assert_eq!(
+        plan.target,
+        SchemaTarget::Create {
+            parent: PARENT_ID.into()
+        }
+    );
+    assert!(plan.to_string().starts_with(&format!(
+        "create database \"Launch tasks\" under page {PARENT_ID}\n  + Blocked by: relation\n"
+    )));
+    let created = notion.apply_schema(&plan).unwrap();
+    assert_eq!(created.id, DATA_SOURCE_ID);
+
+    let requests = server.requests();
+    let paths: Vec<_> = requests.iter().map(|r| r.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        [
+            "/search",
+            "/databases",
+            &format!("/data_sources/{DATA_SOURCE_ID}")
+        ]
+    );
+    let body = requests[1].json();
+    assert_eq!(
+        body["parent"],
+        json!({ "type": "page_id", "page_id": PARENT_ID })
+    );
+    assert_eq!(body["title"][0]["text"]["content"], "Launch tasks");
+    let properties = &body["initial_data_source"]["properties"];
+    assert_eq!(properties["Task"], json!({ "title": {} }));
+    assert_eq!(
+        properties["Blocked by"]["relation"],
+        json!({ "data_source_id": DATA_SOURCE_ID, "type": "single_property", "single_property": {} })
+    );
+    assert_eq!(
+        properties["Priority"]["select"]["options"],
+        json!([
+            { "name": "High" },
+            { "name": "Urgent", "color": "red" },
+            { "name": "Minor" }
+        ])
+    );
+}
+
+#[test]
+fn legacy_versions_create_databases_with_properties() {
+    let server = creating_server();
+    let notion = client(&server).with_notion_version(LEGACY_NOTION_VERSION);
+    let plan = notion.plan_schema(&spec()).unwrap();
+    notion.apply_schema(&plan).unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests[2].path, format!("/databases/{DATABASE_ID}"));
+    let body = requests[1].json();
+    assert!(body.get("initial_data_source").is_none());
+    assert_eq!(
+        body["properties"]["Blocked by"]["relation"]["database_id"],
+        DATA_SOURCE_ID
+    );
+}
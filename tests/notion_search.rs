@@ -0,0 +1,112 @@
+#![cfg(feature = "notion")]
+
+mod common;
+
+use common::{page_json, MockServer, Reply};
+use serde_json::json;
+use swivel::notion::{Direction, NotionClient, Parent, SearchObject, SearchResult};
+use swivel::Error;
+
+fn client(server: &MockServer) -> NotionClient {
+    NotionClient::new("secret")
+        .with_base_url(server.url())
+        .with_rate_limiter(None)
+}
+
+fn data_source_json(id: &str) -> serde_json::Value {
+    json!({
+        "object": "data_source",
+        "id": id,
+        "title": [{ "type": "text", "text": { "content": "Tasks" }, "plain_text": "Tasks" }],
+        "parent": { "type": "database_id", "database_id": "db1" },
+        "properties": {}
+    })
+}
+
+#[test]
+fn follows_pages_of_typed_results() {
+    let server = MockServer::sequence(vec![
+        Reply::json(
+            200,
+            json!({
+                "object": "list",
+                "results": [page_json("p1"), data_source_json("ds1")],
+                "next_cursor": "c2",
+                "has_more": true
+            }),
+        ),
+        Reply::json(
+            200,
+            json!({
+                "object": "list",
+                "results": [{ "object": "agent", "id": "a1" }],
+                "next_cursor": null,
+                "has_more": false
+            }),
+        ),
+    ]);
+    let results: Vec<SearchResult> = client(&server)
+        .search("tasks")
+        .results()
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    assert_eq!(results.len(), 3);
+    assert!(matches!(results[0], SearchResult::Page(_)));
+    assert_eq!(results[0].url(), Some("https://www.notion.so/p1"));
+    assert_eq!(
+        results[0].parent(),
+        Some(&Parent::Page {
+            page_id: "root".into()
+        })
+    );
+    assert_eq!(results[1].object(), "data_source");
+    assert_eq!(results[1].title(), "Tasks");
+    assert_eq!(
+        results[1].parent(),
+        Some(&Parent::Database {
+            database_id: "db1".into()
+        })
+    );
+    assert_eq!(results[2].object(), "agent");
+    assert_eq!(results[2].id(), "a1");
+
+    let bodies: Vec<_> = server.requests().iter().map(|r| r.json()).collect();
+    assert_eq!(server.requests()[0].path, "/search");
+    assert_eq!(bodies[0], json!({ "query": "tasks", "page_size": 100 }));
+    assert_eq!(bodies[1]["start_cursor"], "c2");
+}
+
+#[test]
+fn filters_by_object_and_sorts_by_edit_time() {
+    let server = MockServer::sequence(vec![Reply::json(
+        200,
+        json!({ "object": "list", "results": [], "next_cursor": null, "has_more": false }),
+    )]);
+    let results = client(&server)
+        .search("")
+        .object(SearchObject::DataSource)
+        .sort(Direction::Descending)
+        .page_size(10)
+        .results()
+        .count();
+    assert_eq!(results, 0);
+    assert_eq!(
+        server.requests()[0].json(),
+        json!({
+            "page_size": 10,
+            "filter": { "property": "object", "value": "data_source" },
+            "sort": { "timestamp": "last_edited_time", "direction": "descending" }
+        })
+    );
+}
+
+#[test]
+fn rejects_a_bad_page_size_without_a_request() {
+    let server = MockServer::sequence(vec![]);
+    let notion = client(&server);
+    let mut results = notion.search("x").page_size(0).results();
+    assert!(matches!(results.next(), Some(Err(Error::InvalidInput(_)))));
+    assert!(results.next().is_none());
+    assert_eq!(server.hits(), 0);
+}
@@ -0,0 +1,256 @@
+#![cfg(feature = "notion")]
+
+mod common;
+
+use common::{MockServer, Reply};
+use serde_json::{json, Value};
+use swivel::notion::{Icon, NewPage, NotionClient, Page, PageUpdate, PropertyValue, SelectOption};
+use swivel::Database;
+
+const PAGE_ID: &str = "275a1865-b187-807a-adea-ebaf36fb49b0";
+
+fn fixture() -> Value {
+    serde_json::from_str(include_str!("fixtures/page.json")).expect("page fixture")
+}
+
+/// Serves the fixture page for every request, recording what was sent.
+fn server() -> MockServer {
+    let page = fixture();
+    MockServer::start(move |_| Reply::json(200, page.clone()))
+}
+
+fn client(server: &MockServer) -> NotionClient {
+    NotionClient::new("secret")
+        .with_base_url(server.url())
+        .with_rate_limiter(None)
+}
+
+fn sent(server: &MockServer, method: &str) -> Vec<Value> {
+    server
+        .requests()
+        .into_iter()
+        .filter(|r| r.method == method)
+        .map(|r| r.json())
+        .collect()
+}
+
+#[test]
+fn put_patches_only_what_changed() {
+    let server = server();
+    let notion = client(&server);
+    let mut page = notion.get_page(PAGE_ID).unwrap();
+    match page.prop_mut("Status") {
+        Some(PropertyValue::Status(status)) => *status = Some(SelectOption::named("Done")),
+        other => panic!("{other:?}"),
+    }
+    match page.prop_mut("Done") {
+        Some(PropertyValue::Checkbox(done)) => *done = true,
+        other => panic!("{other:?}"),
+    }
+    page.icon = Some(Icon::Emoji("✅".into()));
+    notion.put(page).unwrap();
+
+    let patches = sent(&server, "PATCH");
+    assert_eq!(patches.len(), 1);
+    assert_eq!(
+        patches[0],
+        json!({
+            "properties": {
+                "Done": { "type": "checkbox", "checkbox": true },
+                "Status": { "type": "status", "status": { "name": "Done" } }
+            },
+            "icon": { "type": "emoji", "emoji": "✅" }
+        })
+    );
+    let paths: Vec<String> = server.requests().into_iter().map(|r| r.path).collect();
+    assert_eq!(
+        paths,
+        [
+            format!("/pages/{PAGE_ID}"),
+            format!("/pages/{PAGE_ID}"),
+            format!("/pages/{PAGE_ID}")
+        ]
+    );
+}
+
+#[test]
+fn put_of_an_unchanged_page_sends_nothing() {
+    let server = server();
+    let notion = client(&server);
+    let page = notion.get_page(PAGE_ID).unwrap();
+    notion.put(page).unwrap();
+    assert!(sent(&server, "PATCH").is_empty());
+}
+
+#[test]
+fn writes_round_trip_values_as_read() {
+    let server = server();
+    let notion = client(&server);
+    let current = notion.get_page(PAGE_ID).unwrap();
+    let mut page = current.clone();
+    // Swap two values of the same types: what is sent is exactly the JSON
+    // Notion sent for the other property.
+    let notes = page.properties["Notes"].value.clone();
+    let name = page.properties["Name"].value.clone();
+    page.properties.get_mut("Notes").unwrap().value = match name {
+        PropertyValue::Title(runs) => PropertyValue::RichText(runs),
+        other => panic!("{other:?}"),
+    };
+    page.properties.get_mut("Name").unwrap().value = match notes {
+        PropertyValue::RichText(runs) => PropertyValue::Title(runs),
+        other => panic!("{other:?}"),
+    };
+    let update = PageUpdate::diff(&current, &page);
+    assert_eq!(update.properties.len(), 2);
+    notion.update_page(PAGE_ID, &update).unwrap();
+
+    let fixture = fixture();
+    let patch = &sent(&server, "PATCH")[0];
+    assert_eq!(
+        patch["properties"]["Name"]["title"],
+        fixture["properties"]["Notes"]["rich_text"]
+    );
+    assert_eq!(
+        patch["properties"]["Notes"]["rich_text"],
+        fixture["properties"]["Name"]["title"]
+    );
+}
+
+#[test]
+fn put_without_an_id_creates_the_page() {
+    let server = server();
+    let notion = client(&server);
+    let mut page: Page = serde_json::from_value(fixture()).unwrap();
+    page.id.clear();
+    notion.put(page).unwrap();
+
+    let created = &sent(&server, "POST")[0];
+    assert_eq!(
+        created["parent"],
+        json!({ "type": "data_source_id", "data_source_id": "b5ad9a34-4e2f-4f8e-9c3c-1b0e6d4f7a21" })
+    );
+    assert_eq!(created["icon"], json!({ "type": "emoji", "emoji": "🚀" }));
+    let properties = created["properties"].as_object().unwrap();
+    assert!(properties.contains_key("Name"));
+    assert!(properties.contains_key("Status"));
+    // Values Notion computes are not sent.
+    for computed in ["Days left", "Total", "Ticket", "Created", "Editor"] {
+        assert!(!properties.contains_key(computed), "{computed}");
+    }
+}
+
+#[test]
+fn new_pages_leave_out_values_the_api_cannot_set() {
+    let mut json = fixture();
+    json["properties"]["Approve"] = json!({ "id": "b1", "type": "button", "button": {} });
+    json["properties"]["Links"] = json!({
+        "id": "f2",
+        "type": "files",
+        "files": [{ "name": "site", "type": "external", "external": { "url": "https://example.com" } }]
+    });
+    json["icon"] = json!({ "type": "file", "file": { "url": "https://files.example.com/i.png" } });
+    let page: Page = serde_json::from_value(json).unwrap();
+    let new = NewPage::from(&page);
+
+    // Buttons and places are unknown to swivel, verification is set in the
+    // app, and Notion-hosted attachments can only be uploaded anew.
+    for name in [
+        "Approve",
+        "Place",
+        "Verified",
+        "Attachments",
+        "Days left",
+        "Editor",
+    ] {
+        assert!(!new.properties.contains_key(name), "{name}");
+    }
+    assert!(new.properties.contains_key("Links"));
+    assert!(new.properties.contains_key("Name"));
+    assert_eq!(new.icon, None);
+    assert!(new.cover.is_some(), "an external cover is kept");
+}
+
+#[test]
+fn archives_restores_and_clears_the_cover() {
+    let server = server();
+    let notion = client(&server);
+    notion.archive_page(PAGE_ID).unwrap();
+    notion.restore_page(PAGE_ID).unwrap();
+    notion
+        .update_page(PAGE_ID, &PageUpdate::new().with_cover(None))
+        .unwrap();
+    assert_eq!(
+        sent(&server, "PATCH"),
+        [
+            json!({ "in_trash": true }),
+            json!({ "in_trash": false }),
+            json!({ "cover": null })
+        ]
+    );
+}
+
+fn swivel(server: &MockServer, args: &[&str]) -> std::process::Output {
+    std::process::Command::new(env!("CARGO_BIN_EXE_swivel"))
+        .args(args)
+        .env_clear()
+        .env("NOTION_API_KEY", "secret")
+        .env("NOTION_BASE_URL", server.url())
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn the_cli_creates_pages_without_an_id_and_updates_those_with_one() {
+    let server = server();
+    let new = json!({
+        "parent": { "type": "page_id", "page_id": PAGE_ID },
+        "properties": {
+            "title": { "type": "title", "title": [
+                { "type": "text", "text": { "content": "Launch plan" } }
+            ] }
+        },
+        "icon": { "type": "emoji", "emoji": "🚀" }
+    });
+    let out = swivel(&server, &["notion", "put", &new.to_string()]);
+    assert_eq!(
+        out.status.code(),
+        Some(0),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+
+    // A page as `notion get` prints it goes back without its read-only values.
+    let out = swivel(&server, &["notion", "put", &fixture().to_string()]);
+    assert_eq!(
+        out.status.code(),
+        Some(0),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+
+    let requests = server.requests();
+    assert_eq!(
+        (requests[0].method.as_str(), requests[0].path.as_str()),
+        ("POST", "/pages")
+    );
+    let created = requests[0].json();
+    assert_eq!(created["parent"]["page_id"], PAGE_ID);
+    assert_eq!(
+        created["properties"]["title"]["title"][0]["text"]["content"],
+        "Launch plan"
+    );
+    assert_eq!(created["icon"]["emoji"], "🚀");
+    assert_eq!(requests[1].method, "PATCH");
+    assert_eq!(requests[1].path, format!("/pages/{PAGE_ID}"));
+    let properties = requests[1].json()["properties"].clone();
+    for name in ["Days left", "Created", "Verified", "Attachments"] {
+        assert!(properties.get(name).is_none(), "{name}");
+    }
+    assert!(properties.get("Name").is_some());
+
+    // Without an id there must be a parent to create the page under.
+    let out = swivel(&server, &["notion", "put", r#"{"properties":{}}"#]);
+    assert_eq!(out.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("needs a `parent`"));
+    assert_eq!(server.hits(), 2);
+}
@@ -0,0 +1,64 @@
+#![cfg(feature = "sqlite")]
+
+use rusqlite::Connection;
+use serde_json::json;
+use swivel::sqlite::SqliteClient;
+use swivel::{Database, Error};
+
+fn todos() -> SqliteClient {
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute_batch(
+        "CREATE TABLE todos (id TEXT PRIMARY KEY, title TEXT, done BOOLEAN, score REAL, tags TEXT, priority INTEGER)",
+    )
+    .unwrap();
+    SqliteClient::from_connection(conn, "todos")
+}
+
+#[test]
+fn put_then_get_round_trips_a_row() {
+    let db = todos();
+    let row = json!({
+        "id": "1",
+        "title": "Ship it",
+        "done": true,
+        "score": 2.5,
+        "tags": null,
+        "priority": 3
+    });
+    db.put(row.clone()).unwrap();
+    assert_eq!(db.get("1").unwrap(), row);
+}
+
+#[test]
+fn put_upserts_on_the_key() {
+    let db = todos();
+    db.put(json!({ "id": "1", "title": "Draft", "done": false }))
+        .unwrap();
+    db.put(json!({ "id": "1", "title": "Final", "tags": ["a", "b"] }))
+        .unwrap();
+
+    // Columns the second record leaves out keep their value; arrays are JSON text.
+    let row = db.get("1").unwrap();
+    assert_eq!(row["title"], "Final");
+    assert_eq!(row["done"], false);
+    assert_eq!(row["tags"], "[\"a\",\"b\"]");
+}
+
+#[test]
+fn booleans_stay_numbers_outside_boolean_columns() {
+    let db = todos();
+    db.put(json!({ "id": "1", "priority": true })).unwrap();
+    assert_eq!(db.get("1").unwrap()["priority"], 1);
+}
+
+#[test]
+fn missing_rows_and_bad_records_are_typed_errors() {
+    let db = todos();
+    let err = db.get("nope").unwrap_err();
+    assert!(matches!(err, Error::ObjectNotFound { .. }), "{err}");
+
+    let err = db.put(json!({ "title": "no id" })).unwrap_err();
+    assert!(matches!(err, Error::InvalidInput(_)), "{err}");
+    let err = db.put(json!(["not", "an", "object"])).unwrap_err();
+    assert!(matches!(err, Error::InvalidInput(_)), "{err}");
+}
@@ -0,0 +1,227 @@
+#![cfg(feature = "supabase")]
+
+mod common;
+
+use std::time::Duration;
+
+use common::{MockServer, Reply};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use swivel::supabase::SupabaseClient;
+use swivel::{Database, Error, RetryPolicy};
+
+fn client(server: &MockServer) -> SupabaseClient {
+    SupabaseClient::new(format!("{}/", server.url()), "anon-key", "tasks")
+}
+
+/// A `tasks` table with one row, answering the way PostgREST does.
+fn postgrest() -> MockServer {
+    MockServer::start(|req| match (req.method.as_str(), req.path.as_str()) {
+        ("GET", "/rest/v1/tasks?id=eq.42") => {
+            Reply::json(200, json!({ "id": 42, "title": "Ship it", "done": false }))
+        }
+        ("GET", _) => Reply::json(
+            406,
+            json!({
+                "code": "PGRST116",
+                "message": "JSON object requested, multiple (or no) rows returned",
+                "details": "The result contains 0 rows",
+                "hint": null
+            }),
+        ),
+        ("POST", _) => {
+            let mut row = req.json();
+            row.as_object_mut()
+                .unwrap()
+                .entry("id")
+                .or_insert(json!(43));
+            Reply::json(201, json!([row]))
+        }
+        _ => Reply::json(405, json!({})),
+    })
+}
+
+#[test]
+fn get_fetches_one_object_by_key() {
+    let server = postgrest();
+    let row = client(&server).get("42").unwrap();
+    assert_eq!(row, json!({ "id": 42, "title": "Ship it", "done": false }));
+
+    let requests = server.requests();
+    assert_eq!(
+        requests[0].header("accept"),
+        Some("application/vnd.pgrst.object+json")
+    );
+    assert_eq!(requests[0].header("apikey"), Some("anon-key"));
+    assert_eq!(requests[0].header("authorization"), Some("Bearer anon-key"));
+
+    // No row is a typed error, and ids are URL-encoded.
+    let err = client(&server).get("a b&c=d").unwrap_err();
+    assert!(matches!(err, Error::ObjectNotFound { .. }), "{err}");
+    assert!(err.to_string().contains("0 rows"), "{err}");
+    assert_eq!(
+        server.requests()[1].path,
+        "/rest/v1/tasks?id=eq.a%20b%26c%3Dd"
+    );
+}
+
+#[test]
+fn put_upserts_on_the_key_column() {
+    let server = postgrest();
+    let tasks = client(&server).with_key("slug");
+    tasks
+        .put(json!({ "slug": "launch", "title": "Launch" }))
+        .unwrap();
+
+    let req = &server.requests()[0];
+    assert_eq!(req.method, "POST");
+    assert_eq!(req.path, "/rest/v1/tasks?on_conflict=slug");
+    assert_eq!(
+        req.header("prefer"),
+        Some("resolution=merge-duplicates,return=representation")
+    );
+    assert_eq!(req.json(), json!({ "slug": "launch", "title": "Launch" }));
+
+    let err = tasks.put(json!([1, 2])).unwrap_err();
+    assert!(matches!(err, Error::InvalidInput(_)), "{err}");
+    assert_eq!(server.hits(), 1, "nothing is sent for a non-object");
+}
+
+#[test]
+fn only_keyed_rows_are_retried() {
+    let unavailable = || Reply::json(503, json!({ "message": "upstream unavailable" }));
+    let stored = || Reply::json(201, json!([{ "id": 7, "title": "Ship it" }]));
+    let server = MockServer::sequence(vec![unavailable(), stored(), unavailable(), stored()]);
+    let tasks = client(&server).with_retry_policy(
+        RetryPolicy::default()
+            .with_base_delay(Duration::from_millis(1))
+            .with_jitter(false),
+    );
+
+    tasks.put(json!({ "id": 7, "title": "Ship it" })).unwrap();
+    assert_eq!(server.hits(), 2);
+
+    // Without a key the first attempt may have inserted a row already.
+    let err = tasks
+        .put(json!({ "id": null, "title": "Ship it" }))
+        .unwrap_err();
+    assert!(matches!(err, Error::Server { status: 503, .. }), "{err:?}");
+    assert_eq!(server.hits(), 3);
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Task {
+    id: Option<i64>,
+    title: String,
+    #[serde(default)]
+    done: bool,
+}
+
+#[test]
+fn rows_can_be_any_serde_type() {
+    let server = postgrest();
+    let tasks = client(&server);
+    let task: Task = tasks.fetch("42").unwrap();
+    assert_eq!(task.title, "Ship it");
+
+    // A row without an id leaves it to the column default.
+    let new = Task {
+        id: None,
+        title: "Write docs".into(),
+        done: false,
+    };
+    let stored = tasks.upsert(&new).unwrap();
+    assert_eq!(stored.id, Some(43));
+    assert_eq!(
+        server.requests()[1].json(),
+        json!({ "title": "Write docs", "done": false })
+    );
+}
+
+#[cfg(feature = "derive")]
+#[test]
+fn records_use_supabase_field_names() {
+    #[derive(Debug, swivel::Record)]
+    struct Todo {
+        #[swivel(id, supabase = "todo_id")]
+        id: String,
+        #[swivel(supabase = "label")]
+        title: String,
+    }
+
+    let server = MockServer::start(|req| {
+        let row = json!({ "todo_id": "7", "label": "Ship it" });
+        match req.method.as_str() {
+            "GET" => Reply::json(200, row),
+            _ => Reply::json(201, json!([row])),
+        }
+    });
+    let todos = client(&server).records::<Todo>();
+    let todo = todos.get("7").unwrap();
+    assert_eq!(todo.title, "Ship it");
+    todos.put(todo).unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests[0].path, "/rest/v1/tasks?todo_id=eq.7");
+    assert_eq!(requests[1].path, "/rest/v1/tasks?on_conflict=todo_id");
+    assert_eq!(
+        requests[1].json(),
+        json!({ "todo_id": "7", "label": "Ship it" })
+    );
+}
+
+#[test]
+fn postgrest_errors_are_typed() {
+    let body = |status: u16, code: &str, message: &str| {
+        Reply::json(
+            status,
+            json!({ "code": code, "message": message, "details": null, "hint": null }),
+        )
+    };
+    let server = MockServer::sequence(vec![
+        body(
+            409,
+            "23505",
+            "duplicate key value violates unique constraint \"tasks_pkey\"",
+        ),
+        body(
+            409,
+            "23503",
+            "insert or update on table \"tasks\" violates foreign key constraint \"tasks_owner_fkey\"",
+        ),
+        body(
+            403,
+            "42501",
+            "new row violates row-level security policy for table \"tasks\"",
+        ),
+        body(401, "PGRST301", "JWT expired"),
+        body(400, "22P02", "invalid input syntax for type bigint: \"x\""),
+    ]);
+    let tasks = client(&server);
+    let row = json!({ "id": 1 });
+
+    match tasks.put(row.clone()).unwrap_err() {
+        Error::UniqueViolation { constraint, .. } => {
+            assert_eq!(constraint.as_deref(), Some("tasks_pkey"))
+        }
+        other => panic!("{other:?}"),
+    }
+    let err = tasks.put(row.clone()).unwrap_err();
+    assert!(
+        matches!(&err, Error::ForeignKeyViolation { constraint: Some(c), .. } if c == "tasks_owner_fkey"),
+        "{err:?}"
+    );
+    assert_eq!(
+        err.to_string(),
+        "foreign key violation on tasks_owner_fkey: insert or update on table \"tasks\" \
+         violates foreign key constraint \"tasks_owner_fkey\""
+    );
+    let err = tasks.put(row.clone()).unwrap_err();
+    assert!(matches!(err, Error::PermissionDenied { .. }), "{err:?}");
+    let err = tasks.get("1").unwrap_err();
+    assert!(matches!(err, Error::Unauthorized { .. }), "{err:?}");
+    match tasks.get("x").unwrap_err() {
+        Error::Validation { code, .. } => assert_eq!(code, "22P02"),
+        other => panic!("{other:?}"),
+    }
+}
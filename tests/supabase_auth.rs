@@ -0,0 +1,257 @@
+#![cfg(feature = "supabase")]
+
+mod common;
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use common::{MockServer, Reply};
+use serde_json::{json, Value};
+use swivel::supabase::{AuthClient, FileStore, MemoryStore, Session, SessionStore, SupabaseClient};
+use swivel::{Database, Error};
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+fn session(generation: u32, expires_in: u64) -> Value {
+    json!({
+        "access_token": format!("jwt-{generation}"),
+        "refresh_token": format!("refresh-{generation}"),
+        "token_type": "bearer",
+        "expires_in": expires_in,
+        "expires_at": now() + expires_in,
+        "user": { "id": "u1", "email": "ann@example.com", "role": "authenticated" }
+    })
+}
+
+/// Supabase Auth and PostgREST on one host. Each refresh token works once
+/// and rotates; access tokens last `lifetime` seconds.
+fn supabase(lifetime: u64) -> MockServer {
+    let generation = AtomicU32::new(1);
+    MockServer::start(move |req| {
+        let current = generation.load(Ordering::SeqCst);
+        match req.path.as_str() {
+            "/auth/v1/token?grant_type=password" => {
+                let body = req.json();
+                if body["password"] == "hunter2" {
+                    Reply::json(200, session(current, lifetime))
+                } else {
+                    Reply::json(
+                        400,
+                        json!({
+                            "code": 400,
+                            "error_code": "invalid_credentials",
+                            "msg": "Invalid login credentials"
+                        }),
+                    )
+                }
+            }
+            "/auth/v1/token?grant_type=refresh_token" => {
+                if req.json()["refresh_token"] == format!("refresh-{current}") {
+                    generation.store(current + 1, Ordering::SeqCst);
+                    Reply::json(200, session(current + 1, lifetime))
+                } else {
+                    Reply::json(
+                        400,
+                        json!({
+                            "error": "invalid_grant",
+                            "error_description": "Invalid Refresh Token: Already Used"
+                        }),
+                    )
+                }
+            }
+            "/auth/v1/otp" => Reply::json(200, json!({})),
+            "/auth/v1/verify" if req.json()["token"] == "123456" => {
+                Reply::json(200, session(current, lifetime))
+            }
+            "/auth/v1/verify" => Reply::json(
+                403,
+                json!({ "code": 403, "error_code": "otp_expired", "msg": "Token has expired or is invalid" }),
+            ),
+            "/auth/v1/logout?scope=global" => Reply {
+                status: 204,
+                headers: Vec::new(),
+                body: String::new(),
+            },
+            _ => Reply::json(200, json!({ "id": 1, "owner": "u1" })),
+        }
+    })
+}
+
+#[test]
+fn requests_run_as_the_signed_in_user() {
+    let server = supabase(3600);
+    let auth = AuthClient::new(server.url(), "anon-key");
+    let tasks = SupabaseClient::new(server.url(), "anon-key", "tasks").with_auth(auth.clone());
+
+    // Signed out, nothing is sent in the user's name.
+    let err = tasks.get("1").unwrap_err();
+    assert!(matches!(err, Error::Unauthorized { .. }), "{err}");
+    assert_eq!(server.hits(), 0);
+
+    let err = auth
+        .sign_in_with_password("ann@example.com", "wrong")
+        .unwrap_err();
+    assert!(matches!(err, Error::Unauthorized { .. }), "{err}");
+    assert!(err.to_string().contains("Invalid login credentials"));
+
+    let session = auth
+        .sign_in_with_password("ann@example.com", "hunter2")
+        .unwrap();
+    assert_eq!(session.user.unwrap().id, "u1");
+    tasks.get("1").unwrap();
+
+    let requests = server.requests();
+    assert_eq!(
+        requests[1].json(),
+        json!({ "email": "ann@example.com", "password": "hunter2" })
+    );
+    assert_eq!(requests[1].header("apikey"), Some("anon-key"));
+    let row = &requests[2];
+    assert_eq!(row.path, "/rest/v1/tasks?id=eq.1");
+    assert_eq!(row.header("authorization"), Some("Bearer jwt-1"));
+    assert_eq!(row.header("apikey"), Some("anon-key"));
+}
+
+#[test]
+fn sessions_refresh_shortly_before_expiry() {
+    // Tokens that last 30 seconds are always within the one-minute margin.
+    let server = supabase(30);
+    let auth = AuthClient::new(server.url(), "anon-key");
+    auth.sign_in_with_password("ann@example.com", "hunter2")
+        .unwrap();
+    let tasks = SupabaseClient::new(server.url(), "anon-key", "tasks").with_auth(auth.clone());
+    tasks.get("1").unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests[1].path, "/auth/v1/token?grant_type=refresh_token");
+    assert_eq!(requests[1].json(), json!({ "refresh_token": "refresh-1" }));
+    assert_eq!(requests[2].header("authorization"), Some("Bearer jwt-2"));
+
+    // Further from expiry than the margin, the session is used as it is.
+    let auth = auth.with_refresh_margin(std::time::Duration::from_secs(5));
+    assert_eq!(auth.access_token().unwrap().as_deref(), Some("jwt-2"));
+    assert_eq!(server.hits(), 3);
+
+    // A forced refresh rotates the refresh token again.
+    assert_eq!(auth.refresh().unwrap().refresh_token, "refresh-3");
+}
+
+#[test]
+fn a_spent_refresh_token_signs_the_user_out() {
+    let server = supabase(3600);
+    let auth = AuthClient::new(server.url(), "anon-key");
+    auth.sign_in_with_password("ann@example.com", "hunter2")
+        .unwrap();
+    let stale: Session = auth.session().unwrap().unwrap();
+    auth.refresh().unwrap();
+
+    // Another process still holds the old tokens and tries to use them.
+    let other = AuthClient::new(server.url(), "anon-key");
+    other.set_session(stale).unwrap();
+    let err = other.refresh().unwrap_err();
+    assert!(matches!(err, Error::Unauthorized { .. }), "{err}");
+    assert!(err.to_string().contains("Already Used"), "{err}");
+    assert!(other.session().unwrap().is_none());
+}
+
+#[test]
+fn one_time_codes_sign_in_and_sign_out_revokes() {
+    let server = supabase(3600);
+    let store =
+        std::env::temp_dir().join(format!("swivel-auth-{}/session.json", std::process::id()));
+    let auth = AuthClient::new(server.url(), "anon-key").with_store(FileStore::new(&store));
+    auth.send_otp("ann@example.com").unwrap();
+    let err = auth.verify_otp("ann@example.com", "000000").unwrap_err();
+    assert!(matches!(err, Error::Unauthorized { .. }), "{err}");
+    auth.verify_otp("ann@example.com", "123456").unwrap();
+
+    // The session outlives the client.
+    let reopened = FileStore::new(&store).load().unwrap().unwrap();
+    assert_eq!(reopened.access_token, "jwt-1");
+    assert!(
+        !format!("{reopened:?}").contains("jwt-1"),
+        "tokens stay out of logs"
+    );
+
+    auth.sign_out().unwrap();
+    assert!(!store.exists());
+    assert!(auth.session().unwrap().is_none());
+    auth.sign_out().unwrap();
+
+    let requests = server.requests();
+    assert_eq!(
+        requests[0].json(),
+        json!({ "email": "ann@example.com", "create_user": false })
+    );
+    assert_eq!(
+        requests[2].json(),
+        json!({ "type": "email", "email": "ann@example.com", "token": "123456" })
+    );
+    assert_eq!(requests[3].path, "/auth/v1/logout?scope=global");
+    assert_eq!(requests[3].header("authorization"), Some("Bearer jwt-1"));
+    assert_eq!(server.hits(), 4, "signing out twice sends one request");
+
+    // A memory store starts empty.
+    assert!(MemoryStore::new().load().unwrap().is_none());
+    let _ = std::fs::remove_dir_all(store.parent().unwrap());
+}
+
+/// Run the CLI against `server` with the session kept in `store`.
+fn swivel(server: &MockServer, store: &std::path::Path, args: &[&str]) -> std::process::Output {
+    std::process::Command::new(env!("CARGO_BIN_EXE_swivel"))
+        .args(args)
+        .env_clear()
+        .env("SUPABASE_URL", server.url())
+        .env("SUPABASE_KEY", "service-role-key")
+        .env("SUPABASE_SESSION_FILE", store)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn the_cli_never_falls_back_to_the_project_key_for_a_broken_session() {
+    let server = supabase(3600);
+    let store = std::env::temp_dir().join(format!("swivel-cli-{}.json", std::process::id()));
+
+    // The stored refresh token has been spent elsewhere.
+    let mut expired = session(7, 0);
+    expired["expires_at"] = json!(now() - 60);
+    std::fs::write(&store, expired.to_string()).unwrap();
+    let out = swivel(
+        &server,
+        &store,
+        &["supabase", "put", "tasks", r#"{"id":1}"#],
+    );
+    assert_eq!(
+        out.status.code(),
+        Some(4),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+
+    // An unreadable session file fails the same way as a refused one would.
+    std::fs::write(&store, "{ not json").unwrap();
+    let out = swivel(&server, &store, &["supabase", "get", "tasks", "1"]);
+    assert_ne!(out.status.code(), Some(0));
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].path, "/auth/v1/token?grant_type=refresh_token");
+    assert!(requests
+        .iter()
+        .all(|req| req.header("authorization") != Some("Bearer service-role-key")));
+
+    // Without a session, the project key is what there is.
+    std::fs::remove_file(&store).unwrap();
+    let out = swivel(&server, &store, &["supabase", "get", "tasks", "1"]);
+    assert_eq!(out.status.code(), Some(0));
+    assert_eq!(
+        server.requests()[1].header("authorization"),
+        Some("Bearer service-role-key")
+    );
+}
@@ -0,0 +1,166 @@
+#![cfg(feature = "supabase")]
+
+mod common;
+
+use common::{MockServer, Reply};
+use serde_json::{json, Value};
+use swivel::supabase::{Count, Filter, Order, SupabaseClient};
+use swivel::Error;
+
+fn client(url: &str) -> SupabaseClient {
+    SupabaseClient::new(url, "anon-key", "posts")
+}
+
+fn param(filter: Filter) -> String {
+    let (key, value) = filter.to_param();
+    format!("{key}={value}")
+}
+
+#[test]
+fn operators_render_in_postgrest_syntax() {
+    let col = || Filter::column("n");
+    let cases = [
+        (col().eq(1), "n=eq.1"),
+        (col().neq("a"), "n=neq.a"),
+        (col().gt(1.5), "n=gt.1.5"),
+        (col().gte(2), "n=gte.2"),
+        (col().lt(3), "n=lt.3"),
+        (col().lte(4), "n=lte.4"),
+        (col().like("*ab*"), "n=like.*ab*"),
+        (col().ilike("ab*"), "n=ilike.ab*"),
+        (col().is_null(), "n=is.null"),
+        (col().is_true(), "n=is.true"),
+        (col().is_false(), "n=is.false"),
+        (col().is_unknown(), "n=is.unknown"),
+        (col().in_([1, 2, 3]), "n=in.(1,2,3)"),
+        (col().in_(["a,b", "c"]), "n=in.(\"a,b\",c)"),
+        (col().contains(["x", "y"]), "n=cs.{x,y}"),
+        (col().contained_by(["x"]), "n=cd.{x}"),
+        (
+            col().overlaps(["x", "say \"hi\""]),
+            "n=ov.{x,\"say \\\"hi\\\"\"}",
+        ),
+        (col().fts("fat & cat"), "n=fts.fat & cat"),
+        (
+            col().config("english").plfts("fat cat"),
+            "n=plfts(english).fat cat",
+        ),
+        (col().phfts("fat cat"), "n=phfts.fat cat"),
+        (col().config("french").wfts("chat"), "n=wfts(french).chat"),
+        (col().op("sl", "[1,10)"), "n=sl.[1,10)"),
+        (!col().eq(1), "n=not.eq.1"),
+        (!col().in_(["a"]), "n=not.in.(a)"),
+        (!!col().eq(1), "n=eq.1"),
+    ];
+    for (filter, expected) in cases {
+        assert_eq!(param(filter), expected);
+    }
+}
+
+#[test]
+fn groups_nest_and_quote_their_values() {
+    let filter = Filter::column("grade")
+        .gte(90)
+        .or(Filter::column("student").eq("Smith, J."))
+        .or(Filter::all([
+            Filter::column("age").lt(12),
+            !Filter::column("tags").contains(["a", "b"]),
+        ]));
+    assert_eq!(
+        param(filter),
+        "or=(grade.gte.90,student.eq.\"Smith, J.\",and(age.lt.12,tags.not.cs.{a,b}))"
+    );
+
+    let negated = !Filter::any([
+        Filter::column("a").is_null(),
+        !Filter::all([Filter::column("b").eq(1), Filter::column("c").eq("")]),
+    ]);
+    assert_eq!(
+        param(negated),
+        "not.or=(a.is.null,not.and(b.eq.1,c.eq.\"\"))"
+    );
+}
+
+#[test]
+fn query_strings_are_url_encoded() {
+    let posts = client("http://localhost:54321");
+    let query = posts
+        .select("id,title,author:profiles!inner(name),comments(*)")
+        .filter(Filter::column("title").eq("R&D = 100% #1+"))
+        .filter(
+            Filter::column("body")
+                .config("english")
+                .wfts("café \"au lait\""),
+        )
+        .filter(Filter::column("tags").in_(["a b", "c,d"]))
+        .filter(Filter::column("author.name").neq("Ann"))
+        .filter(Filter::column("data->>kind").eq("post"))
+        .order(Order::desc("published_at").nulls_last())
+        .order(Order::asc("id").nulls_first())
+        .order(Order::asc("title"))
+        .limit(25)
+        .offset(50);
+    assert_eq!(
+        query.query_string(),
+        "select=id,title,author:profiles!inner(name),comments(*)\
+         &title=eq.R%26D%20%3D%20100%25%20%231%2B\
+         &body=wfts(english).caf%C3%A9%20%22au%20lait%22\
+         &tags=in.(%22a%20b%22,%22c,d%22)\
+         &author.name=neq.Ann\
+         &data-%3E%3Ekind=eq.post\
+         &order=published_at.desc.nullslast,id.asc.nullsfirst,title.asc\
+         &limit=25&offset=50"
+    );
+    assert_eq!(posts.select("*").query_string(), "select=*");
+}
+
+#[test]
+fn counts_and_ranges_go_in_headers() {
+    let server = MockServer::start(|req| {
+        let reply = Reply::json(206, json!([{ "id": 1 }, { "id": 2 }]));
+        match req.header("prefer") {
+            Some(_) => reply.header("Content-Range", "0-1/57"),
+            None => reply.header("Content-Range", "0-1/*"),
+        }
+    });
+    let posts = client(server.url());
+    let page = posts
+        .select("id")
+        .filter(Filter::column("draft").is_false())
+        .range(0, 1)
+        .count(Count::Exact)
+        .execute::<Value>()
+        .unwrap();
+    assert_eq!(page.rows, [json!({ "id": 1 }), json!({ "id": 2 })]);
+    assert_eq!(page.total, Some(57));
+
+    let req = &server.requests()[0];
+    assert_eq!(req.method, "GET");
+    assert_eq!(req.path, "/rest/v1/posts?select=id&draft=is.false");
+    assert_eq!(req.header("range"), Some("0-1"));
+    assert_eq!(req.header("range-unit"), Some("items"));
+    assert_eq!(req.header("prefer"), Some("count=exact"));
+
+    for (count, header) in [
+        (Count::Planned, "count=planned"),
+        (Count::Estimated, "count=estimated"),
+    ] {
+        posts.select("id").count(count).execute::<Value>().unwrap();
+        assert_eq!(
+            server.requests().last().unwrap().header("prefer"),
+            Some(header)
+        );
+    }
+
+    // Without a count, the total is unknown.
+    let page = posts.select("id").range(0, 1).execute::<Value>().unwrap();
+    assert_eq!(page.total, None);
+
+    let err = posts
+        .select("id")
+        .range(5, 1)
+        .execute::<Value>()
+        .unwrap_err();
+    assert!(matches!(err, Error::InvalidInput(_)), "{err}");
+    assert_eq!(server.hits(), 4);
+}
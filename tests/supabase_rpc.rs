@@ -0,0 +1,151 @@
+#![cfg(feature = "supabase")]
+
+mod common;
+
+use common::{MockServer, Reply};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use swivel::supabase::{Count, Filter, Order, SupabaseClient};
+use swivel::Error;
+
+fn client(server: &MockServer) -> SupabaseClient {
+    SupabaseClient::new(server.url(), "anon-key", "tasks")
+}
+
+#[derive(Serialize)]
+struct AddArgs {
+    a: i64,
+    b: i64,
+}
+
+#[test]
+fn post_calls_return_scalars_and_nothing() {
+    let server = MockServer::start(|req| match req.path.as_str() {
+        "/rest/v1/rpc/add" => {
+            let args = req.json();
+            Reply::json(
+                200,
+                json!(args["a"].as_i64().unwrap() + args["b"].as_i64().unwrap()),
+            )
+        }
+        _ => Reply {
+            status: 204,
+            headers: Vec::new(),
+            body: String::new(),
+        },
+    });
+    let db = client(&server);
+    let sum = db
+        .rpc::<AddArgs, i64>("add", AddArgs { a: 2, b: 3 })
+        .unwrap();
+    assert_eq!(sum, 5);
+    db.rpc::<_, ()>("archive_done_tasks", ()).unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests[0].method, "POST");
+    assert_eq!(requests[0].json(), json!({ "a": 2, "b": 3 }));
+    assert_eq!(requests[0].header("apikey"), Some("anon-key"));
+    assert_eq!(requests[1].path, "/rest/v1/rpc/archive_done_tasks");
+    assert_eq!(requests[1].json(), json!({}));
+
+    // Arguments are named, so they must be an object.
+    let err = db.rpc::<_, Value>("add", [1, 2]).unwrap_err();
+    assert!(matches!(err, Error::InvalidInput(_)), "{err}");
+    assert_eq!(server.hits(), 2);
+}
+
+#[test]
+fn get_calls_put_arguments_in_the_query_string() {
+    let server = MockServer::start(|_| Reply::json(200, json!(["a", "b"])));
+    let db = client(&server);
+    let slugs: Vec<String> = db
+        .rpc_get(
+            "slugs",
+            json!({
+                "prefix": "Q3 & Q4",
+                "tags": ["a b", "c"],
+                "max": 10,
+                "since": null
+            }),
+        )
+        .unwrap();
+    assert_eq!(slugs, ["a", "b"]);
+
+    let req = &server.requests()[0];
+    assert_eq!(req.method, "GET");
+    assert_eq!(
+        req.path,
+        "/rest/v1/rpc/slugs?max=10&prefix=Q3%20%26%20Q4&tags=%7B%22a%20b%22,c%7D"
+    );
+    assert_eq!(req.body, "");
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Task {
+    id: i64,
+    title: String,
+}
+
+#[test]
+fn set_returning_functions_filter_and_order_their_rows() {
+    let server = MockServer::start(|_| {
+        let rows = json!([{ "id": 1, "title": "Ship it" }]);
+        Reply::json(200, rows).header("Content-Range", "0-0/12")
+    });
+    let db = client(&server);
+    let page = db
+        .call("tasks_for", json!({ "owner": "u1" }))
+        .select("id,title")
+        .filter(Filter::column("done").is_false())
+        .order(Order::asc("due").nulls_last())
+        .range(0, 0)
+        .count(Count::Exact)
+        .execute::<Task>()
+        .unwrap();
+    assert_eq!(
+        page.rows,
+        [Task {
+            id: 1,
+            title: "Ship it".into()
+        }]
+    );
+    assert_eq!(page.total, Some(12));
+
+    let req = &server.requests()[0];
+    assert_eq!(req.method, "POST");
+    assert_eq!(
+        req.path,
+        "/rest/v1/rpc/tasks_for?select=id,title&done=is.false&order=due.asc.nullslast"
+    );
+    assert_eq!(req.json(), json!({ "owner": "u1" }));
+    assert_eq!(req.header("range"), Some("0-0"));
+
+    // The same by GET: arguments first, then the row filters.
+    db.call("tasks_for", json!({ "owner": "u1" }))
+        .immutable()
+        .filter(Filter::column("done").is_false())
+        .value::<Vec<Task>>()
+        .unwrap();
+    assert_eq!(
+        server.requests()[1].path,
+        "/rest/v1/rpc/tasks_for?owner=u1&done=is.false"
+    );
+}
+
+#[test]
+fn project_clients_call_functions_without_a_table() {
+    let server = MockServer::start(|_| Reply::json(200, json!(5)));
+    let db = SupabaseClient::project(server.url(), "anon-key");
+    let sum: i64 = db.rpc("add", AddArgs { a: 2, b: 3 }).unwrap();
+    assert_eq!(sum, 5);
+    assert_eq!(server.requests()[0].path, "/rest/v1/rpc/add");
+
+    let err = db.select("*").execute::<Value>().unwrap_err();
+    assert!(matches!(err, Error::Config(_)), "{err}");
+    let err = db.upsert(&json!({ "id": 1 })).unwrap_err();
+    assert!(matches!(err, Error::Config(_)), "{err}");
+    assert_eq!(server.hits(), 1);
+
+    db.with_table("tasks").fetch::<Value>("1").unwrap();
+    assert_eq!(server.requests()[1].path, "/rest/v1/tasks?id=eq.1");
+}
@@ -0,0 +1,91 @@
+#![cfg(feature = "supabase")]
+
+mod common;
+
+use common::{MockServer, Reply};
+use serde_json::json;
+use swivel::supabase::{AuthClient, Session, StorageClient};
+use swivel::Error;
+
+/// A bucket holding `ann/notes.txt`, answering the way Supabase Storage
+/// does, including its 400 with a 404 `statusCode` for a missing object.
+fn storage() -> MockServer {
+    MockServer::start(|req| match (req.method.as_str(), req.path.as_str()) {
+        ("POST", "/storage/v1/object/docs/ann/q3%20plan.md") => {
+            Reply::json(200, json!({ "Key": "docs/ann/q3 plan.md" }))
+        }
+        ("GET", "/storage/v1/object/docs/ann/notes.txt") => Reply {
+            status: 200,
+            headers: vec![("Content-Type".into(), "text/plain".into())],
+            body: "remember the milk".into(),
+        },
+        ("GET", _) => Reply::json(
+            400,
+            json!({ "statusCode": "404", "error": "not_found", "message": "Object not found" }),
+        ),
+        ("DELETE", "/storage/v1/object/docs") => Reply::json(200, json!([])),
+        _ => Reply::json(
+            400,
+            json!({
+                "statusCode": "403",
+                "error": "Unauthorized",
+                "message": "new row violates row-level security policy"
+            }),
+        ),
+    })
+}
+
+#[test]
+fn objects_upload_download_and_remove() {
+    let server = storage();
+    let docs = StorageClient::new(server.url(), "anon-key", "docs");
+    docs.upload("ann/q3 plan.md", "# Q3", "text/markdown")
+        .unwrap();
+    assert_eq!(
+        docs.download("ann/notes.txt").unwrap(),
+        b"remember the milk"
+    );
+    docs.remove(&["ann/notes.txt", "ann/old.txt"]).unwrap();
+
+    let requests = server.requests();
+    assert_eq!(requests[0].body, "# Q3");
+    assert_eq!(requests[0].header("content-type"), Some("text/markdown"));
+    assert_eq!(requests[0].header("x-upsert"), Some("true"));
+    assert_eq!(requests[0].header("authorization"), Some("Bearer anon-key"));
+    assert_eq!(
+        requests[2].json(),
+        json!({ "prefixes": ["ann/notes.txt", "ann/old.txt"] })
+    );
+
+    let err = docs.download("ann/missing.txt").unwrap_err();
+    assert!(matches!(err, Error::ObjectNotFound { .. }), "{err}");
+    assert!(err.to_string().contains("Object not found"), "{err}");
+    let err = docs.upload("bob/x.txt", "x", "text/plain").unwrap_err();
+    assert!(matches!(err, Error::PermissionDenied { .. }), "{err}");
+}
+
+#[test]
+fn objects_are_read_as_the_signed_in_user() {
+    let server = storage();
+    let auth = AuthClient::new(server.url(), "anon-key");
+    let docs = StorageClient::new(server.url(), "anon-key", "docs").with_auth(auth.clone());
+
+    // Signed out, nothing is sent in the user's name.
+    let err = docs.download("ann/notes.txt").unwrap_err();
+    assert!(matches!(err, Error::Unauthorized { .. }), "{err}");
+    assert_eq!(server.hits(), 0);
+
+    let session: Session = serde_json::from_value(json!({
+        "access_token": "jwt-1",
+        "refresh_token": "refresh-1",
+        "token_type": "bearer",
+        "expires_in": 3600,
+        "expires_at": u64::MAX / 2
+    }))
+    .unwrap();
+    auth.set_session(session).unwrap();
+    docs.download("ann/notes.txt").unwrap();
+    let req = &server.requests()[0];
+    assert_eq!(req.header("authorization"), Some("Bearer jwt-1"));
+    assert_eq!(req.header("apikey"), Some("anon-key"));
+}
@@ -0,0 +1,13 @@
+use swivel::Record;
+
+#[derive(Record)]
+struct Task {
+    #[swivel(id)]
+    id: String,
+    #[swivel(notion = "Due")]
+    due: Option<String>,
+    #[swivel(notion = "Due")]
+    deadline: Option<String>,
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use swivel::Record;
+
+#[derive(Record)]
+struct Task {
+    title: String,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use swivel::Record;
+
+#[derive(Record)]
+struct Task {
+    #[swivel(id)]
+    id: String,
+    #[swivel(postgres = due_date)]
+    due: Option<String>,
+}
+
+fn main() {}
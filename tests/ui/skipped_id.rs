@@ -0,0 +1,9 @@
+use swivel::Record;
+
+#[derive(Record)]
+struct Task {
+    #[swivel(id, skip)]
+    id: String,
+}
+
+fn main() {}
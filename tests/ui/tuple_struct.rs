@@ -0,0 +1,6 @@
+use swivel::Record;
+
+#[derive(Record)]
+struct Task(String);
+
+fn main() {}
@@ -0,0 +1,11 @@
+use swivel::Record;
+
+#[derive(Record)]
+struct Task {
+    #[swivel(id)]
+    id: String,
+    #[swivel(notoin = "Name")]
+    title: String,
+}
+
+fn main() {}